@@ -0,0 +1,631 @@
+//! 平面剖分（arrangement）：由点交结果构建双向连接边表（DCEL），并枚举面及其边界环。
+//!
+//! 约定：
+//! - 输入线段之间不得有共线重叠（重叠请先用 `crate::atomic` 切成原子子段，见 `build_arrangement_from_segments`）；
+//! - 每条线段按 `crate::noding` 在其作为 `interior_segments` 参与的点组处切开，每个子段为一条边，边的两条半边互为 twin；
+//! - 顶点坐标为 `PointRat`（端点与交点），按坐标升序编号；
+//! - 半边的左侧为其所属的面：有界面的外边界逆时针，洞（内边界）与无界面的边界顺时针；
+//! - `FaceId(0)` 恒为无界面。
+//!
+//! 说明：边的方向总是沿原线段（整数端点），因此围绕顶点的角度排序只需整数叉积，精确且不溢出；
+//! 洞所在的面由连通分量最左下顶点向下的竖直射线确定（全部分量共用一次扫描，`O((V + E) log E)`）。
+
+use core::cmp::Ordering;
+use core::fmt;
+
+use crate::atomic::{AtomicSegments, decompose_atomic_segments};
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::point::PointRat;
+use crate::geom::segment::{SegmentId, Segments};
+use crate::limits::Limits;
+use crate::noding::node_segments;
+use crate::rational::Rational;
+use crate::sweep::bo::{BoError, enumerate_point_intersections_with_limits};
+use crate::sweep::segment_order::y_at_x;
+use crate::sweep::status::{SweepStatus, TreapSweepStatus};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VertexId(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HalfEdgeId(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FaceId(pub usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vertex {
+    pub point: PointRat,
+    /// 以该顶点为起点的半边，按方向角逆时针排序（从 +x 方向开始）。
+    pub outgoing: Vec<HalfEdgeId>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalfEdge {
+    pub origin: VertexId,
+    pub twin: HalfEdgeId,
+    pub next: HalfEdgeId,
+    pub prev: HalfEdgeId,
+    /// 半边左侧的面。
+    pub face: FaceId,
+    /// 该边所在的输入线段。
+    pub segment: SegmentId,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Face {
+    /// 外边界环上的任一半边（无界面为 `None`）。
+    pub outer: Option<HalfEdgeId>,
+    /// 各个洞（内边界环）上的任一半边，按洞的最左下顶点升序。
+    pub holes: Vec<HalfEdgeId>,
+}
+
+/// 平面剖分的 DCEL。
+///
+/// 说明：第 `k` 条边对应半边 `2k`（沿线段 `a -> b` 方向）与 `2k+1`（反向）。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Arrangement {
+    pub vertices: Vec<Vertex>,
+    pub half_edges: Vec<HalfEdge>,
+    pub faces: Vec<Face>,
+}
+
+impl Arrangement {
+    pub const UNBOUNDED_FACE: FaceId = FaceId(0);
+
+    pub fn vertex(&self, id: VertexId) -> &Vertex {
+        &self.vertices[id.0]
+    }
+
+    pub fn half_edge(&self, id: HalfEdgeId) -> &HalfEdge {
+        &self.half_edges[id.0]
+    }
+
+    pub fn face(&self, id: FaceId) -> &Face {
+        &self.faces[id.0]
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.half_edges.len() / 2
+    }
+
+    /// 半边的终点（即 twin 的起点）。
+    pub fn target(&self, id: HalfEdgeId) -> VertexId {
+        self.half_edge(self.half_edge(id).twin).origin
+    }
+
+    /// 从 `start` 沿 `next` 走一圈得到的边界环（含 `start`）。
+    pub fn cycle(&self, start: HalfEdgeId) -> Vec<HalfEdgeId> {
+        let mut out = vec![start];
+        let mut cur = self.half_edge(start).next;
+        while cur != start {
+            out.push(cur);
+            cur = self.half_edge(cur).next;
+        }
+        out
+    }
+
+    /// 边界环上依次经过的顶点坐标（环的起点不重复）。
+    pub fn cycle_points(&self, start: HalfEdgeId) -> Vec<PointRat> {
+        self.cycle(start)
+            .into_iter()
+            .map(|h| self.vertex(self.half_edge(h).origin).point)
+            .collect()
+    }
+
+    /// 面的全部边界环：外边界在前（若有），其后为各个洞。
+    pub fn face_boundaries(&self, face: FaceId) -> Vec<Vec<HalfEdgeId>> {
+        let f = self.face(face);
+        f.outer
+            .iter()
+            .chain(&f.holes)
+            .map(|&h| self.cycle(h))
+            .collect()
+    }
+
+    /// 按坐标查找顶点。
+    pub fn find_vertex(&self, point: PointRat) -> Option<VertexId> {
+        self.vertices
+            .binary_search_by(|v| v.point.cmp(&point))
+            .ok()
+            .map(VertexId)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArrangementError {
+    Sweep(BoError),
+    /// 两条线段在同一顶点沿同一方向离开（共线重叠）；应先切分为原子子段。
+    CollinearOverlap {
+        a: SegmentId,
+        b: SegmentId,
+    },
+}
+
+impl From<BoError> for ArrangementError {
+    fn from(value: BoError) -> Self {
+        Self::Sweep(value)
+    }
+}
+
+impl fmt::Display for ArrangementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrangementError::Sweep(e) => write!(f, "{}", e),
+            ArrangementError::CollinearOverlap { a, b } => write!(
+                f,
+                "线段 {} 与 {} 共线重叠，无法直接构建平面剖分（请先切分为原子子段）",
+                a.0, b.0
+            ),
+        }
+    }
+}
+
+/// 由线段集合与其点交结果构建平面剖分。
+///
+/// 前提：`records` 是 `segments` 的完整点交结果（`sweep::bo::enumerate_point_intersections` 的输出），
+/// 且线段之间没有共线重叠；点交缺失会得到错误的拓扑（不做检测）。
+pub fn build_arrangement(
+    segments: &Segments,
+    records: &[PointIntersectionGroupRecord],
+) -> Result<Arrangement, ArrangementError> {
+    // 1) 每条线段的切分点（沿线段方向即坐标升序）。
+    let noded = node_segments(segments, records);
+    let mut points: Vec<PointRat> = noded
+        .iter()
+        .flat_map(|n| n.split_points.iter().copied())
+        .collect();
+    points.sort();
+    points.dedup();
+    let vertex_of = |p: &PointRat| VertexId(points.binary_search(p).expect("切分点应已登记为顶点"));
+
+    // 2) 边与半边（`next`/`face` 稍后填写）。
+    let mut half_edges: Vec<HalfEdge> = Vec::new();
+    let mut directions: Vec<(i128, i128)> = Vec::new();
+    for n in &noded {
+        let seg = segments.get(n.segment);
+        let d = (
+            seg.b.x as i128 - seg.a.x as i128,
+            seg.b.y as i128 - seg.a.y as i128,
+        );
+        for piece in n.pieces() {
+            let k = half_edges.len();
+            for (origin, dir) in [(piece.a, d), (piece.b, (-d.0, -d.1))] {
+                half_edges.push(HalfEdge {
+                    origin: vertex_of(&origin),
+                    twin: HalfEdgeId(half_edges.len() ^ 1),
+                    next: HalfEdgeId(k),
+                    prev: HalfEdgeId(k),
+                    face: Arrangement::UNBOUNDED_FACE,
+                    segment: n.segment,
+                });
+                directions.push(dir);
+            }
+        }
+    }
+
+    // 3) 每个顶点的出边按角度排序；同方向的两条出边即共线重叠。
+    let mut vertices: Vec<Vertex> = points
+        .iter()
+        .map(|&point| Vertex {
+            point,
+            outgoing: Vec::new(),
+        })
+        .collect();
+    for (i, h) in half_edges.iter().enumerate() {
+        vertices[h.origin.0].outgoing.push(HalfEdgeId(i));
+    }
+    for v in &mut vertices {
+        v.outgoing
+            .sort_by(|a, b| cmp_direction(directions[a.0], directions[b.0]));
+        for w in v.outgoing.windows(2) {
+            if cmp_direction(directions[w[0].0], directions[w[1].0]) == Ordering::Equal {
+                let (a, b) = (half_edges[w[0].0].segment, half_edges[w[1].0].segment);
+                return Err(ArrangementError::CollinearOverlap {
+                    a: a.min(b),
+                    b: a.max(b),
+                });
+            }
+        }
+    }
+
+    // 4) next：到达顶点后，取 twin 方向顺时针方向上的第一条出边（保持面在左侧）。
+    for i in 0..half_edges.len() {
+        let twin = half_edges[i].twin;
+        let at = &vertices[half_edges[twin.0].origin.0];
+        let next = clockwise_from(&at.outgoing, &directions, directions[twin.0]);
+        half_edges[i].next = next;
+        half_edges[next.0].prev = HalfEdgeId(i);
+    }
+
+    let mut arrangement = Arrangement {
+        vertices,
+        half_edges,
+        faces: vec![Face::default()],
+    };
+    assign_faces(&mut arrangement, segments, &directions);
+    Ok(arrangement)
+}
+
+/// 由预处理后的线段（允许共线重叠）直接构建平面剖分。
+///
+/// 说明：先切分为原子子段再扫描，返回的 `HalfEdge.segment` 为原子子段下标（即 `AtomicSegmentId`），
+/// 可通过 `AtomicSegments::provenance` 溯源到原始 `SegmentId`。
+pub fn build_arrangement_from_segments(
+    segments: &Segments,
+    limits: Limits,
+) -> Result<(AtomicSegments, Arrangement), ArrangementError> {
+    let atomic = decompose_atomic_segments(segments);
+    let records = enumerate_point_intersections_with_limits(&atomic.segments, limits)?;
+    let arrangement = build_arrangement(&atomic.segments, &records)?;
+    Ok((atomic, arrangement))
+}
+
+/// 枚举边界环并分配面：逆时针环各成一个有界面；每个连通分量的最外环是某个面的洞。
+fn assign_faces(arr: &mut Arrangement, segments: &Segments, directions: &[(i128, i128)]) {
+    let n = arr.half_edges.len();
+
+    // 连通分量（顶点下标最小者即最左下顶点，因为顶点按坐标升序编号）。
+    let mut component = vec![usize::MAX; arr.vertices.len()];
+    let mut component_min: Vec<VertexId> = Vec::new();
+    for start in 0..arr.vertices.len() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        let c = component_min.len();
+        component_min.push(VertexId(start));
+        let mut stack = vec![start];
+        component[start] = c;
+        while let Some(v) = stack.pop() {
+            for h in &arr.vertices[v].outgoing {
+                let w = arr.target(*h).0;
+                if component[w] == usize::MAX {
+                    component[w] = c;
+                    stack.push(w);
+                }
+            }
+        }
+    }
+
+    // 最外环：最左下顶点处，紧邻 -x 方向顺时针的出边（所有出边都指向右半平面或正上方）。
+    let mut cycle_of = vec![usize::MAX; n];
+    let mut cycles: Vec<HalfEdgeId> = Vec::new();
+    let mut outer_cycles: Vec<usize> = Vec::new();
+    for &v in &component_min {
+        let start = clockwise_from(&arr.vertices[v.0].outgoing, directions, (-1, 0));
+        let c = mark_cycle(arr, start, cycles.len(), &mut cycle_of);
+        cycles.push(start);
+        outer_cycles.push(c);
+    }
+
+    // 其余环都是有界面的外边界。
+    let mut face_of_cycle: Vec<Option<FaceId>> = vec![None; cycles.len()];
+    for i in 0..n {
+        if cycle_of[i] != usize::MAX {
+            continue;
+        }
+        mark_cycle(arr, HalfEdgeId(i), cycles.len(), &mut cycle_of);
+        cycles.push(HalfEdgeId(i));
+        face_of_cycle.push(Some(FaceId(arr.faces.len())));
+        arr.faces.push(Face {
+            outer: Some(HalfEdgeId(i)),
+            holes: Vec::new(),
+        });
+    }
+
+    // 洞按最左下顶点升序定位：射线命中的环的最左下顶点更小，其面已确定。
+    let below = locate_below(arr, segments, directions, &component_min);
+    for (c, hit) in outer_cycles.into_iter().zip(below) {
+        let face = match hit {
+            None => Arrangement::UNBOUNDED_FACE,
+            Some(h) => face_of_cycle[cycle_of[h.0]].expect("射线命中的环应已分配面"),
+        };
+        face_of_cycle[c] = Some(face);
+        arr.faces[face.0].holes.push(cycles[c]);
+    }
+
+    for i in 0..n {
+        arr.half_edges[i].face = face_of_cycle[cycle_of[i]].expect("每个环都应分配面");
+    }
+}
+
+fn mark_cycle(arr: &Arrangement, start: HalfEdgeId, c: usize, cycle_of: &mut [usize]) -> usize {
+    for h in arr.cycle(start) {
+        cycle_of[h.0] = c;
+    }
+    c
+}
+
+/// 从每个连通分量的最左下顶点（`component_min`，升序）向下发射竖直射线，返回命中点处朝上方向左侧的半边
+/// （其所在的面即包含该分量的面）；未命中任何其他分量时为 `None`（无界面）。
+///
+/// 说明：按顶点顺序扫描一遍，状态结构中为当前 x 处的非竖直线段（在经过的顶点处按 `x+ε` 重排）。
+/// 最左下顶点正下方不会有同一分量的边；命中点要么是同一 x 上紧邻的更低顶点
+/// （竖直边的上端点、更低的顶点都不会比它更近），要么是状态结构中正下方线段的某条边的内部。
+fn locate_below(
+    arr: &Arrangement,
+    segments: &Segments,
+    directions: &[(i128, i128)],
+    component_min: &[VertexId],
+) -> Vec<Option<HalfEdgeId>> {
+    // 每条线段的边（沿线段方向），以及以各顶点为起点的非竖直线段。
+    let mut edges_of: Vec<Vec<usize>> = vec![Vec::new(); segments.len()];
+    for k in 0..arr.edge_count() {
+        edges_of[arr.half_edges[2 * k].segment.0].push(k);
+    }
+    let mut starts: Vec<Vec<SegmentId>> = vec![Vec::new(); arr.vertices.len()];
+    for (i, edges) in edges_of.iter().enumerate() {
+        if let Some(&k) = edges.first()
+            && !segments.get(SegmentId(i)).is_vertical()
+        {
+            starts[arr.half_edges[2 * k].origin.0].push(SegmentId(i));
+        }
+    }
+
+    let mut status = TreapSweepStatus::new(Rational::from_int(0));
+    let mut queries = component_min.iter().peekable();
+    let mut hits = Vec::with_capacity(component_min.len());
+    for (i, vertex) in arr.vertices.iter().enumerate() {
+        let p = vertex.point;
+        status.set_sweep_x(p.x);
+
+        if queries.next_if(|v| v.0 == i).is_some() {
+            let lower_vertex = i.checked_sub(1).filter(|&u| arr.vertices[u].point.x == p.x);
+            let lower_edge = status
+                .last_below_y(segments, p.y)
+                .expect("顶点处的比较与扫描线相同，不会溢出")
+                .map(|s| {
+                    let y = y_at_x(segments.get(s), p.x).expect("顶点处的 y_at_x 不会溢出");
+                    (y, s)
+                });
+            hits.push(match (lower_vertex, lower_edge) {
+                (u, Some((y, s))) if u.is_none_or(|u| arr.vertices[u].point.y < y) => {
+                    // 命中点不是顶点：落在线段 `s` 上跨过 x 的那条边的内部；半边 `2k` 沿线段方向（x 增大），其左侧朝上。
+                    let edges = &edges_of[s.0];
+                    let j = edges.partition_point(|&k| {
+                        arr.vertex(arr.target(HalfEdgeId(2 * k))).point.x <= p.x
+                    });
+                    Some(HalfEdgeId(2 * edges[j]))
+                }
+                (Some(u), _) => Some(clockwise_from(
+                    &arr.vertices[u].outgoing,
+                    directions,
+                    (0, 1),
+                )),
+                (None, _) => None,
+            });
+        }
+
+        // 经过 p 的线段先删除，再与从 p 开始的线段一起按 `x+ε` 插入（在 p 结束的不再插入）。
+        let through = status
+            .range_by_y(segments, p.y, p.y)
+            .expect("顶点处的比较与扫描线相同，不会溢出");
+        for &s in &through {
+            status.remove(s).expect("经过顶点的线段应在状态结构中");
+        }
+        for &s in through
+            .iter()
+            .filter(|&&s| PointRat::from_i64(segments.get(s).b) != p)
+            .chain(&starts[i])
+        {
+            status
+                .insert(segments, s)
+                .expect("顶点处的比较与扫描线相同，不会溢出");
+        }
+    }
+    hits
+}
+
+/// 出边列表（逆时针有序）中，严格位于方向 `d` 顺时针一侧的第一条出边（循环）。
+fn clockwise_from(
+    outgoing: &[HalfEdgeId],
+    directions: &[(i128, i128)],
+    d: (i128, i128),
+) -> HalfEdgeId {
+    let below = outgoing.partition_point(|h| cmp_direction(directions[h.0], d) == Ordering::Less);
+    if below == 0 {
+        *outgoing.last().expect("顶点至少有一条出边")
+    } else {
+        outgoing[below - 1]
+    }
+}
+
+/// 按方向角（`[0, 2π)`，从 +x 逆时针）比较两个非零向量。
+fn cmp_direction(a: (i128, i128), b: (i128, i128)) -> Ordering {
+    let half = |d: (i128, i128)| !(d.1 > 0 || (d.1 == 0 && d.0 > 0));
+    half(a)
+        .cmp(&half(b))
+        .then_with(|| 0.cmp(&(a.0 * b.1 - a.1 * b.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::segment::segments_from;
+    use crate::sweep::bo::enumerate_point_intersections;
+
+    fn square(x: i64, y: i64, size: i64) -> Vec<(i64, i64, i64, i64)> {
+        vec![
+            (x, y, x + size, y),
+            (x + size, y, x + size, y + size),
+            (x + size, y + size, x, y + size),
+            (x, y + size, x, y),
+        ]
+    }
+
+    fn build(coords: &[(i64, i64, i64, i64)]) -> Arrangement {
+        let segments = segments_from(coords);
+        let records = enumerate_point_intersections(&segments).unwrap();
+        build_arrangement(&segments, &records).unwrap()
+    }
+
+    /// DCEL 的基本不变量，以及欧拉公式 `V - E + F = 1 + C`。
+    fn check_invariants(arr: &Arrangement, components: usize) {
+        for (i, h) in arr.half_edges.iter().enumerate() {
+            let id = HalfEdgeId(i);
+            assert_eq!(arr.half_edge(h.twin).twin, id);
+            assert_eq!(arr.half_edge(h.next).prev, id);
+            assert_eq!(arr.half_edge(h.next).origin, arr.target(id));
+            assert_eq!(arr.half_edge(h.next).face, h.face);
+        }
+        let boundary_edges: usize = (0..arr.faces.len())
+            .map(|f| {
+                arr.face_boundaries(FaceId(f))
+                    .iter()
+                    .map(Vec::len)
+                    .sum::<usize>()
+            })
+            .sum();
+        assert_eq!(boundary_edges, arr.half_edges.len());
+        assert_eq!(
+            arr.vertices.len() + arr.faces.len(),
+            arr.edge_count() + 1 + components
+        );
+    }
+
+    fn at(x: i128, y: i128) -> PointRat {
+        PointRat {
+            x: Rational::from_int(x),
+            y: Rational::from_int(y),
+        }
+    }
+
+    #[test]
+    fn square_with_crossing_diagonals_has_four_triangles() {
+        let mut coords = square(0, 0, 2);
+        coords.extend([(0, 0, 2, 2), (0, 2, 2, 0)]);
+        let arr = build(&coords);
+        check_invariants(&arr, 1);
+
+        assert_eq!(arr.vertices.len(), 5);
+        assert_eq!(arr.edge_count(), 8);
+        assert_eq!(arr.faces.len(), 5);
+        let centre = arr.find_vertex(at(1, 1)).unwrap();
+        assert_eq!(arr.vertex(centre).outgoing.len(), 4);
+
+        // 无界面只有一个洞：正方形外轮廓（顺时针）。
+        let unbounded = arr.face(Arrangement::UNBOUNDED_FACE);
+        assert_eq!(unbounded.outer, None);
+        assert_eq!(unbounded.holes.len(), 1);
+        assert_eq!(
+            arr.cycle_points(unbounded.holes[0]),
+            vec![at(0, 0), at(0, 2), at(2, 2), at(2, 0)]
+        );
+        for f in 1..arr.faces.len() {
+            let face = arr.face(FaceId(f));
+            assert!(face.holes.is_empty());
+            let points = arr.cycle_points(face.outer.unwrap());
+            assert_eq!(points.len(), 3);
+            assert!(points.contains(&at(1, 1)));
+        }
+    }
+
+    #[test]
+    fn nested_and_dangling_components_become_holes_of_the_right_faces() {
+        let mut coords = square(0, 0, 10);
+        // 内部正方形（在大正方形内部形成洞），其右侧挂一条悬边。
+        coords.extend(square(2, 2, 2));
+        coords.push((4, 3, 6, 3));
+        // 内部的一条孤立线段，位于内部正方形正上方（射线先命中内部正方形的顶边）。
+        coords.push((3, 6, 3, 8));
+        // 外部的另一个正方形。
+        coords.extend(square(20, 0, 1));
+        let arr = build(&coords);
+        check_invariants(&arr, 4);
+
+        // 面：无界面、大正方形内部、内部正方形内部、外部正方形内部。
+        assert_eq!(arr.faces.len(), 4);
+        let face_of_outer = |x: i128, y: i128| {
+            (1..arr.faces.len())
+                .map(FaceId)
+                .find(|f| {
+                    arr.cycle_points(arr.face(*f).outer.unwrap())
+                        .contains(&at(x, y))
+                })
+                .unwrap()
+        };
+        let big = face_of_outer(0, 10);
+        let small = face_of_outer(2, 4);
+
+        let unbounded = arr.face(Arrangement::UNBOUNDED_FACE);
+        assert_eq!(unbounded.holes.len(), 2);
+        let big_face = arr.face(big);
+        assert_eq!(big_face.holes.len(), 2);
+        let hole_points: Vec<Vec<PointRat>> = big_face
+            .holes
+            .iter()
+            .map(|&h| arr.cycle_points(h))
+            .collect();
+        assert!(hole_points[0].contains(&at(6, 3)));
+        assert_eq!(hole_points[1], vec![at(3, 6), at(3, 8)]);
+        assert!(arr.face(small).holes.is_empty());
+
+        // 悬边两侧的半边属于同一个面。
+        let dangling = arr.find_vertex(at(6, 3)).unwrap();
+        let out = arr.vertex(dangling).outgoing[0];
+        assert_eq!(arr.half_edge(out).face, big);
+        assert_eq!(arr.half_edge(arr.half_edge(out).twin).face, big);
+    }
+
+    #[test]
+    fn rays_hitting_vertices_pick_the_face_around_the_vertex() {
+        let mut coords = square(0, 0, 10);
+        // 菱形：顶点 (5,2)/(7,4)/(5,6)/(3,4)。
+        coords.extend([(5, 2, 7, 4), (7, 4, 5, 6), (5, 6, 3, 4), (3, 4, 5, 2)]);
+        // 射线分别从菱形外、菱形内命中其顶点 (5,6) 与 (5,2)。
+        coords.push((5, 7, 6, 8));
+        coords.push((5, 3, 6, 4));
+        // 竖直线段叠放：上方线段的射线命中下方线段的上端点。
+        coords.push((8, 1, 8, 2));
+        coords.push((8, 3, 8, 5));
+        let arr = build(&coords);
+        check_invariants(&arr, 6);
+
+        let face_with_outer = |x: i128, y: i128| {
+            (1..arr.faces.len())
+                .map(FaceId)
+                .find(|f| {
+                    arr.cycle_points(arr.face(*f).outer.unwrap())
+                        .contains(&at(x, y))
+                })
+                .unwrap()
+        };
+        let face_at = |x: i128, y: i128| {
+            let v = arr.find_vertex(at(x, y)).unwrap();
+            arr.half_edge(arr.vertex(v).outgoing[0]).face
+        };
+        let big = face_with_outer(0, 10);
+        let diamond = face_with_outer(5, 2);
+        assert_eq!(face_at(5, 7), big);
+        assert_eq!(face_at(8, 1), big);
+        assert_eq!(face_at(8, 3), big);
+        assert_eq!(face_at(5, 3), diamond);
+        assert_eq!(arr.face(big).holes.len(), 4);
+        assert_eq!(arr.face(diamond).holes.len(), 1);
+    }
+
+    #[test]
+    fn overlapping_input_requires_atomic_decomposition() {
+        let coords = [(0, 0, 10, 0), (5, 0, 15, 0), (7, -5, 7, 5)];
+        let segments = segments_from(&coords);
+        let records = enumerate_point_intersections(&segments).unwrap();
+        assert_eq!(
+            build_arrangement(&segments, &records),
+            Err(ArrangementError::CollinearOverlap {
+                a: SegmentId(0),
+                b: SegmentId(1),
+            })
+        );
+
+        let (atomic, arr) = build_arrangement_from_segments(&segments, Limits::default()).unwrap();
+        check_invariants(&arr, 1);
+        // 顶点：0、5、7（竖线交点）、10、15 以及竖线两端。
+        assert_eq!(arr.vertices.len(), 7);
+        assert_eq!(arr.edge_count(), 6);
+        assert_eq!(arr.faces.len(), 1);
+        let shared = arr.find_vertex(at(5, 0)).unwrap();
+        let to_right = arr.vertex(shared).outgoing[0];
+        let piece = crate::atomic::AtomicSegmentId(arr.half_edge(to_right).segment.0);
+        assert_eq!(atomic.provenance(piece), &[SegmentId(0), SegmentId(1)]);
+    }
+}
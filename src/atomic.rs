@@ -0,0 +1,280 @@
+//! Phase 2：原子子段替换（见 `plans/phase2-precheck.md` 的 6)）。
+//!
+//! 约定：
+//! - 把每组共线线段在所有端点处切开，得到互不重叠的“原子子段”（两两之间至多共享端点）；
+//! - 不属于任何重叠组的线段原样保留为一条原子子段；
+//! - 原子子段使用独立的 `AtomicSegmentId`（不复用 `SegmentId`），并提供 `atomic_segment_id -> [SegmentId...]` 溯源；
+//! - 点交扫描在原子子段集合上重新运行，交点记录引用 `AtomicSegmentId`（方案 B）。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::geom::fixed::PointI64;
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::point::PointRat;
+use crate::geom::predicates::orient;
+use crate::geom::segment::{Segment, SegmentId, Segments};
+use crate::overlap::{collinear_groups, group_breakpoints};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AtomicSegmentId(pub usize);
+
+/// 原子子段集合。
+///
+/// 说明：
+/// - `segments` 的下标与 `AtomicSegmentId` 一一对应（`SegmentId(i)` 即 `AtomicSegmentId(i)`），便于直接喂给扫描线；
+/// - 原子子段按 `(a,b)` 字典序升序编号，保证确定性与稳定排序；
+/// - `Segment.source_index` 取溯源集合中最小 `SegmentId` 的输入下标。
+#[derive(Clone, Debug, Default)]
+pub struct AtomicSegments {
+    pub segments: Segments,
+    provenance: Vec<Vec<SegmentId>>,
+}
+
+impl AtomicSegments {
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn get(&self, id: AtomicSegmentId) -> &Segment {
+        self.segments.get(SegmentId(id.0))
+    }
+
+    /// 覆盖该原子子段的预处理后线段集合（去重、升序）。
+    pub fn provenance(&self, id: AtomicSegmentId) -> &[SegmentId] {
+        &self.provenance[id.0]
+    }
+
+    /// 该原子子段的覆盖次数 `c(t)`（子段内部恒定；不属于重叠组的子段恒为 1）。
+    pub fn coverage(&self, id: AtomicSegmentId) -> usize {
+        self.provenance[id.0].len()
+    }
+
+    /// 覆盖次数恰为 `coverage` 的原子子段（按 `AtomicSegmentId` 升序）。
+    pub fn with_coverage(&self, coverage: usize) -> Vec<AtomicSegmentId> {
+        (0..self.len())
+            .map(AtomicSegmentId)
+            .filter(|id| self.coverage(*id) == coverage)
+            .collect()
+    }
+
+    /// 所有原子子段中的最大覆盖次数（空集合为 0）。
+    pub fn max_coverage(&self) -> usize {
+        self.provenance.iter().map(Vec::len).max().unwrap_or(0)
+    }
+}
+
+/// 按点聚合后的交点记录（引用原子子段）。
+///
+/// 字段语义与 `PointIntersectionGroupRecord` 相同，只是 ID 空间为 `AtomicSegmentId`。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AtomicIntersectionGroupRecord {
+    pub point: PointRat,
+    pub endpoint_segments: Vec<AtomicSegmentId>,
+    pub interior_segments: Vec<AtomicSegmentId>,
+}
+
+/// 把预处理后的线段集合切分为原子子段。
+pub fn decompose_atomic_segments(segments: &Segments) -> AtomicSegments {
+    let mut pieces: Vec<(Segment, Vec<SegmentId>)> = Vec::with_capacity(segments.len());
+    let mut grouped: BTreeSet<SegmentId> = BTreeSet::new();
+
+    for group in collinear_groups(segments) {
+        grouped.extend(group.segments.iter().copied());
+
+        // 沿 1D 投影扫过所有断点：每个覆盖≥1 的开区间对应一条原子子段，溯源为当前覆盖它的线段集合。
+        let breakpoints = group_breakpoints(segments, &group);
+        let mut active: BTreeSet<SegmentId> = BTreeSet::new();
+        let mut prev = None;
+        for bp in breakpoints.values() {
+            let point = bp.point.expect("断点应来自线段端点");
+            if let Some(start) = prev
+                && !active.is_empty()
+            {
+                pieces.push((
+                    piece_segment(segments, start, point, &active),
+                    active.iter().copied().collect(),
+                ));
+            }
+            for id in &bp.ends {
+                active.remove(id);
+            }
+            active.extend(bp.starts.iter().copied());
+            prev = Some(point);
+        }
+        debug_assert!(active.is_empty(), "共线组扫描结束时不应仍有活动线段");
+    }
+
+    for (i, seg) in segments.iter().enumerate() {
+        let id = SegmentId(i);
+        if !grouped.contains(&id) {
+            pieces.push((*seg, vec![id]));
+        }
+    }
+
+    pieces.sort_by_key(|(seg, _)| (seg.a, seg.b));
+
+    let mut out = AtomicSegments::default();
+    for (seg, prov) in pieces {
+        out.segments.push(seg);
+        out.provenance.push(prov);
+    }
+    out
+}
+
+fn piece_segment(
+    segments: &Segments,
+    a: PointI64,
+    b: PointI64,
+    covering: &BTreeSet<SegmentId>,
+) -> Segment {
+    let first = *covering.first().expect("覆盖集合不应为空");
+    Segment {
+        a,
+        b,
+        source_index: segments.get(first).source_index,
+    }
+}
+
+/// 把原子子段上的点交结果转换为 `AtomicSegmentId` 记录。
+///
+/// 说明：若某点的参与者全部位于同一条支撑直线上（切分点、重叠段边界、共线首尾相接），
+/// 该接触已由 `crate::overlap` 的重叠段/重叠点完整表达，这里不再作为点交输出。
+pub fn atomic_intersections_from_records(
+    atomic: &AtomicSegments,
+    records: Vec<PointIntersectionGroupRecord>,
+) -> Vec<AtomicIntersectionGroupRecord> {
+    records
+        .into_iter()
+        .filter(|r| !is_collinear_only(atomic, r))
+        .map(|r| AtomicIntersectionGroupRecord {
+            point: r.point,
+            endpoint_segments: r
+                .endpoint_segments
+                .iter()
+                .map(|id| AtomicSegmentId(id.0))
+                .collect(),
+            interior_segments: r
+                .interior_segments
+                .iter()
+                .map(|id| AtomicSegmentId(id.0))
+                .collect(),
+        })
+        .collect()
+}
+
+fn is_collinear_only(atomic: &AtomicSegments, record: &PointIntersectionGroupRecord) -> bool {
+    let mut ids = record
+        .endpoint_segments
+        .iter()
+        .chain(&record.interior_segments)
+        .map(|id| atomic.segments.get(*id));
+    let Some(first) = ids.next() else {
+        return true;
+    };
+    ids.all(|s| orient(first.a, first.b, s.a) == 0 && orient(first.a, first.b, s.b) == 0)
+}
+
+/// 汇总每个原子子段被哪些预处理后线段覆盖（`SegmentId -> [AtomicSegmentId...]` 的反向索引）。
+pub fn atomic_segments_by_source(
+    atomic: &AtomicSegments,
+) -> BTreeMap<SegmentId, Vec<AtomicSegmentId>> {
+    let mut out: BTreeMap<SegmentId, Vec<AtomicSegmentId>> = BTreeMap::new();
+    for (i, prov) in atomic.provenance.iter().enumerate() {
+        for &id in prov {
+            out.entry(id).or_default().push(AtomicSegmentId(i));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rational::Rational;
+    use crate::sweep::bo::enumerate_point_intersections;
+
+    fn push(segments: &mut Segments, ax: i64, ay: i64, bx: i64, by: i64) -> SegmentId {
+        let (a, b) = (PointI64 { x: ax, y: ay }, PointI64 { x: bx, y: by });
+        let (a, b) = if b < a { (b, a) } else { (a, b) };
+        let source_index = segments.len();
+        segments.push(Segment { a, b, source_index })
+    }
+
+    #[test]
+    fn splits_overlap_group_into_non_overlapping_pieces_with_provenance() {
+        let mut segments = Segments::new();
+        let long = push(&mut segments, 0, 0, 30, 0);
+        let short = push(&mut segments, 5, 0, 10, 0);
+        let other = push(&mut segments, 0, 5, 5, 10);
+
+        let atomic = decompose_atomic_segments(&segments);
+        let pieces: Vec<(PointI64, PointI64, Vec<SegmentId>)> = (0..atomic.len())
+            .map(|i| {
+                let id = AtomicSegmentId(i);
+                let s = atomic.get(id);
+                (s.a, s.b, atomic.provenance(id).to_vec())
+            })
+            .collect();
+        assert_eq!(
+            pieces,
+            vec![
+                (PointI64 { x: 0, y: 0 }, PointI64 { x: 5, y: 0 }, vec![long]),
+                (
+                    PointI64 { x: 0, y: 5 },
+                    PointI64 { x: 5, y: 10 },
+                    vec![other]
+                ),
+                (
+                    PointI64 { x: 5, y: 0 },
+                    PointI64 { x: 10, y: 0 },
+                    vec![long, short]
+                ),
+                (
+                    PointI64 { x: 10, y: 0 },
+                    PointI64 { x: 30, y: 0 },
+                    vec![long]
+                ),
+            ]
+        );
+
+        let by_source = atomic_segments_by_source(&atomic);
+        assert_eq!(
+            by_source[&long],
+            vec![AtomicSegmentId(0), AtomicSegmentId(2), AtomicSegmentId(3)]
+        );
+        assert_eq!(by_source[&short], vec![AtomicSegmentId(2)]);
+
+        assert_eq!(atomic.coverage(AtomicSegmentId(2)), 2);
+        assert_eq!(atomic.max_coverage(), 2);
+        assert_eq!(atomic.with_coverage(2), vec![AtomicSegmentId(2)]);
+    }
+
+    #[test]
+    fn drops_collinear_only_contacts_and_keeps_crossings() {
+        let mut segments = Segments::new();
+        push(&mut segments, 0, 0, 30, 0);
+        push(&mut segments, 5, 0, 10, 0);
+        push(&mut segments, 7, -5, 7, 5);
+
+        let atomic = decompose_atomic_segments(&segments);
+        let raw = enumerate_point_intersections(&atomic.segments).unwrap();
+        let hits = atomic_intersections_from_records(&atomic, raw);
+
+        // 原子子段：[0,5] [5,10] [7,-5..5] [10,30]；只有竖线与 [5,10] 的交点是真正的点交。
+        assert_eq!(
+            hits,
+            vec![AtomicIntersectionGroupRecord {
+                point: PointRat {
+                    x: Rational::from_int(7),
+                    y: Rational::from_int(0),
+                },
+                endpoint_segments: vec![],
+                interior_segments: vec![AtomicSegmentId(1), AtomicSegmentId(2)],
+            }]
+        );
+    }
+}
@@ -0,0 +1,366 @@
+//! 最小无符号大整数（仅供 `crate::mapping` 生成精确十进制字符串使用）。
+//!
+//! 说明：
+//! - 只实现十进制展开所需的运算（加、减、乘、比较、左移、带余除法）；
+//! - 一般除法采用逐位二进制长除法（只用于整数部分，每次转换一次）；除以单段小整数（`div_small`）按段进行。
+
+use core::cmp::Ordering;
+
+/// 小端序 `u32` 分段；不保留高位的 0（0 表示为空向量）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub(crate) fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub(crate) fn from_u128(mut value: u128) -> Self {
+        let mut limbs = Vec::new();
+        while value != 0 {
+            limbs.push(value as u32);
+            value >>= 32;
+        }
+        Self { limbs }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    fn bit_len(&self) -> usize {
+        match self.limbs.last() {
+            Some(&top) => self.limbs.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        self.limbs
+            .get(i / 32)
+            .is_some_and(|limb| (limb >> (i % 32)) & 1 == 1)
+    }
+
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        let n = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(n + 1);
+        let mut carry = 0_u64;
+        for i in 0..n {
+            let a = self.limbs.get(i).copied().unwrap_or(0) as u64;
+            let b = other.limbs.get(i).copied().unwrap_or(0) as u64;
+            let sum = a + b + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry != 0 {
+            limbs.push(carry as u32);
+        }
+        Self { limbs }
+    }
+
+    /// `self - other`；调用方需保证 `self >= other`。
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        debug_assert!(*self >= *other, "BigUint 减法结果不能为负");
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0_i64;
+        for i in 0..self.limbs.len() {
+            let a = self.limbs[i] as i64;
+            let b = other.limbs.get(i).copied().unwrap_or(0) as i64;
+            let mut diff = a - b - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << 32;
+                borrow = 1;
+            }
+            limbs.push(diff as u32);
+        }
+        let mut out = Self { limbs };
+        out.trim();
+        out
+    }
+
+    pub(crate) fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero();
+        }
+        let mut limbs = vec![0_u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let cur = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = cur as u32;
+                carry = cur >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        let mut out = Self { limbs };
+        out.trim();
+        out
+    }
+
+    pub(crate) fn mul_small(&self, factor: u32) -> Self {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0_u64;
+        for &limb in &self.limbs {
+            let cur = limb as u64 * factor as u64 + carry;
+            limbs.push(cur as u32);
+            carry = cur >> 32;
+        }
+        limbs.push(carry as u32);
+        let mut out = Self { limbs };
+        out.trim();
+        out
+    }
+
+    /// 除以单段小整数 `(self / divisor, self % divisor)`，从高段到低段逐段进行；`divisor` 不能为 0。
+    pub(crate) fn div_small(&self, divisor: u32) -> (Self, u32) {
+        assert!(divisor != 0, "BigUint 除数不能为 0");
+        let mut limbs = vec![0_u32; self.limbs.len()];
+        let mut rem = 0_u64;
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let cur = (rem << 32) | limb as u64;
+            limbs[i] = (cur / divisor as u64) as u32;
+            rem = cur % divisor as u64;
+        }
+        let mut quotient = Self { limbs };
+        quotient.trim();
+        (quotient, rem as u32)
+    }
+
+    /// 商小于 `max_quotient` 时的带余除法：逐次减去 `divisor`（用于十进制展开中商只有一位的步骤）。
+    pub(crate) fn div_rem_small_quotient(&self, divisor: &Self, max_quotient: u32) -> (u32, Self) {
+        let mut rem = self.clone();
+        let mut quotient = 0;
+        while rem >= *divisor {
+            debug_assert!(quotient < max_quotient, "商超出预期范围");
+            rem = rem.sub(divisor);
+            quotient += 1;
+        }
+        (quotient, rem)
+    }
+
+    pub(crate) fn shl(&self, bits: usize) -> Self {
+        if self.is_zero() {
+            return Self::zero();
+        }
+        let (words, rem) = (bits / 32, bits % 32);
+        let mut limbs = vec![0_u32; words];
+        let mut carry = 0_u32;
+        for &limb in &self.limbs {
+            if rem == 0 {
+                limbs.push(limb);
+            } else {
+                limbs.push((limb << rem) | carry);
+                carry = limb >> (32 - rem);
+            }
+        }
+        if carry != 0 {
+            limbs.push(carry);
+        }
+        Self { limbs }
+    }
+
+    /// 带余除法 `(self / divisor, self % divisor)`；`divisor` 不能为 0。
+    pub(crate) fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "BigUint 除数不能为 0");
+        if *self < *divisor {
+            return (Self::zero(), self.clone());
+        }
+        let n = self.bit_len();
+        let mut quotient = vec![0_u32; n.div_ceil(32)];
+        let mut rem = Self::zero();
+        for i in (0..n).rev() {
+            rem = rem.shl(1);
+            if self.bit(i) {
+                match rem.limbs.first_mut() {
+                    Some(low) => *low |= 1,
+                    None => rem.limbs.push(1),
+                }
+            }
+            if rem >= *divisor {
+                rem = rem.sub(divisor);
+                quotient[i / 32] |= 1 << (i % 32);
+            }
+        }
+        let mut quotient = Self { limbs: quotient };
+        quotient.trim();
+        (quotient, rem)
+    }
+
+    /// 十进制文本（不含符号）。
+    pub(crate) fn to_decimal_string(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+        // 每次除以 10^9，按 9 位一组从低到高收集。
+        let mut groups: Vec<u32> = Vec::new();
+        let mut cur = self.clone();
+        while !cur.is_zero() {
+            let (q, r) = cur.div_small(1_000_000_000);
+            groups.push(r);
+            cur = q;
+        }
+        let mut out = groups.pop().unwrap_or(0).to_string();
+        for g in groups.iter().rev() {
+            out.push_str(&format!("{g:09}"));
+        }
+        out
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_matches_u128() {
+        let a = 0xDEAD_BEEF_1234_5678_9ABC_u128;
+        let b = 0x1_0000_0001_u128;
+        let (ba, bb) = (BigUint::from_u128(a), BigUint::from_u128(b));
+        assert_eq!(ba.add(&bb), BigUint::from_u128(a + b));
+        assert_eq!(ba.sub(&bb), BigUint::from_u128(a - b));
+        assert_eq!(
+            BigUint::from_u128(0xFFFF_FFFF).mul(&bb),
+            BigUint::from_u128(0xFFFF_FFFF * b)
+        );
+        assert_eq!(bb.shl(40), BigUint::from_u128(b << 40));
+        let (q, r) = ba.div_rem(&bb);
+        assert_eq!(
+            (q, r),
+            (BigUint::from_u128(a / b), BigUint::from_u128(a % b))
+        );
+        assert_eq!(ba.to_decimal_string(), a.to_string());
+        assert_eq!(BigUint::zero().to_decimal_string(), "0");
+    }
+
+    #[test]
+    fn handles_values_beyond_u128() {
+        // 2^200 = 1606938044258990275541962092341162602522202993782792835301376
+        let big = BigUint::from_u128(1).shl(200);
+        assert_eq!(
+            big.to_decimal_string(),
+            "1606938044258990275541962092341162602522202993782792835301376"
+        );
+        let (q, r) = big.div_rem(&BigUint::from_u128(1).shl(137));
+        assert_eq!(q, BigUint::from_u128(1 << 63));
+        assert!(r.is_zero());
+    }
+
+    #[test]
+    fn propagates_carries_and_borrows_across_limbs() {
+        let one = BigUint::from_u128(1);
+        let max96 = BigUint::from_u128((1 << 96) - 1);
+        let p96 = one.shl(96);
+        // 进位穿过全部分段并新增一段；借位反向穿过全部分段并去掉最高段。
+        assert_eq!(max96.add(&one), p96);
+        assert_eq!(p96.sub(&one), max96);
+        assert_eq!(p96.sub(&p96), BigUint::zero());
+        assert_eq!(BigUint::from_u128(u128::MAX).add(&one), one.shl(128),);
+        assert_eq!(one.shl(128).sub(&one), BigUint::from_u128(u128::MAX));
+
+        // (2^64 - 1)^2 = 2^128 - 2^65 + 1：乘法每一列都有进位。
+        let max64 = BigUint::from_u128(u64::MAX as u128);
+        assert_eq!(
+            max64.mul(&max64),
+            BigUint::from_u128((u64::MAX as u128) * (u64::MAX as u128))
+        );
+        assert_eq!(max64.mul(&BigUint::zero()), BigUint::zero());
+        assert_eq!(
+            BigUint::from_u128(u128::MAX).mul_small(10),
+            BigUint::from_u128(u128::MAX)
+                .shl(3)
+                .add(&BigUint::from_u128(u128::MAX).shl(1)),
+        );
+
+        // 左移：整段、跨段、移出最高位。
+        let v = BigUint::from_u128(0x8000_0001);
+        assert_eq!(v.shl(0), v);
+        assert_eq!(v.shl(32), BigUint::from_u128(0x8000_0001 << 32));
+        assert_eq!(v.shl(33), BigUint::from_u128(0x8000_0001 << 33));
+        assert_eq!(BigUint::zero().shl(1000), BigUint::zero());
+    }
+
+    #[test]
+    fn divides_and_prints_edge_values() {
+        let a = BigUint::from_u128(12345);
+        assert_eq!(a.div_rem(&a), (BigUint::from_u128(1), BigUint::zero()));
+        assert_eq!(
+            a.div_rem(&BigUint::from_u128(12346)),
+            (BigUint::zero(), a.clone())
+        );
+        assert_eq!(
+            BigUint::zero().div_rem(&a),
+            (BigUint::zero(), BigUint::zero())
+        );
+
+        // (2^300 + 7) / 2^150 = 2^150 余 7；除数与商都超出 u128。
+        let p150 = BigUint::from_u128(1).shl(150);
+        let n = p150.mul(&p150).add(&BigUint::from_u128(7));
+        assert_eq!(n.div_rem(&p150), (p150.clone(), BigUint::from_u128(7)));
+
+        // 十进制分组内部的 0 要补齐。
+        assert_eq!(
+            BigUint::from_u128(1_000_000_000_000_000_005).to_decimal_string(),
+            "1000000000000000005"
+        );
+        assert_eq!(
+            BigUint::from_u128(u128::MAX).to_decimal_string(),
+            u128::MAX.to_string()
+        );
+        // 2^1074（f64 最小次正规数的倒数）首尾数位。
+        let text = BigUint::from_u128(1).shl(1074).to_decimal_string();
+        assert_eq!(text.len(), 324);
+        assert!(text.starts_with("2024022533073106") && text.ends_with("1093494784"));
+
+        assert!(BigUint::from_u128(1 << 32) > BigUint::from_u128(u32::MAX as u128));
+        assert!(BigUint::from_u128(2 << 40) > BigUint::from_u128(3 << 39));
+    }
+
+    #[test]
+    fn divides_by_single_limbs_and_small_quotients() {
+        let a = u128::MAX - 12345;
+        for d in [1, 2, 10, 1_000_000_000, u32::MAX] {
+            let (q, r) = BigUint::from_u128(a).div_small(d);
+            assert_eq!(
+                (q, r),
+                (BigUint::from_u128(a / d as u128), (a % d as u128) as u32)
+            );
+        }
+        assert_eq!(BigUint::zero().div_small(7), (BigUint::zero(), 0));
+        let p200 = BigUint::from_u128(1).shl(200);
+        assert_eq!(
+            p200.mul_small(u32::MAX).div_small(u32::MAX),
+            (p200.clone(), 0)
+        );
+
+        // 9·d + (d - 1) 除以 d：商 9，余数跨越多段。
+        let d = p200.add(&BigUint::from_u128(u64::MAX as u128));
+        let rem = d.sub(&BigUint::from_u128(1));
+        let n = d.mul_small(9).add(&rem);
+        assert_eq!(n.div_rem_small_quotient(&d, 10), (9, rem.clone()));
+        assert_eq!(rem.div_rem_small_quotient(&d, 10), (0, rem.clone()));
+    }
+}
@@ -48,20 +48,15 @@ fn run(args: Args) -> Result<(), String> {
     fs::create_dir_all(&random_dir)
         .map_err(|e| format!("创建目录失败：{}（{}）", random_dir.display(), e))?;
 
-    let mut items: Vec<IndexItem> = Vec::new();
-
-    items.push(write_curated_basic_cross(&curated_dir)?);
-    items.push(write_curated_rational_intersection(&curated_dir)?);
-    items.push(write_curated_endpoint_touch(&curated_dir)?);
-    items.push(write_curated_preprocess_warnings(&curated_dir)?);
-
-    items.push(write_perf_grid_orthogonal(&perf_dir, PERF_GRID_N)?);
-    items.push(write_perf_grid_diagonal_45(&perf_dir, PERF_GRID_N)?);
-    items.push(write_perf_spider_web(
-        &perf_dir,
-        PERF_SPIDER_SPOKES,
-        PERF_SPIDER_RINGS,
-    )?);
+    let mut items: Vec<IndexItem> = vec![
+        write_curated_basic_cross(&curated_dir)?,
+        write_curated_rational_intersection(&curated_dir)?,
+        write_curated_endpoint_touch(&curated_dir)?,
+        write_curated_preprocess_warnings(&curated_dir)?,
+        write_perf_grid_orthogonal(&perf_dir, PERF_GRID_N)?,
+        write_perf_grid_diagonal_45(&perf_dir, PERF_GRID_N)?,
+        write_perf_spider_web(&perf_dir, PERF_SPIDER_SPOKES, PERF_SPIDER_RINGS)?,
+    ];
 
     for i in 0..args.random_count {
         let seed = mix_seed(args.seed, i as u64);
@@ -738,7 +733,7 @@ fn isqrt_u128(n: u128) -> u128 {
 
     // Newton-Raphson：确定性、无依赖。
     let mut x0 = n;
-    let mut x1 = (x0 + 1) / 2;
+    let mut x1 = x0.div_ceil(2);
     while x1 < x0 {
         x0 = x1;
         x1 = (x1 + n / x1) / 2;
@@ -0,0 +1,454 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use sweep_line::json::parse_json;
+use sweep_line::limits::Limits;
+use sweep_line::run::{Phase1Options, run_phase1_with_options};
+use sweep_line::trace::intersection_to_json_string;
+use sweep_line::{InputSegmentF64, Quantization, QuantizationMode};
+
+fn main() {
+    let args = match Args::parse_from(env::args().skip(1)) {
+        Ok(v) => v,
+        Err(msg) => {
+            if !msg.is_empty() {
+                eprintln!("错误：{msg}");
+                eprintln!();
+            }
+            eprintln!("{}", Args::usage());
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(msg) = run(args) {
+        eprintln!("错误：{msg}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let text = match &args.input {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| format!("读取输入失败：{}（{}）", path.display(), e))?,
+        None => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("读取标准输入失败：{e}"))?;
+            text
+        }
+    };
+
+    let input = parse_segments(&text, args.format)?;
+    let out =
+        run_phase1_with_options(&input, &args.options).map_err(|e| format!("运行算法失败：{e}"))?;
+
+    for w in &out.preprocess.warnings {
+        eprintln!("告警：{w}");
+    }
+
+    if let Some(target) = &args.session_out {
+        let json = out
+            .to_session_json_string_limited(args.options.limits)
+            .map_err(|e| format!("生成 session JSON 失败：{e}"))?;
+        write_output(target, &json)?;
+    }
+
+    if let Some(target) = &args.intersections_out {
+        let mut lines = String::new();
+        for it in &out.intersections {
+            lines.push_str(&intersection_to_json_string(it));
+            lines.push('\n');
+        }
+        write_output(target, &lines)?;
+    }
+
+    eprintln!(
+        "完成：输入 {} 条，预处理后 {} 条线段，告警 {} 条，交点 {} 个，trace 步数 {}",
+        input.len(),
+        out.preprocess.segments.len(),
+        out.preprocess.warnings.len(),
+        out.intersections.len(),
+        out.trace.steps.len()
+    );
+
+    Ok(())
+}
+
+fn write_output(target: &Output, content: &str) -> Result<(), String> {
+    match target {
+        Output::Stdout => io::stdout()
+            .lock()
+            .write_all(content.as_bytes())
+            .map_err(|e| format!("写入标准输出失败：{e}")),
+        Output::File(path) => {
+            fs::write(path, content).map_err(|e| format!("写入失败：{}（{}）", path.display(), e))
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InputFormat {
+    /// 逐行判断：以 `{` 或 `[` 开头按 JSON 解析，否则按 CSV 解析。
+    Auto,
+    Csv,
+    JsonLines,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Output {
+    Stdout,
+    File(PathBuf),
+}
+
+impl Output {
+    fn from_arg(v: String) -> Self {
+        if v == "-" {
+            Output::Stdout
+        } else {
+            Output::File(PathBuf::from(v))
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Args {
+    /// `None` 表示从标准输入读取。
+    input: Option<PathBuf>,
+    format: InputFormat,
+    options: Phase1Options,
+    intersections_out: Option<Output>,
+    session_out: Option<Output>,
+}
+
+impl Args {
+    fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut input: Option<PathBuf> = None;
+        let mut format = InputFormat::Auto;
+        let mut options = Phase1Options::default();
+        let mut intersections_out: Option<Output> = None;
+        let mut session_out: Option<Output> = None;
+
+        let mut it = args.into_iter();
+        while let Some(arg) = it.next() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    return Err("".to_string());
+                }
+                "--input" => {
+                    let Some(v) = it.next() else {
+                        return Err("--input 缺少参数".to_string());
+                    };
+                    input = if v == "-" {
+                        None
+                    } else {
+                        Some(PathBuf::from(v))
+                    };
+                }
+                "--format" => {
+                    let Some(v) = it.next() else {
+                        return Err("--format 缺少参数".to_string());
+                    };
+                    format = match v.as_str() {
+                        "auto" => InputFormat::Auto,
+                        "csv" => InputFormat::Csv,
+                        "jsonl" => InputFormat::JsonLines,
+                        _ => return Err(format!("--format 必须是 auto/csv/jsonl：{v}")),
+                    };
+                }
+                "--quantization" => {
+                    let Some(v) = it.next() else {
+                        return Err("--quantization 缺少参数".to_string());
+                    };
+                    options.quantization = parse_quantization(&v)?;
+                }
+                "--no-trace" => {
+                    options.trace_enabled = false;
+                }
+                "--intersections" => {
+                    let Some(v) = it.next() else {
+                        return Err("--intersections 缺少参数".to_string());
+                    };
+                    intersections_out = Some(Output::from_arg(v));
+                }
+                "--session" => {
+                    let Some(v) = it.next() else {
+                        return Err("--session 缺少参数".to_string());
+                    };
+                    session_out = Some(Output::from_arg(v));
+                }
+                "--max-session-bytes" => {
+                    options.limits.max_session_bytes = parse_limit(&arg, it.next())?;
+                }
+                "--max-trace-steps" => {
+                    options.limits.max_trace_steps = parse_limit(&arg, it.next())?;
+                }
+                "--max-trace-active-entries-total" => {
+                    options.limits.max_trace_active_entries_total = parse_limit(&arg, it.next())?;
+                }
+                "--max-intersections" => {
+                    options.limits.max_intersections = parse_limit(&arg, it.next())?;
+                }
+                _ => {
+                    return Err(format!("未知参数：{arg}"));
+                }
+            }
+        }
+
+        if intersections_out.is_none() && session_out.is_none() {
+            intersections_out = Some(Output::Stdout);
+        }
+        if intersections_out == Some(Output::Stdout) && session_out == Some(Output::Stdout) {
+            return Err("--intersections 与 --session 不能同时输出到标准输出".to_string());
+        }
+
+        Ok(Self {
+            input,
+            format,
+            options,
+            intersections_out,
+            session_out,
+        })
+    }
+
+    fn usage() -> String {
+        let d = Limits::default();
+        format!(
+            "用法：cargo run --bin sweep-segments -- [--input <file|->] [--format auto|csv|jsonl] [--intersections <file|->] [--session <file|->] [--quantization unit|auto|ox,oy,k] [--no-trace] [--max-* N]\n\
+\n\
+说明：\n\
+- 从文件或标准输入（默认）读取线段，每行一条 `ax,ay,bx,by`：\n\
+  - CSV：`ax,ay,bx,by`（可选表头 `ax,ay,bx,by`；空行与 `#` 开头的行会被忽略）\n\
+  - JSON lines：`{{\"ax\":..,\"ay\":..,\"bx\":..,\"by\":..}}` 或 `[ax,ay,bx,by]`\n\
+  - auto（默认）：逐行判断，以 `{{` 或 `[` 开头按 JSON 解析，否则按 CSV 解析\n\
+- 运行 phase1（预处理 + 点交枚举），预处理告警输出到 stderr。\n\
+- --intersections：按点聚合的交点记录，每行一个 JSON（未指定任何输出时默认写到 stdout）。\n\
+- --session：`session.v2` JSON（可直接用 viewer 回放）。\n\
+- --quantization：输入坐标到整数网格的映射（结果记录在 session 的 `fixed.transform`）：\n\
+  - unit（默认）：坐标范围 [-1, 1]，乘以 SCALE\n\
+  - auto：按输入包围盒居中并等比缩放\n\
+  - ox,oy,k：网格坐标 = round((v - o) * k)\n\
+- --no-trace：关闭 trace（大规模输入建议关闭）。\n\
+\n\
+上限（任一触发即 fail-fast 报错）：\n\
+- --max-session-bytes N（默认 {}）\n\
+- --max-trace-steps N（默认 {}）\n\
+- --max-trace-active-entries-total N（默认 {}）\n\
+- --max-intersections N（默认 {}）\n\
+\n\
+示例：\n\
+- cargo run --bin sweep-segments -- --input segs.csv --session out/session.json\n\
+- cat segs.jsonl | cargo run --bin sweep-segments -- --no-trace --intersections -\n",
+            d.max_session_bytes,
+            d.max_trace_steps,
+            d.max_trace_active_entries_total,
+            d.max_intersections
+        )
+    }
+}
+
+fn parse_quantization(v: &str) -> Result<QuantizationMode, String> {
+    match v {
+        "unit" => return Ok(QuantizationMode::Unit),
+        "auto" => return Ok(QuantizationMode::Auto),
+        _ => {}
+    }
+    let err =
+        || format!("--quantization 必须是 unit/auto 或 `origin_x,origin_y,grid_per_unit`：{v}");
+    let fields: Vec<f64> = v
+        .split(',')
+        .map(|f| f.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| err())?;
+    let [origin_x, origin_y, grid_per_unit] = fields[..] else {
+        return Err(err());
+    };
+    Quantization::new(origin_x, origin_y, grid_per_unit)
+        .map(QuantizationMode::Fixed)
+        .ok_or_else(err)
+}
+
+fn parse_limit(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let Some(v) = value else {
+        return Err(format!("{flag} 缺少参数"));
+    };
+    v.parse::<usize>()
+        .map_err(|_| format!("{flag} 必须是非负整数"))
+}
+
+fn parse_segments(text: &str, format: InputFormat) -> Result<Vec<InputSegmentF64>, String> {
+    let mut out = Vec::new();
+    let mut seen_data = false;
+    for (i, raw) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let is_json = match format {
+            InputFormat::Auto => line.starts_with('{') || line.starts_with('['),
+            InputFormat::Csv => false,
+            InputFormat::JsonLines => true,
+        };
+        if !is_json && !seen_data && is_csv_header(line) {
+            seen_data = true;
+            continue;
+        }
+        seen_data = true;
+
+        let seg = if is_json {
+            parse_json_line(line)
+        } else {
+            parse_csv_line(line)
+        }
+        .map_err(|msg| format!("第 {line_no} 行：{msg}"))?;
+        out.push(seg);
+    }
+    Ok(out)
+}
+
+fn is_csv_header(line: &str) -> bool {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    fields == ["ax", "ay", "bx", "by"]
+}
+
+fn parse_csv_line(line: &str) -> Result<InputSegmentF64, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() != 4 {
+        return Err(format!(
+            "CSV 需要 4 列 `ax,ay,bx,by`，实际为 {} 列",
+            fields.len()
+        ));
+    }
+    let mut v = [0.0_f64; 4];
+    for (k, field) in fields.iter().enumerate() {
+        v[k] = field
+            .parse::<f64>()
+            .map_err(|_| format!("第 {} 列不是数字：{field:?}", k + 1))?;
+    }
+    Ok(InputSegmentF64 {
+        ax: v[0],
+        ay: v[1],
+        bx: v[2],
+        by: v[3],
+    })
+}
+
+fn parse_json_line(line: &str) -> Result<InputSegmentF64, String> {
+    let describe = |e: sweep_line::json::JsonError| {
+        format!("第 {} 列（{}）：{}", e.pos.column, e.path, e.message)
+    };
+    let node = parse_json(line).map_err(describe)?;
+    let v = if let Ok(items) = node.as_array("$") {
+        if items.len() != 4 {
+            return Err(format!(
+                "JSON 数组需要 4 个数 [ax,ay,bx,by]，实际为 {} 个",
+                items.len()
+            ));
+        }
+        let mut v = [0.0_f64; 4];
+        for (k, item) in items.iter().enumerate() {
+            v[k] = item.as_f64(&format!("$[{k}]")).map_err(describe)?;
+        }
+        v
+    } else {
+        let mut v = [0.0_f64; 4];
+        for (k, key) in ["ax", "ay", "bx", "by"].iter().enumerate() {
+            v[k] = node
+                .get(key, "$")
+                .and_then(|n| n.as_f64(&format!("$.{key}")))
+                .map_err(describe)?;
+        }
+        v
+    };
+    Ok(InputSegmentF64 {
+        ax: v[0],
+        ay: v[1],
+        bx: v[2],
+        by: v[3],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Args, String> {
+        Args::parse_from(list.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn parses_csv_and_json_lines_in_auto_mode() {
+        let text = "# 注释\nax, ay, bx, by\n-1,0,1,0\n\n{\"ax\":0,\"ay\":-1,\"bx\":0,\"by\":1}\n[0.5, 0.5, -0.5, -0.5]\n";
+        let segs = parse_segments(text, InputFormat::Auto).unwrap();
+        let coords: Vec<[f64; 4]> = segs.iter().map(|s| [s.ax, s.ay, s.bx, s.by]).collect();
+        assert_eq!(
+            coords,
+            vec![
+                [-1.0, 0.0, 1.0, 0.0],
+                [0.0, -1.0, 0.0, 1.0],
+                [0.5, 0.5, -0.5, -0.5]
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_input_errors_with_line_numbers() {
+        let err = parse_segments("0,0,1,1\n0,0,1\n", InputFormat::Auto).unwrap_err();
+        assert!(err.starts_with("第 2 行："), "{err}");
+
+        let err = parse_segments("{\"ax\":0,\"ay\":0,\"bx\":1}\n", InputFormat::Auto).unwrap_err();
+        assert!(err.contains("缺少字段 `by`"), "{err}");
+
+        let err = parse_segments("0,0,1,1\n", InputFormat::JsonLines).unwrap_err();
+        assert!(err.starts_with("第 1 行："), "{err}");
+    }
+
+    #[test]
+    fn parses_limit_flags_and_outputs() {
+        let a = args(&[
+            "--no-trace",
+            "--max-session-bytes",
+            "10",
+            "--max-trace-steps",
+            "11",
+            "--max-trace-active-entries-total",
+            "12",
+            "--max-intersections",
+            "13",
+            "--session",
+            "out.json",
+        ])
+        .unwrap();
+        assert!(!a.options.trace_enabled);
+        assert_eq!(
+            a.options.limits,
+            Limits {
+                max_session_bytes: 10,
+                max_trace_steps: 11,
+                max_trace_active_entries_total: 12,
+                max_intersections: 13,
+            }
+        );
+        assert_eq!(a.session_out, Some(Output::File(PathBuf::from("out.json"))));
+        assert_eq!(a.intersections_out, None);
+
+        let a = args(&["--quantization", "auto"]).unwrap();
+        assert_eq!(a.options.quantization, QuantizationMode::Auto);
+        let a = args(&["--quantization", "1, 2, 1000"]).unwrap();
+        assert_eq!(
+            a.options.quantization,
+            QuantizationMode::Fixed(Quantization::new(1.0, 2.0, 1000.0).unwrap())
+        );
+        assert!(args(&["--quantization", "1,2"]).is_err());
+        assert!(args(&["--quantization", "0,0,-1"]).is_err());
+
+        let a = args(&[]).unwrap();
+        assert_eq!(a.intersections_out, Some(Output::Stdout));
+        assert!(args(&["--session", "-", "--intersections", "-"]).is_err());
+        assert!(args(&["--max-trace-steps", "x"]).is_err());
+    }
+}
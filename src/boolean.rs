@@ -0,0 +1,500 @@
+//! 多边形布尔运算（并 / 交 / 差 / 异或）：在 `crate::arrangement` 的平面剖分上做面分类（overlay）。
+//!
+//! 约定：
+//! - 输入为整数网格上的多边形（外环 + 洞），环首尾自动闭合、方向任意；填充规则为奇偶（even-odd）：
+//!   点在 A 内 ⇔ 从无穷远到该点穿过 A 的环边次数为奇数（自交、重叠的环也有确定语义）；
+//! - A、B 的全部环边一起构建平面剖分（共线重叠先切原子子段），从无界面出发沿边传播 A/B 内外标记，
+//!   按运算选取面，输出“左侧选中、右侧未选中”的半边连成的边界环；
+//! - 结果是正则化的：零面积部分（悬边、孤立点、只在边上接触的部分）不出现在输出中；
+//! - 输出确定：外环逆时针、洞顺时针，每个环从最左下顶点开始并去掉共线的中间顶点；
+//!   只在一点接触的两块区域分属不同多边形，与外环（或其他洞）只在一点接触的洞仍单独输出；
+//!   多边形按外环起点升序，洞按起点升序。
+
+use core::fmt;
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::arrangement::{
+    Arrangement, ArrangementError, HalfEdgeId, VertexId, build_arrangement_from_segments,
+};
+use crate::atomic::{AtomicSegmentId, AtomicSegments};
+use crate::geom::fixed::{MAX_ABS_COORD, PointI64};
+use crate::geom::point::PointRat;
+use crate::geom::segment::{Segment, SegmentKey, Segments};
+use crate::limits::Limits;
+
+/// 结果环的顶点序列（不重复首点）。
+pub type Ring = Vec<PointRat>;
+
+/// 整数网格上的多边形：外环 + 洞（环不重复首点）。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PolygonI64 {
+    pub exterior: Vec<PointI64>,
+    pub holes: Vec<Vec<PointI64>>,
+}
+
+impl PolygonI64 {
+    pub fn new(exterior: Vec<PointI64>) -> Self {
+        Self {
+            exterior,
+            holes: Vec::new(),
+        }
+    }
+
+    pub fn with_hole(mut self, hole: Vec<PointI64>) -> Self {
+        self.holes.push(hole);
+        self
+    }
+
+    fn rings(&self) -> impl Iterator<Item = &Vec<PointI64>> {
+        core::iter::once(&self.exterior).chain(&self.holes)
+    }
+}
+
+/// 布尔运算结果中的一个多边形（顶点为精确有理坐标）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BooleanPolygon {
+    /// 逆时针。
+    pub exterior: Ring,
+    /// 各个洞（顺时针）。
+    pub holes: Vec<Ring>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    /// `A - B`。
+    Difference,
+    Xor,
+}
+
+impl BooleanOp {
+    fn select(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            BooleanOp::Union => in_a || in_b,
+            BooleanOp::Intersection => in_a && in_b,
+            BooleanOp::Difference => in_a && !in_b,
+            BooleanOp::Xor => in_a != in_b,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BooleanError {
+    /// 顶点坐标超出 `[-MAX_ABS_COORD, MAX_ABS_COORD]`。
+    CoordinateOutOfRange {
+        point: PointI64,
+    },
+    Arrangement(ArrangementError),
+}
+
+impl From<ArrangementError> for BooleanError {
+    fn from(value: ArrangementError) -> Self {
+        Self::Arrangement(value)
+    }
+}
+
+impl fmt::Display for BooleanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BooleanError::CoordinateOutOfRange { point } => write!(
+                f,
+                "多边形顶点 ({}, {}) 超出网格坐标范围 [-{}, {}]",
+                point.x, point.y, MAX_ABS_COORD, MAX_ABS_COORD
+            ),
+            BooleanError::Arrangement(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// 计算 `a op b`（两侧都可以是多个多边形）。
+pub fn polygon_boolean(
+    a: &[PolygonI64],
+    b: &[PolygonI64],
+    op: BooleanOp,
+    limits: Limits,
+) -> Result<Vec<BooleanPolygon>, BooleanError> {
+    let (segments, toggles) = collect_ring_edges(a, b)?;
+    let (atomic, arr) = build_arrangement_from_segments(&segments, limits)?;
+    let selected = classify_faces(&atomic, &arr, &toggles, op);
+    let boundary = |h: HalfEdgeId| {
+        let he = arr.half_edge(h);
+        selected[he.face.0] && !selected[arr.half_edge(he.twin).face.0]
+    };
+
+    // 选中的面经由两侧都选中的边连通，即为同一个多边形。
+    let mut component: Vec<usize> = (0..arr.faces.len()).collect();
+    for he in &arr.half_edges {
+        let (f, g) = (he.face.0, arr.half_edge(he.twin).face.0);
+        if selected[f] && selected[g] {
+            let (rf, rg) = (find(&mut component, f), find(&mut component, g));
+            component[rf.max(rg)] = rf.min(rg);
+        }
+    }
+
+    let mut visited = vec![false; arr.half_edges.len()];
+    let mut polygons: BTreeMap<usize, (Option<Ring>, Vec<Ring>)> = BTreeMap::new();
+    for start in (0..arr.half_edges.len()).map(HalfEdgeId) {
+        if visited[start.0] || !boundary(start) {
+            continue;
+        }
+        let ring = trace_ring(&arr, start, &boundary);
+        for &h in &ring {
+            visited[h.0] = true;
+        }
+        for ring in split_at_repeated_vertices(&arr, ring) {
+            let root = find(&mut component, arr.half_edge(ring[0]).face.0);
+            let (points, ccw) = ring_points(&atomic, &arr, &ring);
+            let entry = polygons.entry(root).or_default();
+            if ccw {
+                debug_assert!(entry.0.is_none(), "每个多边形恰有一个外环");
+                entry.0 = Some(points);
+            } else {
+                entry.1.push(points);
+            }
+        }
+    }
+
+    let mut out: Vec<BooleanPolygon> = polygons
+        .into_values()
+        .map(|(exterior, mut holes)| {
+            holes.sort();
+            BooleanPolygon {
+                exterior: exterior.expect("每个多边形恰有一个外环"),
+                holes,
+            }
+        })
+        .collect();
+    out.sort_by(|p, q| p.exterior.cmp(&q.exterior));
+    Ok(out)
+}
+
+/// 收集两侧全部环边：相同的边合并，每条线段记录穿过它时 A/B 的奇偶是否翻转（翻转均为假的边直接丢弃）。
+pub(crate) fn collect_ring_edges(
+    a: &[PolygonI64],
+    b: &[PolygonI64],
+) -> Result<(Segments, Vec<[bool; 2]>), BooleanError> {
+    let mut edges: BTreeMap<SegmentKey, [bool; 2]> = BTreeMap::new();
+    for (side, polygons) in [a, b].into_iter().enumerate() {
+        for ring in polygons.iter().flat_map(PolygonI64::rings) {
+            for (i, &p) in ring.iter().enumerate() {
+                if p.x.abs() > MAX_ABS_COORD || p.y.abs() > MAX_ABS_COORD {
+                    return Err(BooleanError::CoordinateOutOfRange { point: p });
+                }
+                let q = ring[(i + 1) % ring.len()];
+                if p != q {
+                    edges.entry(SegmentKey::new(p, q)).or_default()[side] ^= true;
+                }
+            }
+        }
+    }
+
+    let mut segments = Segments::new();
+    let mut toggles = Vec::new();
+    for (key, toggle) in edges {
+        if toggle != [false, false] {
+            segments.push(Segment {
+                a: key.a,
+                b: key.b,
+                source_index: segments.len(),
+            });
+            toggles.push(toggle);
+        }
+    }
+    Ok((segments, toggles))
+}
+
+/// 从无界面（A、B 之外）出发，跨边时按该边的奇偶翻转更新内外标记，返回每个面是否被选中。
+pub(crate) fn classify_faces(
+    atomic: &AtomicSegments,
+    arr: &Arrangement,
+    toggles: &[[bool; 2]],
+    op: BooleanOp,
+) -> Vec<bool> {
+    let mut by_face: Vec<Vec<HalfEdgeId>> = vec![Vec::new(); arr.faces.len()];
+    for (i, he) in arr.half_edges.iter().enumerate() {
+        by_face[he.face.0].push(HalfEdgeId(i));
+    }
+
+    let mut inside: Vec<Option<[bool; 2]>> = vec![None; arr.faces.len()];
+    inside[Arrangement::UNBOUNDED_FACE.0] = Some([false, false]);
+    let mut queue = VecDeque::from([Arrangement::UNBOUNDED_FACE]);
+    while let Some(f) = queue.pop_front() {
+        let state = inside[f.0].expect("入队的面已有标记");
+        for &h in &by_face[f.0] {
+            let he = arr.half_edge(h);
+            let g = arr.half_edge(he.twin).face;
+            if inside[g.0].is_some() {
+                continue;
+            }
+            let mut next = state;
+            for id in atomic.provenance(AtomicSegmentId(he.segment.0)) {
+                next[0] ^= toggles[id.0][0];
+                next[1] ^= toggles[id.0][1];
+            }
+            inside[g.0] = Some(next);
+            queue.push_back(g);
+        }
+    }
+
+    inside
+        .into_iter()
+        .map(|s| {
+            let [in_a, in_b] = s.expect("每个面都与无界面连通");
+            op.select(in_a, in_b)
+        })
+        .collect()
+}
+
+/// 沿边界半边走一圈：到达顶点后，从来向顺时针找第一条边界半边（其间扫过的扇区都是选中的）。
+fn trace_ring(
+    arr: &Arrangement,
+    start: HalfEdgeId,
+    boundary: &impl Fn(HalfEdgeId) -> bool,
+) -> Vec<HalfEdgeId> {
+    let mut ring = vec![start];
+    let mut cur = start;
+    loop {
+        let mut next = arr.half_edge(cur).next;
+        while !boundary(next) {
+            next = arr.half_edge(arr.half_edge(next).twin).next;
+        }
+        if next == start {
+            return ring;
+        }
+        ring.push(next);
+        cur = next;
+    }
+}
+
+/// 在重复经过的顶点处把环拆成若干简单环（点接触的洞 / 区域各自成环）。
+fn split_at_repeated_vertices(arr: &Arrangement, ring: Vec<HalfEdgeId>) -> Vec<Vec<HalfEdgeId>> {
+    let mut out = Vec::new();
+    let mut stack: Vec<HalfEdgeId> = Vec::new();
+    let mut position: BTreeMap<VertexId, usize> = BTreeMap::new();
+    for h in ring {
+        let v = arr.half_edge(h).origin;
+        if let Some(&i) = position.get(&v) {
+            let sub = stack.split_off(i);
+            for s in &sub {
+                position.remove(&arr.half_edge(*s).origin);
+            }
+            out.push(sub);
+        }
+        position.insert(v, stack.len());
+        stack.push(h);
+    }
+    out.push(stack);
+    out
+}
+
+/// 环的顶点（去掉共线中间点、从最左下顶点开始）及其是否为逆时针。
+fn ring_points(
+    atomic: &AtomicSegments,
+    arr: &Arrangement,
+    ring: &[HalfEdgeId],
+) -> (Vec<PointRat>, bool) {
+    let direction = |h: HalfEdgeId| {
+        let s = atomic.get(AtomicSegmentId(arr.half_edge(h).segment.0));
+        let d = (s.b.x as i128 - s.a.x as i128, s.b.y as i128 - s.a.y as i128);
+        // 半边 `2k` 沿线段方向，`2k+1` 反向。
+        if h.0.is_multiple_of(2) {
+            d
+        } else {
+            (-d.0, -d.1)
+        }
+    };
+    let turn = |i: usize| {
+        let (din, dout) = (
+            direction(ring[(i + ring.len() - 1) % ring.len()]),
+            direction(ring[i]),
+        );
+        din.0 * dout.1 - din.1 * dout.0
+    };
+
+    let corners: Vec<(PointRat, i128)> = (0..ring.len())
+        .filter_map(|i| {
+            let t = turn(i);
+            (t != 0).then(|| (arr.vertex(arr.half_edge(ring[i]).origin).point, t))
+        })
+        .collect();
+    let start = (0..corners.len())
+        .min_by_key(|&i| corners[i].0)
+        .expect("面积非零的环至少有 3 个拐点");
+    // 最左下顶点是凸包顶点，其转向即环的方向。
+    let ccw = corners[start].1 > 0;
+    let points = (0..corners.len())
+        .map(|i| corners[(start + i) % corners.len()].0)
+        .collect();
+    (points, ccw)
+}
+
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rational::Rational;
+
+    fn poly(points: &[(i64, i64)]) -> PolygonI64 {
+        PolygonI64::new(ring(points))
+    }
+
+    fn ring(points: &[(i64, i64)]) -> Vec<PointI64> {
+        points.iter().map(|&(x, y)| PointI64 { x, y }).collect()
+    }
+
+    fn rect(x0: i64, y0: i64, x1: i64, y1: i64) -> PolygonI64 {
+        poly(&[(x0, y0), (x1, y0), (x1, y1), (x0, y1)])
+    }
+
+    fn rat(points: &[(i64, i64)]) -> Vec<PointRat> {
+        ring(points).into_iter().map(PointRat::from_i64).collect()
+    }
+
+    fn run(a: &[PolygonI64], b: &[PolygonI64], op: BooleanOp) -> Vec<BooleanPolygon> {
+        polygon_boolean(a, b, op, Limits::default()).unwrap()
+    }
+
+    #[test]
+    fn overlapping_squares_for_all_operations() {
+        let a = [rect(0, 0, 2, 2)];
+        // 顺时针输入：奇偶规则与环方向无关。
+        let b = [poly(&[(1, 1), (1, 3), (3, 3), (3, 1)])];
+
+        assert_eq!(
+            run(&a, &b, BooleanOp::Union),
+            vec![BooleanPolygon {
+                exterior: rat(&[
+                    (0, 0),
+                    (2, 0),
+                    (2, 1),
+                    (3, 1),
+                    (3, 3),
+                    (1, 3),
+                    (1, 2),
+                    (0, 2)
+                ]),
+                holes: vec![],
+            }]
+        );
+        assert_eq!(
+            run(&a, &b, BooleanOp::Intersection),
+            vec![BooleanPolygon {
+                exterior: rat(&[(1, 1), (2, 1), (2, 2), (1, 2)]),
+                holes: vec![],
+            }]
+        );
+        assert_eq!(
+            run(&a, &b, BooleanOp::Difference),
+            vec![BooleanPolygon {
+                exterior: rat(&[(0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2)]),
+                holes: vec![],
+            }]
+        );
+        // 两块 L 形只在 (1,2)、(2,1) 处点接触：分属两个多边形。
+        let xor = run(&a, &b, BooleanOp::Xor);
+        assert_eq!(xor.len(), 2);
+        assert_eq!(xor[0], run(&a, &b, BooleanOp::Difference)[0]);
+        assert_eq!(
+            xor[1].exterior,
+            rat(&[(1, 2), (2, 2), (2, 1), (3, 1), (3, 3), (1, 3)])
+        );
+    }
+
+    #[test]
+    fn handles_holes_and_multi_polygons() {
+        let a = [rect(0, 0, 10, 10).with_hole(ring(&[(2, 2), (8, 2), (8, 8), (2, 8)]))];
+        let strip = [rect(4, -2, 6, 12)];
+
+        let union = run(&a, &strip, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert_eq!(
+            union[0].holes,
+            vec![
+                rat(&[(2, 2), (2, 8), (4, 8), (4, 2)]),
+                rat(&[(6, 2), (6, 8), (8, 8), (8, 2)]),
+            ]
+        );
+        assert_eq!(union[0].exterior.len(), 12);
+
+        let intersection = run(&a, &strip, BooleanOp::Intersection);
+        assert_eq!(
+            intersection
+                .iter()
+                .map(|p| p.exterior.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                rat(&[(4, 0), (6, 0), (6, 2), (4, 2)]),
+                rat(&[(4, 8), (6, 8), (6, 10), (4, 10)]),
+            ]
+        );
+
+        // 洞只在一点接触外环：仍单独作为洞输出。
+        let keyhole = run(
+            &[rect(0, 0, 4, 4)],
+            &[poly(&[(2, 0), (3, 2), (1, 2)])],
+            BooleanOp::Difference,
+        );
+        assert_eq!(
+            keyhole,
+            vec![BooleanPolygon {
+                exterior: rat(&[(0, 0), (4, 0), (4, 4), (0, 4)]),
+                holes: vec![rat(&[(1, 2), (3, 2), (2, 0)])],
+            }]
+        );
+    }
+
+    #[test]
+    fn handles_shared_edges_corner_touches_and_rational_vertices() {
+        // 共享一条边：合并为一个矩形，共线的中间顶点被去掉；交集面积为 0。
+        let left = [rect(0, 0, 1, 1)];
+        let right = [rect(1, 0, 2, 1)];
+        assert_eq!(
+            run(&left, &right, BooleanOp::Union),
+            vec![BooleanPolygon {
+                exterior: rat(&[(0, 0), (2, 0), (2, 1), (0, 1)]),
+                holes: vec![],
+            }]
+        );
+        assert!(run(&left, &right, BooleanOp::Intersection).is_empty());
+
+        // 只在角点接触：两个多边形。
+        assert_eq!(run(&left, &[rect(1, 1, 2, 2)], BooleanOp::Union).len(), 2);
+
+        // 两个三角形相交，交点为有理坐标。
+        let tri_a = [poly(&[(0, 0), (3, 0), (0, 3)])];
+        let tri_b = [poly(&[(0, 1), (2, 1), (2, 2)])];
+        let q = |x: (i128, i128), y: (i128, i128)| PointRat {
+            x: Rational::new(x.0, x.1),
+            y: Rational::new(y.0, y.1),
+        };
+        assert_eq!(
+            run(&tri_a, &tri_b, BooleanOp::Intersection),
+            vec![BooleanPolygon {
+                exterior: vec![q((0, 1), (1, 1)), q((2, 1), (1, 1)), q((4, 3), (5, 3))],
+                holes: vec![],
+            }]
+        );
+
+        let far = PointI64 {
+            x: MAX_ABS_COORD + 1,
+            y: 0,
+        };
+        assert_eq!(
+            polygon_boolean(
+                &[PolygonI64::new(vec![far, PointI64 { x: 0, y: 1 }])],
+                &[],
+                BooleanOp::Union,
+                Limits::default()
+            ),
+            Err(BooleanError::CoordinateOutOfRange { point: far })
+        );
+    }
+}
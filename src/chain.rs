@@ -0,0 +1,244 @@
+//! 折线 / 多边形环输入：展开为线段参与预处理与扫描，同时保留“链 + 边序号”的结构。
+//!
+//! 说明：
+//! - 第 `i` 条边连接 `points[i]` 与 `points[i + 1]`；环（`closed`）额外有一条闭合边 `points[n-1] -> points[0]`；
+//! - 展开后的线段按链顺序排列，`ChainLayout.edges[input_index]` 给出每条输入线段对应的 `(chain, edge)`；
+//! - 同一条链上相邻的边（跳过预处理丢弃的零长度边，环首尾相接）在公共顶点处的端点-端点接触是平凡的，
+//!   由 `ChainLayout.neighbours` 放行；其余接触（自接触、交叉、跨链接触）照常报告。
+
+use std::collections::BTreeSet;
+
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::point::PointRat;
+use crate::geom::segment::SegmentId;
+use crate::preprocess::{
+    InputSegmentF64, PreprocessOutput, QuantizationMode, preprocess_segments_with_quantization,
+};
+use crate::sweep::bo::IntersectionQueryOptions;
+
+/// 一条折线或多边形环（至少 2 个顶点才有边）。
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputChainF64 {
+    pub points: Vec<(f64, f64)>,
+    /// 是否为闭合环（自动补上最后一个顶点到第一个顶点的边）。
+    pub closed: bool,
+}
+
+impl InputChainF64 {
+    pub fn polyline(points: Vec<(f64, f64)>) -> Self {
+        Self {
+            points,
+            closed: false,
+        }
+    }
+
+    pub fn ring(points: Vec<(f64, f64)>) -> Self {
+        Self {
+            points,
+            closed: true,
+        }
+    }
+
+    /// 边数：折线为 `n-1`，环为 `n`（不足 2 个顶点时为 0）。
+    pub fn edge_count(&self) -> usize {
+        match self.points.len() {
+            0 | 1 => 0,
+            n if self.closed => n,
+            n => n - 1,
+        }
+    }
+
+    fn edge(&self, edge: usize) -> InputSegmentF64 {
+        let (a, b) = (
+            self.points[edge],
+            self.points[(edge + 1) % self.points.len()],
+        );
+        InputSegmentF64 {
+            ax: a.0,
+            ay: a.1,
+            bx: b.0,
+            by: b.1,
+        }
+    }
+}
+
+/// 链上的一条边。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeRef {
+    pub chain: usize,
+    pub edge: usize,
+}
+
+/// 链结构：输入线段下标到 `(chain, edge)` 的映射，以及相邻边的放行集合。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainLayout {
+    /// 下标为展开后的输入线段下标（即 `Segment.source_index`）。
+    pub edges: Vec<EdgeRef>,
+    /// 相邻边（预处理后的 `SegmentId`）的端点-端点接触放行集合。
+    pub neighbours: IntersectionQueryOptions,
+}
+
+/// 按链顺序展开为输入线段（与 `ChainLayout.edges` 一一对应）。
+pub fn flatten_chains(input: &[InputChainF64]) -> (Vec<InputSegmentF64>, Vec<EdgeRef>) {
+    let mut segments = Vec::new();
+    let mut edges = Vec::new();
+    for (chain, c) in input.iter().enumerate() {
+        for edge in 0..c.edge_count() {
+            segments.push(c.edge(edge));
+            edges.push(EdgeRef { chain, edge });
+        }
+    }
+    (segments, edges)
+}
+
+/// 展开并预处理链输入，返回预处理结果与链结构。
+pub fn preprocess_chains(
+    input: &[InputChainF64],
+    quantization: &QuantizationMode,
+) -> (PreprocessOutput, ChainLayout) {
+    let (segments, edges) = flatten_chains(input);
+    let q = quantization.resolve(&segments);
+    let preprocess = preprocess_segments_with_quantization(&segments, &q);
+    let layout = chain_layout(input, edges, &preprocess);
+    (preprocess, layout)
+}
+
+/// 由预处理结果确定相邻边：同一条链上依次保留下来的边，且确实共享顶点（量化后）。
+///
+/// 说明：被判为重复而丢弃的边会打断相邻关系（折返/重合本身就不是平凡接触）。
+pub fn chain_layout(
+    input: &[InputChainF64],
+    edges: Vec<EdgeRef>,
+    preprocess: &PreprocessOutput,
+) -> ChainLayout {
+    let mut neighbours = IntersectionQueryOptions::default();
+    let mut start = 0;
+    for c in input {
+        let n = c.edge_count();
+        let kept: Vec<SegmentId> = preprocess.input_to_segment[start..start + n]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        let mut pairs: Vec<(SegmentId, SegmentId)> =
+            kept.windows(2).map(|w| (w[0], w[1])).collect();
+        if c.closed && kept.len() > 2 {
+            pairs.push((kept[kept.len() - 1], kept[0]));
+        }
+        for (a, b) in pairs {
+            let (sa, sb) = (preprocess.segments.get(a), preprocess.segments.get(b));
+            if [sa.a, sa.b].iter().any(|p| *p == sb.a || *p == sb.b) {
+                neighbours.allow_endpoint_contact(a, b);
+            }
+        }
+        start += n;
+    }
+    ChainLayout { edges, neighbours }
+}
+
+/// 以链与边序号表示的点交（只包含非平凡接触）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainIntersection {
+    pub point: PointRat,
+    /// 在该点以端点参与的边（升序、去重）。
+    pub endpoint_edges: Vec<EdgeRef>,
+    /// 在该点以内部点参与的边（升序、去重）。
+    pub interior_edges: Vec<EdgeRef>,
+}
+
+/// 过滤掉相邻边在公共顶点处的平凡接触，并把 `SegmentId` 换成 `(chain, edge)`。
+pub fn chain_intersections(
+    layout: &ChainLayout,
+    preprocess: &PreprocessOutput,
+    records: &[PointIntersectionGroupRecord],
+) -> Vec<ChainIntersection> {
+    let edges_of = |ids: &[SegmentId]| -> Vec<EdgeRef> {
+        let set: BTreeSet<EdgeRef> = ids
+            .iter()
+            .map(|id| layout.edges[preprocess.segments.get(*id).source_index])
+            .collect();
+        set.into_iter().collect()
+    };
+    records
+        .iter()
+        .filter(|r| layout.neighbours.is_reportable(r))
+        .map(|r| ChainIntersection {
+            point: r.point,
+            endpoint_edges: edges_of(&r.endpoint_segments),
+            interior_edges: edges_of(&r.interior_segments),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::fixed::Quantization;
+    use crate::rational::Rational;
+    use crate::sweep::bo::enumerate_point_intersections;
+
+    fn run(input: &[InputChainF64]) -> Vec<ChainIntersection> {
+        let (preprocess, layout) = preprocess_chains(input, &QuantizationMode::Auto);
+        let records = enumerate_point_intersections(&preprocess.segments).unwrap();
+        chain_intersections(&layout, &preprocess, &records)
+    }
+
+    #[test]
+    fn simple_chains_report_nothing() {
+        let square = InputChainF64::ring(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        // 重复顶点产生零长度边：其两侧的边仍视为相邻。
+        let zigzag = InputChainF64::polyline(vec![
+            (10.0, 0.0),
+            (11.0, 1.0),
+            (11.0, 1.0),
+            (12.0, 0.0),
+            (13.0, 1.0),
+        ]);
+        assert_eq!(square.edge_count(), 4);
+        assert_eq!(zigzag.edge_count(), 4);
+        assert!(run(&[square, zigzag]).is_empty());
+    }
+
+    #[test]
+    fn reports_self_touch_crossing_and_cross_chain_contact_with_edge_indices() {
+        // 领结形环：边 0 与边 2 在 (1,1) 交叉。
+        let bowtie = InputChainF64::ring(vec![(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)]);
+        // 折线的第 3 个顶点落在第 0 条边内部（自接触）。
+        let hook = InputChainF64::polyline(vec![(4.0, 0.0), (8.0, 0.0), (8.0, 2.0), (6.0, 0.0)]);
+        // 另一条链的端点与领结的顶点 (2,2) 重合（跨链的端点-端点接触）。
+        let tail = InputChainF64::polyline(vec![(2.0, 2.0), (3.0, 3.0)]);
+
+        let at = |x: i128, y: i128| PointRat {
+            x: Rational::from_int(x),
+            y: Rational::from_int(y),
+        };
+        // 恒等量化：网格坐标即输入坐标，便于直接比较交点。
+        let (preprocess, layout) = preprocess_chains(
+            &[bowtie, hook, tail],
+            &QuantizationMode::Fixed(Quantization::identity()),
+        );
+        let records = enumerate_point_intersections(&preprocess.segments).unwrap();
+        let out = chain_intersections(&layout, &preprocess, &records);
+        let e = |chain, edge| EdgeRef { chain, edge };
+        assert_eq!(
+            out,
+            vec![
+                ChainIntersection {
+                    point: at(1, 1),
+                    endpoint_edges: vec![],
+                    interior_edges: vec![e(0, 0), e(0, 2)],
+                },
+                ChainIntersection {
+                    point: at(2, 2),
+                    endpoint_edges: vec![e(0, 0), e(0, 1), e(2, 0)],
+                    interior_edges: vec![],
+                },
+                ChainIntersection {
+                    point: at(6, 0),
+                    endpoint_edges: vec![e(1, 2)],
+                    interior_edges: vec![e(1, 0)],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! 退化输入的随机对拍（无依赖）：生成小规模退化用例，与 `crate::naive` 的暴力结果比对，失败时自动缩减。
+//!
+//! 说明：
+//! - 用例直接使用整数网格输入 `InputSegmentI64`，经 `preprocess_segments_i64` 预处理后再比对，
+//!   因此零长度、完全重复等输入也会按真实流程被过滤；
+//! - 生成器覆盖：同点多线（星形）、共线链、垂直堆叠、共享端点、近似平行（见 `DegenerateKind`）；
+//! - 失败时按“删线段 → 坐标向 0 收缩”贪心缩减到局部最小，并输出可直接粘贴的 `#[test]`；
+//! - RNG 为确定性 xorshift：同一 `seed` 必然得到同一组用例，便于复现；
+//! - 不属于稳定的公开 API：只在本 crate 的测试中或启用 `fuzz` feature 时编译。
+
+use core::fmt;
+
+use crate::geom::fixed::Coord;
+use crate::naive::check_against_naive;
+use crate::preprocess::{InputSegmentI64, preprocess_segments_i64};
+
+/// 一个简单的确定性 RNG（无依赖），用于生成可复现用例。
+#[derive(Clone, Copy, Debug)]
+pub struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    pub fn new(seed: u64) -> Self {
+        let seed = if seed == 0 {
+            0x4d59_5df4_d0f3_3173
+        } else {
+            seed
+        };
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// `[0, n)` 上的整数（`n` 需大于 0；取模偏差对测试用途可忽略）。
+    pub fn below(&mut self, n: u64) -> u64 {
+        debug_assert!(n > 0, "below 的上界必须大于 0");
+        self.next_u64() % n
+    }
+
+    /// `[lo, hi]` 上的整数（闭区间）。
+    pub fn range(&mut self, lo: Coord, hi: Coord) -> Coord {
+        debug_assert!(lo <= hi, "range 需满足 lo <= hi");
+        lo + self.below((hi - lo) as u64 + 1) as Coord
+    }
+}
+
+/// 生成器类别：每类刻意制造一种扫描线容易出错的退化结构。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DegenerateKind {
+    /// 多条线段经过同一点（以端点或内部经过）。
+    Star,
+    /// 同一直线上首尾相接/互相包含的线段，再加少量穿过者。
+    CollinearChain,
+    /// 同一 x 上的多条垂直线段（相接、重叠、分离），再加少量穿过者。
+    VerticalStack,
+    /// 少量公共端点之间随机连线。
+    SharedEndpoints,
+    /// 斜率极接近的长线段（交点坐标为大分母有理数）。
+    NearParallel,
+    /// 以上各类混合，外加完全随机的线段。
+    Mixed,
+}
+
+impl DegenerateKind {
+    pub const ALL: [DegenerateKind; 6] = [
+        DegenerateKind::Star,
+        DegenerateKind::CollinearChain,
+        DegenerateKind::VerticalStack,
+        DegenerateKind::SharedEndpoints,
+        DegenerateKind::NearParallel,
+        DegenerateKind::Mixed,
+    ];
+}
+
+impl fmt::Display for DegenerateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DegenerateKind::Star => "Star",
+            DegenerateKind::CollinearChain => "CollinearChain",
+            DegenerateKind::VerticalStack => "VerticalStack",
+            DegenerateKind::SharedEndpoints => "SharedEndpoints",
+            DegenerateKind::NearParallel => "NearParallel",
+            DegenerateKind::Mixed => "Mixed",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// 对拍参数（默认值适合作为常规单测运行）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FuzzConfig {
+    pub seed: u64,
+    /// 用例数量（各类别轮流生成）。
+    pub cases: usize,
+    /// 网格边长：坐标基本落在 `[0, grid]`（`NearParallel` 会放大到 `grid * 8`）。
+    pub grid: Coord,
+    /// 每个用例的最大线段数（至少 2 条）。
+    pub max_segments: usize,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            cases: 600,
+            grid: 6,
+            max_segments: 10,
+        }
+    }
+}
+
+/// 对拍失败：包含原始用例、缩减后的用例与可复现测试代码。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzFailure {
+    pub seed: u64,
+    pub case_index: usize,
+    pub kind: DegenerateKind,
+    pub original: Vec<InputSegmentI64>,
+    pub shrunk: Vec<InputSegmentI64>,
+    /// 缩减后用例的失败原因（`check_case` 的错误信息）。
+    pub report: String,
+}
+
+impl FuzzFailure {
+    /// 可直接粘贴到 `#[cfg(test)] mod tests` 的回归测试。
+    pub fn regression_test(&self) -> String {
+        regression_test_source(
+            &format!("fuzz_seed_{}_case_{}", self.seed, self.case_index),
+            &self.shrunk,
+        )
+    }
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "随机对拍失败（seed={}，第 {} 个用例，类型 {}）：原始 {} 条线段，缩减后 {} 条",
+            self.seed,
+            self.case_index,
+            self.kind,
+            self.original.len(),
+            self.shrunk.len()
+        )?;
+        writeln!(f, "{}", self.report.trim_end())?;
+        writeln!(f, "可复现测试：")?;
+        write!(f, "{}", self.regression_test())
+    }
+}
+
+/// 按类别生成一个用例（线段数在 `[2, max_segments]`）。
+pub fn generate_case(
+    rng: &mut XorShift64,
+    kind: DegenerateKind,
+    config: &FuzzConfig,
+) -> Vec<InputSegmentI64> {
+    let grid = config.grid.max(2);
+    let count = 2 + rng.below(config.max_segments.max(2) as u64 - 1) as usize;
+    let mut out = Vec::with_capacity(count);
+    match kind {
+        DegenerateKind::Star => {
+            let (cx, cy) = (rng.range(0, grid), rng.range(0, grid));
+            while out.len() < count {
+                let (dx, dy) = small_direction(rng);
+                // t = 0 表示该侧以中心为端点。
+                let (t0, t1) = (rng.range(0, 2), rng.range(0, 2));
+                out.push(seg(cx - t0 * dx, cy - t0 * dy, cx + t1 * dx, cy + t1 * dy));
+            }
+        }
+        DegenerateKind::CollinearChain => {
+            let (px, py) = (rng.range(0, grid), rng.range(0, grid));
+            let (dx, dy) = small_direction(rng);
+            while out.len() + 1 < count {
+                let (i, j) = (rng.range(-3, 3), rng.range(-3, 3));
+                out.push(seg(px + i * dx, py + i * dy, px + j * dx, py + j * dy));
+            }
+            out.push(random_segment(rng, grid));
+        }
+        DegenerateKind::VerticalStack => {
+            let xs = [rng.range(0, grid), rng.range(0, grid)];
+            while out.len() < count {
+                if rng.below(3) == 0 {
+                    out.push(random_segment(rng, grid));
+                } else {
+                    let x = xs[rng.below(2) as usize];
+                    out.push(seg(x, rng.range(0, grid), x, rng.range(0, grid)));
+                }
+            }
+        }
+        DegenerateKind::SharedEndpoints => {
+            let pool: Vec<(Coord, Coord)> = (0..4)
+                .map(|_| (rng.range(0, grid), rng.range(0, grid)))
+                .collect();
+            while out.len() < count {
+                let a = pool[rng.below(pool.len() as u64) as usize];
+                let b = if rng.below(4) == 0 {
+                    (rng.range(0, grid), rng.range(0, grid))
+                } else {
+                    pool[rng.below(pool.len() as u64) as usize]
+                };
+                out.push(seg(a.0, a.1, b.0, b.1));
+            }
+        }
+        DegenerateKind::NearParallel => {
+            let span = grid * 8;
+            while out.len() < count {
+                // 方向 (k, k+1) 与 (k+1, k+2) 的斜率差约为 1/k²。
+                let k = rng.range(1, grid);
+                let (dx, dy) = if rng.below(2) == 0 {
+                    (k, k + 1)
+                } else {
+                    (k + 1, k + 2)
+                };
+                let (x0, y0) = (rng.range(-span, 0), rng.range(-span, 0));
+                let t = rng.range(1, 4) * (span / dx).max(1);
+                out.push(seg(x0, y0, x0 + t * dx, y0 + t * dy));
+            }
+        }
+        DegenerateKind::Mixed => {
+            while out.len() < count {
+                let sub = DegenerateKind::ALL[rng.below(5) as usize];
+                let mut part = generate_case(
+                    rng,
+                    sub,
+                    &FuzzConfig {
+                        max_segments: 3,
+                        ..*config
+                    },
+                );
+                part.truncate(count - out.len());
+                out.extend(part);
+                if rng.below(2) == 0 && out.len() < count {
+                    out.push(random_segment(rng, grid));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// 对拍性质：预处理后扫描线输出与暴力参考完全一致。
+pub fn check_case(input: &[InputSegmentI64]) -> Result<(), String> {
+    let preprocess = preprocess_segments_i64(input);
+    let diff = check_against_naive(&preprocess.segments).map_err(|e| format!("扫描线报错：{e}"))?;
+    if diff.is_empty() {
+        Ok(())
+    } else {
+        Err(diff.to_string())
+    }
+}
+
+/// 贪心缩减失败用例：反复尝试删除单条线段、把坐标向 0 收缩，直到任何单步修改都不再失败。
+///
+/// `fails` 对传入用例返回 `true` 表示“仍然失败”；传入的 `case` 本身必须失败。
+pub fn shrink_case(
+    case: &[InputSegmentI64],
+    mut fails: impl FnMut(&[InputSegmentI64]) -> bool,
+) -> Vec<InputSegmentI64> {
+    let mut cur = case.to_vec();
+    loop {
+        let mut progressed = false;
+
+        let mut i = 0;
+        while i < cur.len() {
+            let mut candidate = cur.clone();
+            candidate.remove(i);
+            if fails(&candidate) {
+                cur = candidate;
+                progressed = true;
+            } else {
+                i += 1;
+            }
+        }
+
+        for i in 0..cur.len() {
+            for field in 0..4 {
+                let v = *coord_mut(&mut cur[i], field);
+                for next in shrink_toward_zero(v) {
+                    let mut candidate = cur.clone();
+                    *coord_mut(&mut candidate[i], field) = next;
+                    if fails(&candidate) {
+                        cur = candidate;
+                        progressed = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if !progressed {
+            return cur;
+        }
+    }
+}
+
+/// 运行随机对拍：各类别轮流生成 `config.cases` 个用例，遇到第一个失败即缩减并返回。
+pub fn run_fuzz(config: &FuzzConfig) -> Result<(), Box<FuzzFailure>> {
+    let mut rng = XorShift64::new(config.seed);
+    for case_index in 0..config.cases {
+        let kind = DegenerateKind::ALL[case_index % DegenerateKind::ALL.len()];
+        let original = generate_case(&mut rng, kind, config);
+        if check_case(&original).is_ok() {
+            continue;
+        }
+        let shrunk = shrink_case(&original, |c| check_case(c).is_err());
+        let report = check_case(&shrunk).err().unwrap_or_default();
+        return Err(Box::new(FuzzFailure {
+            seed: config.seed,
+            case_index,
+            kind,
+            original,
+            shrunk,
+            report,
+        }));
+    }
+    Ok(())
+}
+
+/// 生成回归测试源码（使用 crate 内路径，适合粘贴到库内的测试模块）。
+pub fn regression_test_source(name: &str, case: &[InputSegmentI64]) -> String {
+    let mut out = String::new();
+    out.push_str("#[test]\n");
+    out.push_str(&format!("fn {name}() {{\n"));
+    out.push_str("    let input = [\n");
+    for s in case {
+        out.push_str(&format!(
+            "        crate::preprocess::InputSegmentI64 {{ ax: {}, ay: {}, bx: {}, by: {} }},\n",
+            s.ax, s.ay, s.bx, s.by
+        ));
+    }
+    out.push_str("    ];\n");
+    out.push_str("    if let Err(e) = crate::fuzz::check_case(&input) {\n");
+    out.push_str("        panic!(\"{e}\");\n");
+    out.push_str("    }\n");
+    out.push_str("}\n");
+    out
+}
+
+fn seg(ax: Coord, ay: Coord, bx: Coord, by: Coord) -> InputSegmentI64 {
+    InputSegmentI64 { ax, ay, bx, by }
+}
+
+fn random_segment(rng: &mut XorShift64, grid: Coord) -> InputSegmentI64 {
+    seg(
+        rng.range(0, grid),
+        rng.range(0, grid),
+        rng.range(0, grid),
+        rng.range(0, grid),
+    )
+}
+
+/// 分量在 `[-2, 2]` 的非零方向（包含水平、垂直与 45°）。
+fn small_direction(rng: &mut XorShift64) -> (Coord, Coord) {
+    loop {
+        let d = (rng.range(-2, 2), rng.range(-2, 2));
+        if d != (0, 0) {
+            return d;
+        }
+    }
+}
+
+fn coord_mut(s: &mut InputSegmentI64, field: usize) -> &mut Coord {
+    match field {
+        0 => &mut s.ax,
+        1 => &mut s.ay,
+        2 => &mut s.bx,
+        _ => &mut s.by,
+    }
+}
+
+/// 候选值按“离 0 越近越优先”排列：`0`、`v/2`、`v∓1`。
+fn shrink_toward_zero(v: Coord) -> Vec<Coord> {
+    let mut out = Vec::new();
+    for c in [0, v / 2, v - v.signum()] {
+        if c != v && !out.contains(&c) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_matches_naive_on_degenerate_families() {
+        // 默认参数作为常规单测；加大规模：SWEEP_FUZZ_CASES=100000 SWEEP_FUZZ_SEED=7 cargo test --release fuzz
+        let env = |key: &str| std::env::var(key).ok().and_then(|v| v.parse::<u64>().ok());
+        let config = FuzzConfig {
+            seed: env("SWEEP_FUZZ_SEED").unwrap_or(1),
+            cases: env("SWEEP_FUZZ_CASES").map_or(600, |v| v as usize),
+            ..FuzzConfig::default()
+        };
+        if let Err(failure) = run_fuzz(&config) {
+            panic!("{failure}");
+        }
+    }
+
+    #[test]
+    fn generators_are_deterministic_and_cover_degenerate_shapes() {
+        let config = FuzzConfig::default();
+        for kind in DegenerateKind::ALL {
+            let a = generate_case(&mut XorShift64::new(42), kind, &config);
+            let b = generate_case(&mut XorShift64::new(42), kind, &config);
+            assert_eq!(a, b, "{kind}");
+            assert!((2..=config.max_segments).contains(&a.len()), "{kind}");
+        }
+
+        let mut rng = XorShift64::new(3);
+        let stack = generate_case(&mut rng, DegenerateKind::VerticalStack, &config);
+        assert!(stack.iter().any(|s| s.ax == s.bx));
+    }
+
+    #[test]
+    fn shrinks_failing_case_and_prints_reproducible_test() {
+        // 人为性质：“任意点上最多 2 条线段相交”；最小反例是 3 条经过同一点的线段。
+        let fails = |c: &[InputSegmentI64]| {
+            let pre = preprocess_segments_i64(c);
+            crate::naive::enumerate_point_intersections_naive(&pre.segments)
+                .iter()
+                .any(|g| g.endpoint_segments.len() + g.interior_segments.len() >= 3)
+        };
+        let case = [
+            seg(-4, 0, 4, 0),
+            seg(0, -4, 0, 4),
+            seg(-4, -4, 4, 4),
+            seg(5, 5, 6, 9),
+            seg(-3, 3, 3, -3),
+        ];
+        assert!(fails(&case));
+
+        let shrunk = shrink_case(&case, fails);
+        assert_eq!(shrunk.len(), 3);
+        assert!(fails(&shrunk));
+
+        let source = regression_test_source("three_lines", &shrunk);
+        assert!(source.starts_with("#[test]\nfn three_lines() {\n"));
+        assert_eq!(source.matches("InputSegmentI64 {").count(), 3);
+        assert!(source.contains("crate::fuzz::check_case(&input)"));
+    }
+}
@@ -0,0 +1,60 @@
+//! 闭的轴对齐包围盒。
+
+use crate::geom::fixed::{Coord, PointI64};
+use crate::geom::segment::Segment;
+
+/// 闭的轴对齐包围盒（`min <= max`）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BBox {
+    pub min: PointI64,
+    pub max: PointI64,
+}
+
+impl BBox {
+    /// 以两个对角点构造（顺序任意）。
+    pub fn new(a: PointI64, b: PointI64) -> Self {
+        Self {
+            min: PointI64 {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+            },
+            max: PointI64 {
+                x: a.x.max(b.x),
+                y: a.y.max(b.y),
+            },
+        }
+    }
+
+    pub fn of_segment(s: &Segment) -> Self {
+        Self::new(s.a, s.b)
+    }
+
+    pub fn intersects(&self, other: &BBox) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    pub fn contains(&self, p: PointI64) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    pub(crate) fn union(&self, other: &BBox) -> BBox {
+        BBox::new(
+            PointI64 {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+            },
+            PointI64 {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+            },
+        )
+    }
+
+    /// 中心坐标的两倍（避免除法，只用于装填排序）。
+    pub(crate) fn center2(&self) -> (Coord, Coord) {
+        (self.min.x + self.max.x, self.min.y + self.max.y)
+    }
+}
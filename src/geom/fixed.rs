@@ -4,6 +4,12 @@ pub type Coord = i64;
 
 pub const SCALE: Coord = 1_000_000_000;
 
+/// 网格坐标的绝对值上限。
+///
+/// 说明：`orient`、`y_at_x` 与交点有理数的中间量都按 `|x|,|y| ≤ MAX_ABS_COORD` 估算过 `i128` 不溢出
+/// （见 `plans/src-code-review-findings.md` 第 1 条）；所有预处理入口都必须保证这一点。
+pub const MAX_ABS_COORD: Coord = SCALE;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum QuantizeError {
     NonFinite,
@@ -14,7 +20,9 @@ impl fmt::Display for QuantizeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             QuantizeError::NonFinite => write!(f, "输入坐标不是有限浮点数"),
-            QuantizeError::OutOfRange => write!(f, "输入坐标超出允许范围 [-1, 1]"),
+            QuantizeError::OutOfRange => {
+                write!(f, "输入坐标超出量化范围（映射后超出 [-SCALE, SCALE]）")
+            }
         }
     }
 }
@@ -25,11 +33,116 @@ pub struct PointI64 {
     pub y: Coord,
 }
 
+/// 输入坐标到整数网格的仿射变换：`grid = round((v - origin) * grid_per_unit)`。
+///
+/// 说明：
+/// - x/y 共用同一个 `grid_per_unit`（等比缩放），避免量化改变角度与共线关系；
+/// - 映射后的网格坐标必须落在 `[-SCALE, SCALE]` 内（`orient`/`y_at_x` 的无溢出前提），否则视为 `OutOfRange`；
+/// - `Quantization::unit()` 即历史约定：输入范围 `[-1, 1]`，乘以 `SCALE`。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantization {
+    pub origin_x: f64,
+    pub origin_y: f64,
+    pub grid_per_unit: f64,
+}
+
+impl Default for Quantization {
+    fn default() -> Self {
+        Self::unit()
+    }
+}
+
+impl Quantization {
+    pub fn unit() -> Self {
+        Self {
+            origin_x: 0.0,
+            origin_y: 0.0,
+            grid_per_unit: SCALE as f64,
+        }
+    }
+
+    /// 恒等变换（网格坐标即输入坐标），用于整数坐标输入。
+    pub fn identity() -> Self {
+        Self {
+            origin_x: 0.0,
+            origin_y: 0.0,
+            grid_per_unit: 1.0,
+        }
+    }
+
+    /// 由调用方指定原点与缩放；`grid_per_unit` 必须是有限正数。
+    pub fn new(origin_x: f64, origin_y: f64, grid_per_unit: f64) -> Option<Self> {
+        if !origin_x.is_finite()
+            || !origin_y.is_finite()
+            || !grid_per_unit.is_finite()
+            || grid_per_unit <= 0.0
+        {
+            return None;
+        }
+        Some(Self {
+            origin_x,
+            origin_y,
+            grid_per_unit,
+        })
+    }
+
+    /// 把包围盒 `[min_x, max_x] × [min_y, max_y]` 居中并等比放大到 `[-SCALE, SCALE]`。
+    ///
+    /// 说明：
+    /// - 包围盒退化为单点（`min_x == max_x && min_y == max_y`）时没有可放大的范围，回退为以该点为原点、
+    ///   `grid_per_unit = SCALE` 的变换（与 `unit()` 同一缩放，只平移），而不是返回 `None`；
+    ///   需要区分这种情况的调用方应在调用前自行检查包围盒；
+    /// - 只在一维退化（例如全部点共线于水平线）时按另一维放大，不受影响；
+    /// - 包围盒无效（非有限或 min > max）时返回 `None`。
+    pub fn fit_bounds(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Option<Self> {
+        let finite = [min_x, min_y, max_x, max_y].iter().all(|v| v.is_finite());
+        if !finite || min_x > max_x || min_y > max_y {
+            return None;
+        }
+        let origin_x = min_x / 2.0 + max_x / 2.0;
+        let origin_y = min_y / 2.0 + max_y / 2.0;
+        let half = (max_x / 2.0 - min_x / 2.0).max(max_y / 2.0 - min_y / 2.0);
+        let grid_per_unit = if half > 0.0 {
+            SCALE as f64 / half
+        } else {
+            SCALE as f64
+        };
+        Self::new(origin_x, origin_y, grid_per_unit)
+    }
+
+    pub fn quantize_x(&self, value: f64) -> Result<Coord, QuantizeError> {
+        quantize_affine(value, self.origin_x, self.grid_per_unit)
+    }
+
+    pub fn quantize_y(&self, value: f64) -> Result<Coord, QuantizeError> {
+        quantize_affine(value, self.origin_y, self.grid_per_unit)
+    }
+
+    /// 网格坐标映射回调用方坐标系（`f64` 近似）。
+    pub fn unquantize(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.origin_x + x / self.grid_per_unit,
+            self.origin_y + y / self.grid_per_unit,
+        )
+    }
+}
+
+fn quantize_affine(value: f64, origin: f64, grid_per_unit: f64) -> Result<Coord, QuantizeError> {
+    if !value.is_finite() {
+        return Err(QuantizeError::NonFinite);
+    }
+    let scaled = ((value - origin) * grid_per_unit).round();
+    if !(-(MAX_ABS_COORD as f64)..=MAX_ABS_COORD as f64).contains(&scaled) {
+        return Err(QuantizeError::OutOfRange);
+    }
+    Ok(scaled as Coord)
+}
+
 pub fn quantize_coord(value: f64) -> Result<Coord, QuantizeError> {
     if !value.is_finite() {
         return Err(QuantizeError::NonFinite);
     }
-    if value < -1.0 || value > 1.0 {
+    if !(-1.0..=1.0).contains(&value) {
         return Err(QuantizeError::OutOfRange);
     }
 
@@ -48,6 +161,33 @@ pub fn quantize_point(x: f64, y: f64) -> Result<PointI64, QuantizeError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn quantization_fits_bounds_and_maps_back() {
+        let q = Quantization::fit_bounds(1000.0, 2000.0, 3000.0, 2500.0).unwrap();
+        assert_eq!((q.origin_x, q.origin_y), (2000.0, 2250.0));
+        assert_eq!(q.quantize_x(1000.0).unwrap(), -SCALE);
+        assert_eq!(q.quantize_x(3000.0).unwrap(), SCALE);
+        // 等比缩放：y 方向只用到一部分网格范围。
+        assert_eq!(q.quantize_y(2500.0).unwrap(), SCALE / 4);
+        assert_eq!(q.unquantize(-SCALE as f64, 0.0), (1000.0, 2250.0));
+        assert_eq!(q.quantize_x(3000.5).unwrap_err(), QuantizeError::OutOfRange);
+
+        let unit = Quantization::unit();
+        assert_eq!(unit.quantize_x(0.25), quantize_coord(0.25));
+        assert_eq!(
+            Quantization::fit_bounds(5.0, 5.0, 5.0, 5.0)
+                .unwrap()
+                .grid_per_unit,
+            SCALE as f64
+        );
+        // 单点回退：只平移到该点，缩放与 `unit()` 相同。
+        let single = Quantization::fit_bounds(5.0, -3.0, 5.0, -3.0).unwrap();
+        assert_eq!((single.origin_x, single.origin_y), (5.0, -3.0));
+        assert_eq!(single.quantize_x(5.5).unwrap(), SCALE / 2);
+        assert!(Quantization::fit_bounds(1.0, 0.0, 0.0, 0.0).is_none());
+        assert!(Quantization::new(0.0, 0.0, 0.0).is_none());
+    }
+
     #[test]
     fn quantize_coord_rounds() {
         assert_eq!(quantize_coord(0.0).unwrap(), 0);
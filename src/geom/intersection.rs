@@ -1,9 +1,10 @@
 use core::fmt;
 
-use crate::geom::fixed::PointI64;
+use crate::geom::bbox::BBox;
+use crate::geom::fixed::{MAX_ABS_COORD, PointI64};
 use crate::geom::point::PointRat;
 use crate::geom::predicates::{on_segment, orient};
-use crate::geom::segment::{Segment, SegmentId};
+use crate::geom::segment::{LayerId, Segment, SegmentId, Segments};
 use crate::rational::Rational;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -44,6 +45,53 @@ pub struct PointIntersectionGroupRecord {
     pub interior_segments: Vec<SegmentId>,
 }
 
+/// 带图层的参与线段。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LayeredSegmentRef {
+    pub segment: SegmentId,
+    pub layer: LayerId,
+}
+
+/// 双色模式的点组：与 `PointIntersectionGroupRecord` 相同，但每个参与者附带其图层。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayeredGroupRecord {
+    pub point: PointRat,
+    pub endpoint_segments: Vec<LayeredSegmentRef>,
+    pub interior_segments: Vec<LayeredSegmentRef>,
+}
+
+impl LayeredGroupRecord {
+    /// `layers` 以 `SegmentId` 为下标。
+    pub fn from_group(group: &PointIntersectionGroupRecord, layers: &[LayerId]) -> Self {
+        let tag = |ids: &[SegmentId]| {
+            ids.iter()
+                .map(|&segment| LayeredSegmentRef {
+                    segment,
+                    layer: layers[segment.0],
+                })
+                .collect()
+        };
+        Self {
+            point: group.point,
+            endpoint_segments: tag(&group.endpoint_segments),
+            interior_segments: tag(&group.interior_segments),
+        }
+    }
+
+    /// 该点涉及的图层（去重、升序）。
+    pub fn layers(&self) -> Vec<LayerId> {
+        let mut out: Vec<LayerId> = self
+            .endpoint_segments
+            .iter()
+            .chain(&self.interior_segments)
+            .map(|r| r.layer)
+            .collect();
+        out.sort();
+        out.dedup();
+        out
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SegmentIntersection {
     /// 唯一的点交（第一阶段的主要输出）。
@@ -51,17 +99,19 @@ pub enum SegmentIntersection {
         point: PointRat,
         kind: PointIntersectionKind,
     },
-    /// 共线且存在长度>0 的重叠段（第二阶段输出“最大重叠段集合”时处理）。
-    CollinearOverlap,
+    /// 共线且存在长度>0 的重叠段：`a < b`（按 `(x,y)` 字典序），两端点均为输入线段的端点。
+    ///
+    /// 说明：这里只描述“两条线段”之间的重叠；多条共线线段的“最大重叠段集合”见 `crate::overlap`。
+    CollinearOverlap { a: PointI64, b: PointI64 },
 }
 
 /// 计算两条（非零长度）线段的交集：
 /// - 空集：返回 `None`
 /// - 点交：返回 `Some(SegmentIntersection::Point{...})`
-/// - 共线重叠段：返回 `Some(SegmentIntersection::CollinearOverlap)`
+/// - 共线重叠段：返回 `Some(SegmentIntersection::CollinearOverlap{a,b})`
 ///
 /// 说明：
-/// - 完全重合/部分重叠属于“无穷多个交点”，第一阶段不展开枚举，用 `CollinearOverlap` 占位；
+/// - 完全重合/部分重叠属于“无穷多个交点”，不展开枚举，只返回重叠段的两个端点；
 /// - 端点接触也算交点，并归类为 `EndpointTouch`，便于前端用不同颜色区分。
 pub fn intersect_segments(a: &Segment, b: &Segment) -> Option<SegmentIntersection> {
     let p1 = a.a;
@@ -78,7 +128,7 @@ pub fn intersect_segments(a: &Segment, b: &Segment) -> Option<SegmentIntersectio
     if o1 == 0 && o2 == 0 && o3 == 0 && o4 == 0 {
         return collinear_intersection(p1, p2, q1, q2).map(|p| match p {
             CollinearResult::Point(pt) => point_intersection(pt, a, b),
-            CollinearResult::Overlap => SegmentIntersection::CollinearOverlap,
+            CollinearResult::Overlap(a, b) => SegmentIntersection::CollinearOverlap { a, b },
         });
     }
 
@@ -159,7 +209,7 @@ fn line_intersection_point(p1: PointI64, p2: PointI64, q1: PointI64, q2: PointI6
 
 enum CollinearResult {
     Point(PointI64),
-    Overlap,
+    Overlap(PointI64, PointI64),
 }
 
 fn collinear_intersection(
@@ -187,7 +237,9 @@ fn collinear_intersection(
             debug_assert!(on_segment(p1, p2, point) && on_segment(q1, q2, point));
             return Some(CollinearResult::Point(point));
         }
-        return Some(CollinearResult::Overlap);
+        let a = find_endpoint_with_y([p1, p2, q1, q2], lo)?;
+        let b = find_endpoint_with_y([p1, p2, q1, q2], hi)?;
+        return Some(CollinearResult::Overlap(a, b));
     }
 
     let p_min = p1.x.min(p2.x);
@@ -205,7 +257,9 @@ fn collinear_intersection(
         debug_assert!(on_segment(p1, p2, point) && on_segment(q1, q2, point));
         return Some(CollinearResult::Point(point));
     }
-    Some(CollinearResult::Overlap)
+    let a = find_endpoint_with_x([p1, p2, q1, q2], lo)?;
+    let b = find_endpoint_with_x([p1, p2, q1, q2], hi)?;
+    Some(CollinearResult::Overlap(a, b))
 }
 
 fn find_endpoint_with_x(points: [PointI64; 4], x: i64) -> Option<PointI64> {
@@ -216,6 +270,167 @@ fn find_endpoint_with_y(points: [PointI64; 4], y: i64) -> Option<PointI64> {
     points.into_iter().find(|p| p.y == y)
 }
 
+/// 线段与查询图形（直线/射线/矩形）的交集，端点为精确有理坐标。
+///
+/// 说明：`Segment` 的两端按原线段方向 `a -> b` 排列（`a != b`）；交集退化为单点时为 `Point`。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShapeIntersection {
+    Point(PointRat),
+    Segment(PointRat, PointRat),
+}
+
+/// 查询图形。
+///
+/// 说明：`Line` 为经过 `a`、`b` 的无限直线，`Ray` 为从 `origin` 出发、经过 `through` 的射线
+/// （两点须不同且坐标在 `±MAX_ABS_COORD` 内，否则不与任何线段相交）；`Box` 为闭矩形（含边界，可以退化为线段或点）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryShape {
+    Line { a: PointI64, b: PointI64 },
+    Ray { origin: PointI64, through: PointI64 },
+    Box(BBox),
+}
+
+/// 线段与经过 `p`、`q` 的直线的交集（线段在直线上时为整条线段）。
+///
+/// 说明：`p == q`（不确定直线）或坐标超出 `±MAX_ABS_COORD`（`orient` 会溢出）时返回 `None`。
+pub fn intersect_segment_line(s: &Segment, p: PointI64, q: PointI64) -> Option<ShapeIntersection> {
+    if p == q || !in_range(p) || !in_range(q) {
+        return None;
+    }
+    let (oa, ob) = (orient(p, q, s.a), orient(p, q, s.b));
+    if oa == 0 && ob == 0 {
+        return Some(whole(s));
+    }
+    if has_opposite_sign(oa, ob) || oa == 0 || ob == 0 {
+        let t = Rational::new(oa, oa - ob);
+        return Some(ShapeIntersection::Point(point_at(s, t)));
+    }
+    None
+}
+
+/// 线段与从 `origin` 出发、经过 `through` 的射线的交集（无效查询同 `intersect_segment_line`，返回 `None`）。
+pub fn intersect_segment_ray(
+    s: &Segment,
+    origin: PointI64,
+    through: PointI64,
+) -> Option<ShapeIntersection> {
+    let hit = intersect_segment_line(s, origin, through)?;
+    let (dx, dy) = (
+        (through.x as i128) - (origin.x as i128),
+        (through.y as i128) - (origin.y as i128),
+    );
+    // 沿射线方向的投影（只比较符号）。
+    let along = |p: PointI64| {
+        dx * ((p.x as i128) - (origin.x as i128)) + dy * ((p.y as i128) - (origin.y as i128))
+    };
+    match hit {
+        ShapeIntersection::Segment(..) => {
+            // 线段在射线所在直线上：按投影裁掉 `origin` 之前的部分。
+            let (sa, sb) = (along(s.a), along(s.b));
+            let o = PointRat::from_i64(origin);
+            match (sa >= 0, sb >= 0) {
+                (true, true) => Some(hit),
+                (false, false) => None,
+                (false, true) if sb == 0 => Some(ShapeIntersection::Point(o)),
+                (true, false) if sa == 0 => Some(ShapeIntersection::Point(o)),
+                (false, true) => Some(ShapeIntersection::Segment(o, PointRat::from_i64(s.b))),
+                (true, false) => Some(ShapeIntersection::Segment(PointRat::from_i64(s.a), o)),
+            }
+        }
+        ShapeIntersection::Point(_) => {
+            // 射线参数 `u = cross(a - origin, e) / cross(d, e)`（`e = b - a`，`d` 为射线方向），要求 `u >= 0`。
+            let (ex, ey) = (
+                (s.b.x as i128) - (s.a.x as i128),
+                (s.b.y as i128) - (s.a.y as i128),
+            );
+            let num = cross(
+                (s.a.x as i128) - (origin.x as i128),
+                (s.a.y as i128) - (origin.y as i128),
+                ex,
+                ey,
+            );
+            let den = cross(dx, dy, ex, ey);
+            (!has_opposite_sign(num, den)).then_some(hit)
+        }
+    }
+}
+
+/// 线段与闭矩形的交集（Liang–Barsky 裁剪，参数取精确有理数）。
+pub fn clip_segment_to_box(s: &Segment, bbox: &BBox) -> Option<ShapeIntersection> {
+    let mut t0 = Rational::from_int(0);
+    let mut t1 = Rational::from_int(1);
+    let axes = [
+        (s.a.x, s.b.x, bbox.min.x, bbox.max.x),
+        (s.a.y, s.b.y, bbox.min.y, bbox.max.y),
+    ];
+    for (a, b, lo, hi) in axes {
+        let d = (b as i128) - (a as i128);
+        if d == 0 {
+            if a < lo || a > hi {
+                return None;
+            }
+            continue;
+        }
+        // `a + t*d` 进入/离开 `[lo, hi]` 的参数。
+        let t_lo = Rational::new((lo as i128) - (a as i128), d);
+        let t_hi = Rational::new((hi as i128) - (a as i128), d);
+        let (enter, leave) = if d > 0 { (t_lo, t_hi) } else { (t_hi, t_lo) };
+        t0 = t0.max(enter);
+        t1 = t1.min(leave);
+    }
+    match t0.cmp(&t1) {
+        core::cmp::Ordering::Greater => None,
+        core::cmp::Ordering::Equal => Some(ShapeIntersection::Point(point_at(s, t0))),
+        core::cmp::Ordering::Less => {
+            Some(ShapeIntersection::Segment(point_at(s, t0), point_at(s, t1)))
+        }
+    }
+}
+
+/// 线段与任一查询图形的交集。
+pub fn intersect_segment_shape(s: &Segment, shape: &QueryShape) -> Option<ShapeIntersection> {
+    match *shape {
+        QueryShape::Line { a, b } => intersect_segment_line(s, a, b),
+        QueryShape::Ray { origin, through } => intersect_segment_ray(s, origin, through),
+        QueryShape::Box(bbox) => clip_segment_to_box(s, &bbox),
+    }
+}
+
+/// 批量版本：与 `shape` 有交集的全部线段及其交集（按 `SegmentId` 升序）。
+pub fn intersect_segments_with_shape(
+    segments: &Segments,
+    shape: &QueryShape,
+) -> Vec<(SegmentId, ShapeIntersection)> {
+    segments
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| intersect_segment_shape(s, shape).map(|hit| (SegmentId(i), hit)))
+        .collect()
+}
+
+/// 查询点是否在网格坐标范围内（与 `IntersectionIndex::add_segment` 的检查一致）。
+fn in_range(p: PointI64) -> bool {
+    p.x.unsigned_abs() <= MAX_ABS_COORD.unsigned_abs()
+        && p.y.unsigned_abs() <= MAX_ABS_COORD.unsigned_abs()
+}
+
+fn whole(s: &Segment) -> ShapeIntersection {
+    ShapeIntersection::Segment(PointRat::from_i64(s.a), PointRat::from_i64(s.b))
+}
+
+/// 线段上参数为 `t` 的点：`a + t * (b - a)`。
+fn point_at(s: &Segment, t: Rational) -> PointRat {
+    let (num, den) = (t.num(), t.den());
+    let coord = |a: i64, b: i64| {
+        let d = (b as i128) - (a as i128);
+        Rational::new((a as i128) * den + d * num, den)
+    };
+    PointRat {
+        x: coord(s.a.x, s.b.x),
+        y: coord(s.a.y, s.b.y),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,7 +521,10 @@ mod tests {
         let b = seg(5, 0, 15, 0, 1);
         assert_eq!(
             intersect_segments(&a, &b).unwrap(),
-            SegmentIntersection::CollinearOverlap
+            SegmentIntersection::CollinearOverlap {
+                a: PointI64 { x: 5, y: 0 },
+                b: PointI64 { x: 10, y: 0 },
+            }
         );
     }
 
@@ -332,7 +550,10 @@ mod tests {
         let b = seg(0, 5, 0, 15, 1);
         assert_eq!(
             intersect_segments(&a, &b).unwrap(),
-            SegmentIntersection::CollinearOverlap
+            SegmentIntersection::CollinearOverlap {
+                a: PointI64 { x: 0, y: 5 },
+                b: PointI64 { x: 0, y: 10 },
+            }
         );
     }
 
@@ -342,4 +563,121 @@ mod tests {
         let b = seg(0, 1, 10, 1, 1);
         assert!(intersect_segments(&a, &b).is_none());
     }
+
+    fn at(x: (i128, i128), y: (i128, i128)) -> PointRat {
+        PointRat {
+            x: Rational::new(x.0, x.1),
+            y: Rational::new(y.0, y.1),
+        }
+    }
+
+    fn pt(x: i64, y: i64) -> PointI64 {
+        PointI64 { x, y }
+    }
+
+    #[test]
+    fn intersects_lines_and_rays_exactly() {
+        let s = seg(0, 0, 3, 1, 0);
+        // 竖直扫描线 x = 1。
+        assert_eq!(
+            intersect_segment_line(&s, pt(1, 0), pt(1, 5)),
+            Some(ShapeIntersection::Point(at((1, 1), (1, 3))))
+        );
+        assert_eq!(intersect_segment_line(&s, pt(0, 2), pt(3, 3)), None);
+        assert_eq!(
+            intersect_segment_line(&s, pt(6, 2), pt(9, 3)),
+            Some(ShapeIntersection::Segment(
+                at((0, 1), (0, 1)),
+                at((3, 1), (1, 1))
+            ))
+        );
+
+        // 射线从 (2,-1) 向上：命中；反方向：不命中。
+        assert_eq!(
+            intersect_segment_ray(&s, pt(2, -1), pt(2, 0)),
+            Some(ShapeIntersection::Point(at((2, 1), (2, 3))))
+        );
+        assert_eq!(intersect_segment_ray(&s, pt(2, -1), pt(2, -2)), None);
+        // 射线起点在线段端点上。
+        assert_eq!(
+            intersect_segment_ray(&s, pt(3, 1), pt(4, 0)),
+            Some(ShapeIntersection::Point(at((3, 1), (1, 1))))
+        );
+        // 共线：只保留射线起点之后的部分（保持线段方向），起点恰在端点时退化为点。
+        assert_eq!(
+            intersect_segment_ray(&seg(0, 0, 6, 2, 0), pt(3, 1), pt(0, 0)),
+            Some(ShapeIntersection::Segment(
+                at((0, 1), (0, 1)),
+                at((3, 1), (1, 1))
+            ))
+        );
+        assert_eq!(
+            intersect_segment_ray(&seg(0, 0, 6, 2, 0), pt(6, 2), pt(9, 3)),
+            Some(ShapeIntersection::Point(at((6, 1), (2, 1))))
+        );
+        assert_eq!(
+            intersect_segment_ray(&seg(0, 0, 6, 2, 0), pt(7, 7), pt(8, 8)),
+            None
+        );
+
+        // 无效查询：两点重合，或坐标超出范围（否则 `orient` 溢出）。
+        assert_eq!(intersect_segment_line(&s, pt(1, 0), pt(1, 0)), None);
+        assert_eq!(intersect_segment_ray(&s, pt(1, 0), pt(1, 0)), None);
+        let far = pt(i64::MAX, 0);
+        assert_eq!(intersect_segment_line(&s, pt(1, 0), far), None);
+        assert_eq!(intersect_segment_ray(&s, far, pt(1, 0)), None);
+        assert_eq!(
+            intersect_segment_line(&s, pt(1, -MAX_ABS_COORD), pt(1, MAX_ABS_COORD)),
+            Some(ShapeIntersection::Point(at((1, 1), (1, 3))))
+        );
+    }
+
+    #[test]
+    fn clips_to_closed_boxes_and_batches_over_segments() {
+        let bbox = BBox::new(pt(0, 0), pt(4, 4));
+        assert_eq!(
+            clip_segment_to_box(&seg(-2, 1, 6, 3, 0), &bbox),
+            Some(ShapeIntersection::Segment(
+                at((0, 1), (3, 2)),
+                at((4, 1), (5, 2))
+            ))
+        );
+        // 内部经过角点 / 端点在角点上 / 整段在边界上 / 完全在外。
+        assert_eq!(
+            clip_segment_to_box(&seg(2, 6, 6, 2, 0), &bbox),
+            Some(ShapeIntersection::Point(at((4, 1), (4, 1))))
+        );
+        assert_eq!(
+            clip_segment_to_box(&seg(4, 4, 6, 8, 0), &bbox),
+            Some(ShapeIntersection::Point(at((4, 1), (4, 1))))
+        );
+        assert_eq!(
+            clip_segment_to_box(&seg(0, -1, 0, 9, 0), &bbox),
+            Some(ShapeIntersection::Segment(
+                at((0, 1), (0, 1)),
+                at((0, 1), (4, 1))
+            ))
+        );
+        assert_eq!(clip_segment_to_box(&seg(5, 0, 5, 4, 0), &bbox), None);
+
+        let mut segments = Segments::new();
+        segments.push(seg(0, 0, 3, 1, 0));
+        segments.push(seg(5, 0, 5, 4, 1));
+        segments.push(seg(-1, 2, 6, 2, 2));
+        let scanline = QueryShape::Line {
+            a: pt(0, 1),
+            b: pt(1, 1),
+        };
+        assert_eq!(
+            intersect_segments_with_shape(&segments, &scanline),
+            vec![
+                (SegmentId(0), ShapeIntersection::Point(at((3, 1), (1, 1)))),
+                (SegmentId(1), ShapeIntersection::Point(at((5, 1), (1, 1)))),
+            ]
+        );
+        assert_eq!(
+            intersect_segments_with_shape(&segments, &QueryShape::Box(bbox)).len(),
+            2
+        );
+    }
 }
@@ -1,3 +1,4 @@
+pub mod bbox;
 pub mod fixed;
 pub mod intersection;
 pub mod point;
@@ -1,8 +1,14 @@
+#[cfg(test)]
+use crate::geom::fixed::Coord;
 use crate::geom::fixed::PointI64;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SegmentId(pub usize);
 
+/// 线段所属图层（双色/多图层模式按图层过滤点交，见 `sweep::bo::enumerate_bichromatic_intersections`）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayerId(pub u32);
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SegmentKey {
     pub a: PointI64,
@@ -82,3 +88,23 @@ impl core::ops::Index<SegmentId> for Segments {
         self.get(index)
     }
 }
+
+/// 测试夹具：由端点坐标 `(ax, ay, bx, by)` 构建线段集合。
+///
+/// 说明：与预处理一致——端点按 `(x,y)` 字典序规范化，跳过零长度与重复线段，`source_index` 为输入下标。
+#[cfg(test)]
+pub(crate) fn segments_from(coords: &[(Coord, Coord, Coord, Coord)]) -> Segments {
+    let mut segments = Segments::new();
+    let mut seen = std::collections::BTreeSet::new();
+    for (i, &(ax, ay, bx, by)) in coords.iter().enumerate() {
+        let key = SegmentKey::new(PointI64 { x: ax, y: ay }, PointI64 { x: bx, y: by });
+        if key.a != key.b && seen.insert(key) {
+            segments.push(Segment {
+                a: key.a,
+                b: key.b,
+                source_index: i,
+            });
+        }
+    }
+    segments
+}
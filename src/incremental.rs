@@ -0,0 +1,430 @@
+//! 增量点交索引：逐条增删线段，只更新受影响的点组（编辑器场景，避免每次全量重跑扫描线）。
+//!
+//! 说明：
+//! - 点组语义与 `sweep::bo::enumerate_point_intersections` 完全一致：按点聚合所有“可报告”的线段对
+//!   （`intersect_segments` 给出的点交；共线重叠只在两端点重合处报告端点接触，见 `crate::naive`）；
+//! - 新线段只与包围盒相交的候选线段做精确求交，候选由分层网格（每条线段放在边长不小于其包围盒的层上，
+//!   至多占 4 个格子）给出；
+//! - `SegmentId` 按添加顺序分配、删除后不复用；`groups()` 按点升序输出，
+//!   与对当前线段全量重算（`recompute`）的结果逐条相同。
+
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::geom::bbox::BBox;
+use crate::geom::fixed::{Coord, MAX_ABS_COORD, PointI64};
+use crate::geom::intersection::{
+    PointIntersectionGroupRecord, SegmentIntersection, intersect_segments,
+};
+use crate::geom::point::PointRat;
+use crate::geom::segment::{Segment, SegmentId, SegmentKey, Segments};
+use crate::limits::Limits;
+use crate::sweep::bo::{BoError, enumerate_point_intersections_with_limits};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexError {
+    /// 端点坐标超出 `[-MAX_ABS_COORD, MAX_ABS_COORD]`。
+    CoordinateOutOfRange { point: PointI64 },
+    /// 两端点相同。
+    ZeroLength { point: PointI64 },
+    /// 与已有线段完全相同（端点无序比较）。
+    Duplicate { existing: SegmentId },
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexError::CoordinateOutOfRange { point } => write!(
+                f,
+                "端点 ({}, {}) 超出网格坐标范围 [-{}, {}]",
+                point.x, point.y, MAX_ABS_COORD, MAX_ABS_COORD
+            ),
+            IndexError::ZeroLength { point } => {
+                write!(f, "零长度线段 ({}, {})", point.x, point.y)
+            }
+            IndexError::Duplicate { existing } => {
+                write!(f, "与已有线段 #{} 重复", existing.0)
+            }
+        }
+    }
+}
+
+/// 可增删线段的点交索引。
+#[derive(Clone, Debug, Default)]
+pub struct IntersectionIndex {
+    /// 下标即 `SegmentId`；已删除的位置为 `None`。
+    slots: Vec<Option<Segment>>,
+    keys: BTreeMap<SegmentKey, SegmentId>,
+    grid: LevelGrid,
+    /// 每个点上的可报告线段对（`a < b`）；点组的线段集合即这些对的并。
+    pairs: BTreeMap<PointRat, BTreeSet<(SegmentId, SegmentId)>>,
+    /// 每条线段参与的点（删除时据此定位受影响的点组）。
+    points_of: Vec<BTreeSet<PointRat>>,
+}
+
+impl IntersectionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 依次添加 `segments` 中的全部线段（`SegmentId` 与输入下标一致）。
+    pub fn from_segments(segments: &Segments) -> Result<Self, IndexError> {
+        let mut index = Self::new();
+        for s in segments.iter() {
+            index.add_segment(s.a, s.b)?;
+        }
+        Ok(index)
+    }
+
+    /// 当前线段数（不含已删除的）。
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// 仍在索引中的线段（端点已规范化为 `a < b`，`source_index` 为其 `SegmentId`）。
+    pub fn segment(&self, id: SegmentId) -> Option<&Segment> {
+        self.slots.get(id.0).and_then(Option::as_ref)
+    }
+
+    /// 按 `SegmentId` 升序遍历仍在索引中的线段。
+    pub fn iter(&self) -> impl Iterator<Item = (SegmentId, &Segment)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.as_ref().map(|s| (SegmentId(i), s)))
+    }
+
+    /// 添加线段，返回新分配的 `SegmentId`，并返回受影响（新增或扩大）的点组所在的点（升序）。
+    pub fn add_segment(
+        &mut self,
+        a: PointI64,
+        b: PointI64,
+    ) -> Result<(SegmentId, Vec<PointRat>), IndexError> {
+        for p in [a, b] {
+            if p.x.unsigned_abs() > MAX_ABS_COORD.unsigned_abs()
+                || p.y.unsigned_abs() > MAX_ABS_COORD.unsigned_abs()
+            {
+                return Err(IndexError::CoordinateOutOfRange { point: p });
+            }
+        }
+        if a == b {
+            return Err(IndexError::ZeroLength { point: a });
+        }
+        let key = SegmentKey::new(a, b);
+        if let Some(&existing) = self.keys.get(&key) {
+            return Err(IndexError::Duplicate { existing });
+        }
+
+        let id = SegmentId(self.slots.len());
+        let seg = Segment {
+            a: key.a,
+            b: key.b,
+            source_index: id.0,
+        };
+        let bbox = BBox::of_segment(&seg);
+        let mut touched = BTreeSet::new();
+        for other in self.grid.query(bbox) {
+            let o = self.slots[other.0]
+                .as_ref()
+                .expect("网格中只有未删除的线段");
+            for point in reportable_points(o, &seg) {
+                self.pairs.entry(point).or_default().insert((other, id));
+                self.points_of[other.0].insert(point);
+                touched.insert(point);
+            }
+        }
+
+        self.slots.push(Some(seg));
+        self.keys.insert(key, id);
+        self.grid.insert(id, bbox);
+        self.points_of.push(touched.clone());
+        Ok((id, touched.into_iter().collect()))
+    }
+
+    /// 删除线段，返回被删除的线段（不存在时为 `None`）以及受影响（缩小或消失）的点组所在的点（升序）。
+    pub fn remove_segment(&mut self, id: SegmentId) -> Option<(Segment, Vec<PointRat>)> {
+        let seg = self.slots.get_mut(id.0)?.take()?;
+        self.keys.remove(&seg.key());
+        self.grid.remove(id, BBox::of_segment(&seg));
+
+        let touched = core::mem::take(&mut self.points_of[id.0]);
+        for point in &touched {
+            let Some(pairs) = self.pairs.get_mut(point) else {
+                continue;
+            };
+            let (gone, kept): (BTreeSet<_>, BTreeSet<_>) =
+                pairs.iter().partition(|&&(a, b)| a == id || b == id);
+            let still: BTreeSet<SegmentId> = kept.iter().flat_map(|&(a, b)| [a, b]).collect();
+            // 只和被删线段构成线段对的其他线段，也不再参与这个点。
+            for (a, b) in gone {
+                let other = if a == id { b } else { a };
+                if !still.contains(&other) {
+                    self.points_of[other.0].remove(point);
+                }
+            }
+            if kept.is_empty() {
+                self.pairs.remove(point);
+            } else {
+                *pairs = kept;
+            }
+        }
+        Some((seg, touched.into_iter().collect()))
+    }
+
+    /// `point` 处的点组（不存在时为 `None`）。
+    pub fn group_at(&self, point: PointRat) -> Option<PointIntersectionGroupRecord> {
+        self.pairs
+            .get(&point)
+            .map(|pairs| self.group_record(point, pairs))
+    }
+
+    /// 线段 `id` 参与的点组（按点升序）。
+    pub fn groups_of(&self, id: SegmentId) -> Vec<PointIntersectionGroupRecord> {
+        self.points_of
+            .get(id.0)
+            .into_iter()
+            .flatten()
+            .filter_map(|p| self.group_at(*p))
+            .collect()
+    }
+
+    /// 全部点组（按点升序）。
+    pub fn groups(&self) -> Vec<PointIntersectionGroupRecord> {
+        self.pairs
+            .iter()
+            .map(|(point, pairs)| self.group_record(*point, pairs))
+            .collect()
+    }
+
+    /// 对当前线段全量重跑扫描线（`SegmentId` 换回索引内的编号，按点升序），用于校验增量结果。
+    pub fn recompute(&self, limits: Limits) -> Result<Vec<PointIntersectionGroupRecord>, BoError> {
+        let mut segments = Segments::new();
+        let mut ids = Vec::new();
+        for (id, s) in self.iter() {
+            segments.push(*s);
+            ids.push(id);
+        }
+        let map = |v: &[SegmentId]| v.iter().map(|s| ids[s.0]).collect::<Vec<_>>();
+        let mut out: Vec<PointIntersectionGroupRecord> =
+            enumerate_point_intersections_with_limits(&segments, limits)?
+                .iter()
+                .map(|r| PointIntersectionGroupRecord {
+                    point: r.point,
+                    endpoint_segments: map(&r.endpoint_segments),
+                    interior_segments: map(&r.interior_segments),
+                })
+                .collect();
+        out.sort_by_key(|r| r.point);
+        Ok(out)
+    }
+
+    fn group_record(
+        &self,
+        point: PointRat,
+        pairs: &BTreeSet<(SegmentId, SegmentId)>,
+    ) -> PointIntersectionGroupRecord {
+        let ids: BTreeSet<SegmentId> = pairs.iter().flat_map(|&(a, b)| [a, b]).collect();
+        let mut endpoint_segments = Vec::new();
+        let mut interior_segments = Vec::new();
+        for id in ids {
+            let seg = self.slots[id.0].as_ref().expect("点组中只有未删除的线段");
+            if point == PointRat::from_i64(seg.a) || point == PointRat::from_i64(seg.b) {
+                endpoint_segments.push(id);
+            } else {
+                interior_segments.push(id);
+            }
+        }
+        PointIntersectionGroupRecord {
+            point,
+            endpoint_segments,
+            interior_segments,
+        }
+    }
+}
+
+/// 一对线段的可报告点：点交本身；共线重叠只取两者重合的端点。
+fn reportable_points(a: &Segment, b: &Segment) -> Vec<PointRat> {
+    match intersect_segments(a, b) {
+        None => Vec::new(),
+        Some(SegmentIntersection::Point { point, .. }) => vec![point],
+        Some(SegmentIntersection::CollinearOverlap { .. }) => [a.a, a.b]
+            .into_iter()
+            .filter(|p| *p == b.a || *p == b.b)
+            .map(PointRat::from_i64)
+            .collect(),
+    }
+}
+
+/// 坐标跨度不超过 `2 * MAX_ABS_COORD < 2^31`，32 层足够。
+const GRID_LEVELS: usize = 32;
+
+/// 一层网格：格子坐标 -> 落在该格的线段及其包围盒。
+type GridLevel = BTreeMap<(Coord, Coord), Vec<(SegmentId, BBox)>>;
+
+/// 分层网格：第 `l` 层格子边长 `2^l`，包围盒最大边长为 `e` 的线段放在满足 `2^l >= e` 的最低层，
+/// 因此每个轴上至多跨 2 个格子。
+#[derive(Clone, Debug)]
+struct LevelGrid {
+    levels: Vec<GridLevel>,
+    counts: Vec<usize>,
+}
+
+impl Default for LevelGrid {
+    fn default() -> Self {
+        Self {
+            levels: vec![BTreeMap::new(); GRID_LEVELS],
+            counts: vec![0; GRID_LEVELS],
+        }
+    }
+}
+
+impl LevelGrid {
+    fn level_of(bbox: BBox) -> usize {
+        let extent = (bbox.max.x - bbox.min.x)
+            .max(bbox.max.y - bbox.min.y)
+            .max(1) as u64;
+        (u64::BITS - (extent - 1).leading_zeros()) as usize
+    }
+
+    fn cells(bbox: BBox, level: usize) -> impl Iterator<Item = (Coord, Coord)> {
+        // 算术右移即向下取整。
+        let (x0, x1) = (bbox.min.x >> level, bbox.max.x >> level);
+        let (y0, y1) = (bbox.min.y >> level, bbox.max.y >> level);
+        (x0..=x1).flat_map(move |x| (y0..=y1).map(move |y| (x, y)))
+    }
+
+    fn insert(&mut self, id: SegmentId, bbox: BBox) {
+        let level = Self::level_of(bbox);
+        for cell in Self::cells(bbox, level) {
+            self.levels[level].entry(cell).or_default().push((id, bbox));
+        }
+        self.counts[level] += 1;
+    }
+
+    fn remove(&mut self, id: SegmentId, bbox: BBox) {
+        let level = Self::level_of(bbox);
+        for cell in Self::cells(bbox, level) {
+            if let Some(entries) = self.levels[level].get_mut(&cell) {
+                entries.retain(|(e, _)| *e != id);
+                if entries.is_empty() {
+                    self.levels[level].remove(&cell);
+                }
+            }
+        }
+        self.counts[level] -= 1;
+    }
+
+    /// 包围盒与 `bbox` 相交的线段（升序、去重）。
+    fn query(&self, bbox: BBox) -> BTreeSet<SegmentId> {
+        let mut out = BTreeSet::new();
+        for (level, cells) in self.levels.iter().enumerate() {
+            if self.counts[level] == 0 {
+                continue;
+            }
+            let span = |lo: Coord, hi: Coord| ((hi >> level) - (lo >> level) + 1) as u128;
+            let probes = span(bbox.min.x, bbox.max.x) * span(bbox.min.y, bbox.max.y);
+            let mut visit = |entries: &Vec<(SegmentId, BBox)>| {
+                for (id, b) in entries {
+                    if b.intersects(&bbox) {
+                        out.insert(*id);
+                    }
+                }
+            };
+            // 查询框远大于该层格子时，逐格探测不如直接扫描该层的全部条目。
+            if probes > cells.len() as u128 {
+                cells.values().for_each(&mut visit);
+            } else {
+                for cell in Self::cells(bbox, level) {
+                    if let Some(entries) = cells.get(&cell) {
+                        visit(entries);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz::XorShift64;
+    use crate::rational::Rational;
+
+    fn p(x: Coord, y: Coord) -> PointI64 {
+        PointI64 { x, y }
+    }
+
+    #[test]
+    fn add_and_remove_update_only_touched_groups() {
+        let mut index = IntersectionIndex::new();
+        let (a, _) = index.add_segment(p(0, 0), p(4, 4)).unwrap();
+        let (b, touched) = index.add_segment(p(0, 4), p(4, 0)).unwrap();
+        let center = PointRat {
+            x: Rational::from_int(2),
+            y: Rational::from_int(2),
+        };
+        assert_eq!(touched, vec![center]);
+
+        // 竖线经过交点并在 (2,4) 处结束：(2,2) 的点组扩大为三条线段。
+        let (v, touched) = index.add_segment(p(2, 4), p(2, -1)).unwrap();
+        assert_eq!(touched, vec![center]);
+        assert_eq!(
+            index.group_at(center).unwrap().interior_segments,
+            vec![a, b, v]
+        );
+        // 与 `b` 共线、端点落在 `b` 内部：不产生点组（与扫描线一致）。
+        let (c, touched) = index.add_segment(p(3, 1), p(6, -2)).unwrap();
+        assert!(touched.is_empty());
+        assert_eq!(index.groups_of(c), vec![]);
+
+        assert_eq!(
+            index.add_segment(p(4, 4), p(0, 0)),
+            Err(IndexError::Duplicate { existing: a })
+        );
+        assert_eq!(
+            index.add_segment(p(1, 1), p(1, 1)),
+            Err(IndexError::ZeroLength { point: p(1, 1) })
+        );
+
+        let (_, touched) = index.remove_segment(a).unwrap();
+        assert_eq!(touched, vec![center]);
+        assert_eq!(
+            index.group_at(center).unwrap().interior_segments,
+            vec![b, v]
+        );
+        index.remove_segment(v).unwrap();
+        assert_eq!(index.group_at(center), None);
+        assert!(index.groups().is_empty());
+        assert_eq!(index.remove_segment(v), None);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn random_edits_match_full_recompute() {
+        let mut rng = XorShift64::new(22);
+        for _ in 0..40 {
+            let mut index = IntersectionIndex::new();
+            // 混合小网格（大量退化）与大跨度线段（落在不同网格层）。
+            let grid = rng.range(3, 12);
+            for _ in 0..60 {
+                let live: Vec<SegmentId> = index.iter().map(|(id, _)| id).collect();
+                if !live.is_empty() && rng.below(3) == 0 {
+                    let id = live[rng.below(live.len() as u64) as usize];
+                    assert!(index.remove_segment(id).is_some());
+                } else {
+                    let scale = if rng.below(4) == 0 { 1 << 20 } else { 1 };
+                    let mut q = || rng.range(-grid, grid) * scale;
+                    let (a, b) = (p(q(), q()), p(q(), q()));
+                    // 零长度与重复输入被拒绝，不改变索引。
+                    let _ = index.add_segment(a, b);
+                }
+                assert_eq!(index.groups(), index.recompute(Limits::default()).unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,510 @@
+//! 最小 JSON 解析器（无依赖）：用于读回 `trace.v2` / `session.v2`，以及读取命令行工具的 JSON 输入。
+//!
+//! 约定：
+//! - 每个值都记录起始位置（行/列，均从 1 开始；列按字符计），便于报出精确的错误位置；
+//! - 数字保留原始文本，由调用方按需要解析为整数或 `f64`（本项目输出不含浮点数）；
+//! - 对象保留字段顺序，重复字段视为错误。
+
+use core::fmt;
+
+/// 嵌套深度上限：session/trace 的实际嵌套不超过 10 层，这里留足余量并避免递归栈溢出。
+const MAX_DEPTH: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JsonPos {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for JsonPos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第 {} 行第 {} 列", self.line, self.column)
+    }
+}
+
+/// JSON 语法错误或 schema 校验错误（包含位置与字段路径）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonError {
+    pub pos: JsonPos,
+    /// 出错值的字段路径（例如 `$.trace.steps[3].sweep_x.den`）。
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}（{}）：{}", self.pos, self.path, self.message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonNode>),
+    Object(Vec<(String, JsonNode)>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonNode {
+    pub pos: JsonPos,
+    pub value: JsonValue,
+}
+
+impl JsonNode {
+    pub fn error(&self, path: &str, message: impl Into<String>) -> JsonError {
+        JsonError {
+            pos: self.pos,
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self.value, JsonValue::Null)
+    }
+
+    pub fn as_object(&self, path: &str) -> Result<&[(String, JsonNode)], JsonError> {
+        match &self.value {
+            JsonValue::Object(fields) => Ok(fields),
+            _ => Err(self.error(path, "不是对象")),
+        }
+    }
+
+    /// 读取对象字段；缺少字段时报告对象本身的位置。
+    pub fn get(&self, key: &str, path: &str) -> Result<&JsonNode, JsonError> {
+        self.as_object(path)?
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .ok_or_else(|| self.error(path, format!("缺少字段 `{key}`")))
+    }
+
+    /// 读取可选的对象字段（字段不存在时返回 `None`）。
+    pub fn get_opt(&self, key: &str, path: &str) -> Result<Option<&JsonNode>, JsonError> {
+        Ok(self
+            .as_object(path)?
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v))
+    }
+
+    pub fn as_array(&self, path: &str) -> Result<&[JsonNode], JsonError> {
+        match &self.value {
+            JsonValue::Array(items) => Ok(items),
+            _ => Err(self.error(path, "不是数组")),
+        }
+    }
+
+    pub fn as_str(&self, path: &str) -> Result<&str, JsonError> {
+        match &self.value {
+            JsonValue::String(s) => Ok(s),
+            _ => Err(self.error(path, "不是字符串")),
+        }
+    }
+
+    pub fn as_usize(&self, path: &str) -> Result<usize, JsonError> {
+        match &self.value {
+            JsonValue::Number(n) => n
+                .parse::<usize>()
+                .map_err(|_| self.error(path, format!("不是非负整数：{n}"))),
+            _ => Err(self.error(path, "不是数字")),
+        }
+    }
+
+    pub fn as_i64(&self, path: &str) -> Result<i64, JsonError> {
+        match &self.value {
+            JsonValue::Number(n) => n
+                .parse::<i64>()
+                .map_err(|_| self.error(path, format!("不是 i64 整数：{n}"))),
+            _ => Err(self.error(path, "不是数字")),
+        }
+    }
+
+    pub fn as_f64(&self, path: &str) -> Result<f64, JsonError> {
+        match &self.value {
+            JsonValue::Number(n) => n
+                .parse::<f64>()
+                .map_err(|_| self.error(path, format!("不是有效的数字：{n}"))),
+            _ => Err(self.error(path, "不是数字")),
+        }
+    }
+
+    /// 读取“整数文本”（字符串形式的 i128，用于 `Rational` 的 `num/den` 与 `fixed.scale`）。
+    pub fn as_i128_text(&self, path: &str) -> Result<i128, JsonError> {
+        let s = self.as_str(path)?;
+        s.parse::<i128>()
+            .map_err(|_| self.error(path, format!("不是 i128 整数文本：{s:?}")))
+    }
+}
+
+/// 解析完整的 JSON 文本（允许首尾空白，不允许尾随内容）。
+pub fn parse_json(text: &str) -> Result<JsonNode, JsonError> {
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        index: 0,
+        line: 1,
+        column: 1,
+    };
+    parser.skip_whitespace();
+    let node = parser.parse_value(0)?;
+    parser.skip_whitespace();
+    if parser.index != parser.bytes.len() {
+        return Err(parser.error_here("JSON 值之后存在多余内容"));
+    }
+    Ok(node)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Parser<'_> {
+    fn pos(&self) -> JsonPos {
+        JsonPos {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn error_at(&self, pos: JsonPos, message: impl Into<String>) -> JsonError {
+        JsonError {
+            pos,
+            path: "$".to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn error_here(&self, message: impl Into<String>) -> JsonError {
+        self.error_at(self.pos(), message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.index).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.index += 1;
+        if b == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if b & 0xC0 != 0x80 {
+            // 只在 UTF-8 首字节处推进列号：列按字符计。
+            self.column += 1;
+        }
+        Some(b)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.bump();
+        }
+    }
+
+    fn expect_byte(&mut self, expected: u8) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(b) if b == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(b) => Err(self.error_here(format!(
+                "期望 `{}`，实际为 `{}`",
+                expected as char,
+                describe_byte(b)
+            ))),
+            None => Err(self.error_here(format!("期望 `{}`，实际已到文本末尾", expected as char))),
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<JsonNode, JsonError> {
+        let pos = self.pos();
+        if depth > MAX_DEPTH {
+            return Err(self.error_at(pos, format!("嵌套深度超过 {MAX_DEPTH}")));
+        }
+        let value = match self.peek() {
+            None => return Err(self.error_here("意外的文本末尾")),
+            Some(b'{') => self.parse_object(depth)?,
+            Some(b'[') => self.parse_array(depth)?,
+            Some(b'"') => JsonValue::String(self.parse_string()?),
+            Some(b't') => {
+                self.parse_literal("true")?;
+                JsonValue::Bool(true)
+            }
+            Some(b'f') => {
+                self.parse_literal("false")?;
+                JsonValue::Bool(false)
+            }
+            Some(b'n') => {
+                self.parse_literal("null")?;
+                JsonValue::Null
+            }
+            Some(b'-' | b'0'..=b'9') => JsonValue::Number(self.parse_number()?),
+            Some(b) => {
+                return Err(self.error_here(format!("意外的字符 `{}`", describe_byte(b))));
+            }
+        };
+        Ok(JsonNode { pos, value })
+    }
+
+    fn parse_literal(&mut self, literal: &str) -> Result<(), JsonError> {
+        let pos = self.pos();
+        for &expected in literal.as_bytes() {
+            if self.peek() != Some(expected) {
+                return Err(self.error_at(pos, format!("无效的字面量（期望 `{literal}`）")));
+            }
+            self.bump();
+        }
+        Ok(())
+    }
+
+    fn parse_number(&mut self) -> Result<String, JsonError> {
+        let pos = self.pos();
+        let start = self.index;
+        if self.peek() == Some(b'-') {
+            self.bump();
+        }
+        match self.peek() {
+            Some(b'0') => {
+                self.bump();
+            }
+            Some(b'1'..=b'9') => {
+                while let Some(b'0'..=b'9') = self.peek() {
+                    self.bump();
+                }
+            }
+            _ => return Err(self.error_at(pos, "无效的数字")),
+        }
+        if self.peek() == Some(b'.') {
+            self.bump();
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error_at(pos, "无效的数字：小数点后缺少数字"));
+            }
+            while let Some(b'0'..=b'9') = self.peek() {
+                self.bump();
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.bump();
+            if let Some(b'+' | b'-') = self.peek() {
+                self.bump();
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error_at(pos, "无效的数字：指数缺少数字"));
+            }
+            while let Some(b'0'..=b'9') = self.peek() {
+                self.bump();
+            }
+        }
+        // 数字只包含 ASCII，切片必然落在字符边界上。
+        Ok(String::from_utf8_lossy(&self.bytes[start..self.index]).into_owned())
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect_byte(b'"')?;
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            let pos = self.pos();
+            let Some(b) = self.bump() else {
+                return Err(self.error_here("字符串未闭合"));
+            };
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let Some(e) = self.bump() else {
+                        return Err(self.error_here("字符串未闭合"));
+                    };
+                    match e {
+                        b'"' => out.push(b'"'),
+                        b'\\' => out.push(b'\\'),
+                        b'/' => out.push(b'/'),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0C),
+                        b'n' => out.push(b'\n'),
+                        b'r' => out.push(b'\r'),
+                        b't' => out.push(b'\t'),
+                        b'u' => {
+                            let c = self.parse_unicode_escape(pos)?;
+                            let mut buf = [0_u8; 4];
+                            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                        }
+                        other => {
+                            return Err(self.error_at(
+                                pos,
+                                format!("无效的转义序列 `\\{}`", describe_byte(other)),
+                            ));
+                        }
+                    }
+                }
+                0x00..=0x1F => {
+                    return Err(self.error_at(pos, "字符串中包含未转义的控制字符"));
+                }
+                _ => out.push(b),
+            }
+        }
+        // 输入来自 `&str`，且转义只会产生合法的 UTF-8。
+        String::from_utf8(out).map_err(|_| self.error_here("字符串不是合法的 UTF-8"))
+    }
+
+    fn parse_hex4(&mut self, pos: JsonPos) -> Result<u32, JsonError> {
+        let mut v = 0_u32;
+        for _ in 0..4 {
+            let digit = match self.bump() {
+                Some(b @ b'0'..=b'9') => (b - b'0') as u32,
+                Some(b @ b'a'..=b'f') => (b - b'a' + 10) as u32,
+                Some(b @ b'A'..=b'F') => (b - b'A' + 10) as u32,
+                _ => return Err(self.error_at(pos, "无效的 \\u 转义：需要 4 位十六进制数")),
+            };
+            v = (v << 4) | digit;
+        }
+        Ok(v)
+    }
+
+    fn parse_unicode_escape(&mut self, pos: JsonPos) -> Result<char, JsonError> {
+        let hi = self.parse_hex4(pos)?;
+        let code = if (0xD800..0xDC00).contains(&hi) {
+            if self.bump() != Some(b'\\') || self.bump() != Some(b'u') {
+                return Err(self.error_at(pos, "无效的 \\u 转义：高代理项后缺少低代理项"));
+            }
+            let lo = self.parse_hex4(pos)?;
+            if !(0xDC00..0xE000).contains(&lo) {
+                return Err(self.error_at(pos, "无效的 \\u 转义：低代理项越界"));
+            }
+            0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00)
+        } else {
+            hi
+        };
+        char::from_u32(code).ok_or_else(|| self.error_at(pos, "无效的 \\u 转义：不是合法字符"))
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        self.expect_byte(b'[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.bump();
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.parse_value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.bump();
+                }
+                Some(b']') => {
+                    self.bump();
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(self.error_here("数组中期望 `,` 或 `]`")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<JsonValue, JsonError> {
+        self.expect_byte(b'{')?;
+        let mut fields: Vec<(String, JsonNode)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.bump();
+            return Ok(JsonValue::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key_pos = self.pos();
+            if self.peek() != Some(b'"') {
+                return Err(self.error_here("对象中期望字符串键"));
+            }
+            let key = self.parse_string()?;
+            if fields.iter().any(|(k, _)| *k == key) {
+                return Err(self.error_at(key_pos, format!("重复的字段 `{key}`")));
+            }
+            self.skip_whitespace();
+            self.expect_byte(b':')?;
+            self.skip_whitespace();
+            let value = self.parse_value(depth + 1)?;
+            fields.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.bump();
+                }
+                Some(b'}') => {
+                    self.bump();
+                    return Ok(JsonValue::Object(fields));
+                }
+                _ => return Err(self.error_here("对象中期望 `,` 或 `}`")),
+            }
+        }
+    }
+}
+
+fn describe_byte(b: u8) -> String {
+    if b.is_ascii_graphic() || b == b' ' {
+        (b as char).to_string()
+    } else {
+        format!("0x{b:02x}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_values_with_positions() {
+        let node =
+            parse_json("{\"a\": [1, -2, \"x\\u00e9\\n\"],\n \"b\": null, \"c\": true}").unwrap();
+        let a = node.get("a", "$").unwrap().as_array("$.a").unwrap();
+        assert_eq!(a[0].as_usize("$.a[0]").unwrap(), 1);
+        assert_eq!(a[1].as_i64("$.a[1]").unwrap(), -2);
+        assert_eq!(a[2].as_str("$.a[2]").unwrap(), "xé\n");
+        assert!(node.get("b", "$").unwrap().is_null());
+
+        let c = node.get("c", "$").unwrap();
+        assert_eq!(c.value, JsonValue::Bool(true));
+        assert_eq!(
+            c.pos,
+            JsonPos {
+                line: 2,
+                column: 18
+            }
+        );
+    }
+
+    #[test]
+    fn reports_syntax_error_position() {
+        let err = parse_json("{\n  \"a\": [1,,2]\n}").unwrap_err();
+        assert_eq!(
+            err.pos,
+            JsonPos {
+                line: 2,
+                column: 11
+            }
+        );
+
+        let err = parse_json("[\"中文\" x]").unwrap_err();
+        assert_eq!(err.pos, JsonPos { line: 1, column: 7 });
+        assert!(err.message.contains("`,` 或 `]`"));
+
+        let err = parse_json("{} 1").unwrap_err();
+        assert_eq!(err.pos, JsonPos { line: 1, column: 4 });
+    }
+
+    #[test]
+    fn rejects_duplicate_keys_and_missing_fields() {
+        let err = parse_json("{\"a\":1,\"a\":2}").unwrap_err();
+        assert_eq!(err.pos, JsonPos { line: 1, column: 8 });
+
+        let node = parse_json("{\"a\":1}").unwrap();
+        let err = node.get("b", "$").unwrap_err();
+        assert_eq!(err.path, "$");
+        assert!(err.message.contains("缺少字段 `b`"));
+    }
+}
@@ -1,14 +1,39 @@
+pub mod arrangement;
+pub mod atomic;
+mod bigint;
+pub mod boolean;
+pub mod chain;
+#[cfg(any(test, feature = "fuzz"))]
+pub mod fuzz;
 pub mod geom;
+pub mod incremental;
+pub mod json;
 pub mod limits;
+pub mod locate;
+pub mod mapping;
+pub mod naive;
+pub mod noding;
+pub mod overlap;
 pub mod preprocess;
 pub mod rational;
 pub mod run;
 pub mod session;
+pub mod snap;
+pub mod spatial;
 pub mod sweep;
 pub mod trace;
 
+pub use geom::fixed::Quantization;
 pub use preprocess::{
-    InputCoord, InputSegmentF64, PreprocessOutput, Warning, WarningKind, preprocess_segments,
+    InputCoord, InputSegmentF64, InputSegmentI64, PreprocessOutput, QuantizationMode, Warning,
+    WarningKind, fit_quantization, preprocess_segments, preprocess_segments_i64,
+    preprocess_segments_with_quantization,
 };
 pub use rational::Rational;
-pub use session::{SESSION_SCHEMA, session_v2_to_json_string, session_v2_to_json_string_limited};
+pub use session::{
+    SESSION_SCHEMA, SESSION_V3_SCHEMA, SessionV2, session_v2_from_json_str,
+    session_v2_to_json_string, session_v2_to_json_string_limited,
+    session_v2_to_json_string_with_quantization,
+    session_v2_to_json_string_with_quantization_limited, session_v3_to_json_string,
+    session_v3_to_json_string_limited,
+};
@@ -13,7 +13,7 @@ pub struct Limits {
     pub max_trace_steps: usize,
     /// 所有 trace step 的 `active.len()` 之和的上限。
     pub max_trace_active_entries_total: usize,
-    /// Phase 1 点交输出（按点聚合）的最大条目数。
+    /// Phase 1 点交输出（按点聚合）的最大条目数（只约束收集结果的接口，流式 `*_into` 接口不受限）。
     pub max_intersections: usize,
 }
 
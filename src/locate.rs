@@ -0,0 +1,679 @@
+//! 离线竖直射线查询与点定位：对完成的切分结果按顶点 x 建持久化 Treap 快照（每个 x 一个版本，路径复制），
+//! 回答任意点“正上方/正下方是哪条线段”，并在平面剖分上做面定位与多边形内外判定。
+//!
+//! 约定：
+//! - 线段先按 `crate::noding` 切成互不交叉的子段，相邻两个顶点 x 之间（开区间）子段的上下顺序固定，
+//!   版本 `x_i` 即扫描线在 `x_i + ε` 处的状态结构（比较器同 `cmp_segments_at_x_plus_epsilon`）；
+//! - 查询点为整数网格点（`|x|,|y| <= MAX_ABS_COORD`），子段上的 y 用原线段的 `y_at_x` 精确计算；
+//! - 射线为闭的：线段经过查询点本身也算命中；竖直线段在同一 x 上与射线重叠时，命中点为重叠部分离查询点最近的一端；
+//! - 构建 `O(n log n)` 期望时间与空间，单次查询 `O(log n + k)`（`k` 为同一命中点上的线段数）。
+
+use core::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::arrangement::{
+    Arrangement, FaceId, HalfEdgeId, VertexId, build_arrangement_from_segments,
+};
+use crate::boolean::{BooleanError, BooleanOp, PolygonI64, classify_faces, collect_ring_edges};
+use crate::geom::fixed::PointI64;
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::point::PointRat;
+use crate::geom::segment::{SegmentId, Segments};
+use crate::limits::Limits;
+use crate::noding::node_segments;
+use crate::rational::Rational;
+use crate::sweep::bo::{BoError, enumerate_point_intersections_with_limits};
+use crate::sweep::segment_order::{cmp_segments_at_x_plus_epsilon, y_at_x};
+
+/// 竖直射线的命中结果：最近的命中点 y，以及经过该点的全部线段（升序、去重）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RayHit {
+    pub y: Rational,
+    pub segments: Vec<SegmentId>,
+}
+
+/// 对一组线段回答“正上方/正下方是哪条线段”。
+#[derive(Clone, Debug)]
+pub struct SegmentLocator {
+    slabs: SlabMap,
+}
+
+impl SegmentLocator {
+    /// 先跑扫描线得到点交，再建索引。
+    pub fn from_segments(segments: &Segments, limits: Limits) -> Result<Self, BoError> {
+        let records = enumerate_point_intersections_with_limits(segments, limits)?;
+        Ok(Self::from_records(segments, &records))
+    }
+
+    /// 由完整的点交结果建索引（`records` 应为 `segments` 的完整点交结果）。
+    pub fn from_records(segments: &Segments, records: &[PointIntersectionGroupRecord]) -> Self {
+        let pieces = node_segments(segments, records)
+            .iter()
+            .flat_map(|n| {
+                n.pieces().into_iter().map(move |p| Piece {
+                    segment: n.segment,
+                    key: n.segment.0,
+                    a: p.a,
+                    b: p.b,
+                })
+            })
+            .collect();
+        Self {
+            slabs: SlabMap::build(segments.clone(), pieces),
+        }
+    }
+
+    /// 从 `p` 向上的射线最先碰到的线段（`y >= p.y` 中最低的命中点）。
+    pub fn above(&self, p: PointI64) -> Option<RayHit> {
+        self.hit(p, Direction::Up)
+    }
+
+    /// 从 `p` 向下的射线最先碰到的线段（`y <= p.y` 中最高的命中点）。
+    pub fn below(&self, p: PointI64) -> Option<RayHit> {
+        self.hit(p, Direction::Down)
+    }
+
+    fn hit(&self, p: PointI64, dir: Direction) -> Option<RayHit> {
+        let (y, keys) = self.slabs.shoot(PointRat::from_i64(p), dir)?;
+        let segments: BTreeSet<SegmentId> = keys.into_iter().map(SegmentId).collect();
+        Some(RayHit {
+            y,
+            segments: segments.into_iter().collect(),
+        })
+    }
+}
+
+/// 点在平面剖分中的位置。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Location {
+    Vertex(VertexId),
+    /// 点在边的内部（给出沿线段方向的半边 `2k`）。
+    Edge(HalfEdgeId),
+    Face(FaceId),
+}
+
+/// 平面剖分上的点定位。
+#[derive(Clone, Debug)]
+pub struct ArrangementLocator {
+    slabs: SlabMap,
+    vertices: Vec<PointRat>,
+    /// 第 `k` 条边正下方的面（反向半边 `2k+1` 左侧的面；竖直边不使用）。
+    face_below: Vec<FaceId>,
+}
+
+impl ArrangementLocator {
+    /// `segments` 为构建 `arr` 时使用的线段（即 `HalfEdge.segment` 所指的线段集合）。
+    pub fn new(arr: &Arrangement, segments: &Segments) -> Self {
+        let pieces = (0..arr.edge_count())
+            .map(|k| {
+                let h = HalfEdgeId(2 * k);
+                Piece {
+                    segment: arr.half_edge(h).segment,
+                    key: k,
+                    a: arr.vertex(arr.half_edge(h).origin).point,
+                    b: arr.vertex(arr.target(h)).point,
+                }
+            })
+            .collect();
+        Self {
+            slabs: SlabMap::build(segments.clone(), pieces),
+            vertices: arr.vertices.iter().map(|v| v.point).collect(),
+            face_below: (0..arr.edge_count())
+                .map(|k| arr.half_edge(HalfEdgeId(2 * k + 1)).face)
+                .collect(),
+        }
+    }
+
+    pub fn locate(&self, p: PointI64) -> Location {
+        let q = PointRat::from_i64(p);
+        if let Ok(v) = self.vertices.binary_search(&q) {
+            return Location::Vertex(VertexId(v));
+        }
+        // 不是顶点时，射线命中点恰为 `p` 只可能是 `p` 在某条边的内部（边互不重叠，至多一条）。
+        if let Some((y, keys)) = self.slabs.shoot(q, Direction::Up)
+            && y == q.y
+        {
+            return Location::Edge(HalfEdgeId(2 * keys[0]));
+        }
+        // `p` 与 `p + (ε, 0)` 同属一个面；后者正上方的第一条边的下侧即为所求（没有边则在无界面）。
+        match self.slabs.first_strictly_above(q) {
+            Some(piece) => Location::Face(self.face_below[self.slabs.pieces[piece].key]),
+            None => Location::Face(Arrangement::UNBOUNDED_FACE),
+        }
+    }
+}
+
+/// 点与多边形（奇偶规则）的位置关系。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Outside,
+    /// 点在内外分界上（两侧内外不同的边或顶点）。
+    Boundary,
+}
+
+/// 多边形内外判定：环边构成平面剖分后按奇偶规则标记面（与 `crate::boolean` 相同的语义）。
+#[derive(Clone, Debug)]
+pub struct PolygonLocator {
+    arrangement: Arrangement,
+    locator: ArrangementLocator,
+    inside: Vec<bool>,
+}
+
+impl PolygonLocator {
+    pub fn new(polygons: &[PolygonI64], limits: Limits) -> Result<Self, BooleanError> {
+        let (segments, toggles) = collect_ring_edges(polygons, &[])?;
+        let (atomic, arrangement) = build_arrangement_from_segments(&segments, limits)?;
+        let inside = classify_faces(&atomic, &arrangement, &toggles, BooleanOp::Union);
+        let locator = ArrangementLocator::new(&arrangement, &atomic.segments);
+        Ok(Self {
+            arrangement,
+            locator,
+            inside,
+        })
+    }
+
+    pub fn contains(&self, p: PointI64) -> Containment {
+        let arr = &self.arrangement;
+        let state = |faces: &mut dyn Iterator<Item = FaceId>| {
+            let states: BTreeSet<bool> = faces.map(|f| self.inside[f.0]).collect();
+            match states.into_iter().collect::<Vec<_>>()[..] {
+                [true] => Containment::Inside,
+                [false] => Containment::Outside,
+                _ => Containment::Boundary,
+            }
+        };
+        match self.locator.locate(p) {
+            Location::Face(f) => state(&mut [f].into_iter()),
+            Location::Edge(h) => state(
+                &mut [h, arr.half_edge(h).twin]
+                    .map(|h| arr.half_edge(h).face)
+                    .into_iter(),
+            ),
+            Location::Vertex(v) => state(
+                &mut arr
+                    .vertex(v)
+                    .outgoing
+                    .iter()
+                    .map(|&h| arr.half_edge(h).face),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+}
+
+/// 互不交叉的子段（`a < b`）：几何取自所在的整数线段 `segment`，`key` 为调用方的编号。
+#[derive(Clone, Copy, Debug)]
+struct Piece {
+    segment: SegmentId,
+    key: usize,
+    a: PointRat,
+    b: PointRat,
+}
+
+const NIL: u32 = u32::MAX;
+
+/// 持久化 Treap 节点（创建后不再修改，版本之间共享未改动的子树）。
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    piece: u32,
+    left: u32,
+    right: u32,
+}
+
+#[derive(Clone, Debug)]
+struct SlabMap {
+    segments: Segments,
+    pieces: Vec<Piece>,
+    nodes: Vec<Node>,
+    /// `(x_i, 根)`：`x_i + ε` 处的非竖直子段，按 x 升序。
+    versions: Vec<(Rational, u32)>,
+    /// 在 `x` 处结束的非竖直子段（版本 `x` 中已不包含它们）。
+    right_ends: BTreeMap<Rational, Vec<usize>>,
+    /// 位于 `x` 的竖直子段。
+    verticals: BTreeMap<Rational, Vec<usize>>,
+}
+
+impl SlabMap {
+    fn build(segments: Segments, pieces: Vec<Piece>) -> Self {
+        let mut starts: BTreeMap<Rational, Vec<usize>> = BTreeMap::new();
+        let mut right_ends: BTreeMap<Rational, Vec<usize>> = BTreeMap::new();
+        let mut verticals: BTreeMap<Rational, Vec<usize>> = BTreeMap::new();
+        for (i, p) in pieces.iter().enumerate() {
+            if segments.get(p.segment).is_vertical() {
+                verticals.entry(p.a.x).or_default().push(i);
+            } else {
+                starts.entry(p.a.x).or_default().push(i);
+                right_ends.entry(p.b.x).or_default().push(i);
+            }
+        }
+        let xs: BTreeSet<Rational> = starts.keys().chain(right_ends.keys()).copied().collect();
+
+        let mut map = Self {
+            segments,
+            pieces,
+            nodes: Vec::new(),
+            versions: Vec::with_capacity(xs.len()),
+            right_ends,
+            verticals,
+        };
+        let mut root = NIL;
+        let mut prev_x = None;
+        for x in xs {
+            // 在上一个版本的顺序下删除结束的子段，再在 `x + ε` 的顺序下插入新开始的子段。
+            if let (Some(px), Some(ending)) = (prev_x, map.right_ends.get(&x).cloned()) {
+                for i in ending {
+                    root = map.remove(root, i, px);
+                }
+            }
+            for &i in starts.get(&x).into_iter().flatten() {
+                root = map.insert(root, i, x);
+            }
+            map.versions.push((x, root));
+            prev_x = Some(x);
+        }
+        map
+    }
+
+    fn cmp(&self, a: usize, b: usize, x: Rational) -> Ordering {
+        if a == b {
+            return Ordering::Equal;
+        }
+        let (sa, sb) = (self.pieces[a].segment, self.pieces[b].segment);
+        cmp_segments_at_x_plus_epsilon(&self.segments, sa, sb, x)
+            .expect("顶点 x 处的比较与扫描线相同，不会溢出")
+    }
+
+    fn y_of(&self, piece: usize, x: Rational) -> Rational {
+        y_at_x(self.segments.get(self.pieces[piece].segment), x)
+            .expect("坐标范围内的 y_at_x 不会溢出")
+    }
+
+    fn node(&mut self, node: Node) -> u32 {
+        self.nodes.push(node);
+        (self.nodes.len() - 1) as u32
+    }
+
+    fn insert(&mut self, root: u32, piece: usize, x: Rational) -> u32 {
+        let (l, r) = self.split(root, &mut |m, p| m.cmp(p, piece, x) == Ordering::Less);
+        let single = self.node(Node {
+            piece: piece as u32,
+            left: NIL,
+            right: NIL,
+        });
+        let l = self.merge(l, single);
+        self.merge(l, r)
+    }
+
+    fn remove(&mut self, root: u32, piece: usize, x: Rational) -> u32 {
+        let (l, rest) = self.split(root, &mut |m, p| m.cmp(p, piece, x) == Ordering::Less);
+        let (found, r) = self.split(rest, &mut |_, p| p == piece);
+        debug_assert!(found != NIL, "删除的子段应在当前版本中");
+        self.merge(l, r)
+    }
+
+    /// 按顺序切成两棵树：左树为满足 `goes_left` 的前缀（路径上的节点被复制）。
+    fn split(&mut self, t: u32, goes_left: &mut dyn FnMut(&Self, usize) -> bool) -> (u32, u32) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        let n = self.nodes[t as usize];
+        if goes_left(self, n.piece as usize) {
+            let (l, r) = self.split(n.right, goes_left);
+            (self.node(Node { right: l, ..n }), r)
+        } else {
+            let (l, r) = self.split(n.left, goes_left);
+            (l, self.node(Node { left: r, ..n }))
+        }
+    }
+
+    fn merge(&mut self, a: u32, b: u32) -> u32 {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+        let (na, nb) = (self.nodes[a as usize], self.nodes[b as usize]);
+        if priority(na.piece) > priority(nb.piece) {
+            let right = self.merge(na.right, b);
+            self.node(Node { right, ..na })
+        } else {
+            let left = self.merge(a, nb.left);
+            self.node(Node { left, ..nb })
+        }
+    }
+
+    /// `x` 所在条带的版本根（`x` 在第一个顶点左侧时为 `None`）。
+    fn version_at(&self, x: Rational) -> Option<u32> {
+        let i = self.versions.partition_point(|(vx, _)| *vx <= x);
+        i.checked_sub(1).map(|i| self.versions[i].1)
+    }
+
+    /// 沿 `dir` 方向最近的命中点及经过它的全部子段的 `key`。
+    fn shoot(&self, p: PointRat, dir: Direction) -> Option<(Rational, Vec<usize>)> {
+        let mut best: Option<(Rational, Vec<usize>)> = None;
+        let mut consider = |y: Rational, piece: usize| {
+            let closer = |b: &Rational| match dir {
+                Direction::Up => y < *b,
+                Direction::Down => y > *b,
+            };
+            match &mut best {
+                Some((b, keys)) if *b == y => keys.push(self.pieces[piece].key),
+                Some((b, _)) if !closer(b) => {}
+                _ => best = Some((y, vec![self.pieces[piece].key])),
+            }
+        };
+        let reaches = |y: Rational| match dir {
+            Direction::Up => y >= p.y,
+            Direction::Down => y <= p.y,
+        };
+
+        if let Some(root) = self.version_at(p.x) {
+            let found = match dir {
+                Direction::Up => self.first_where(root, p.x, &|y| y >= p.y),
+                Direction::Down => self.last_where(root, p.x, &|y| y <= p.y),
+            };
+            if let Some(piece) = found {
+                let y = self.y_of(piece, p.x);
+                let mut ties = Vec::new();
+                self.collect_at(root, p.x, y, &mut ties);
+                for piece in ties {
+                    consider(y, piece);
+                }
+            }
+        }
+        for &i in self.right_ends.get(&p.x).into_iter().flatten() {
+            let y = self.pieces[i].b.y;
+            if reaches(y) {
+                consider(y, i);
+            }
+        }
+        for &i in self.verticals.get(&p.x).into_iter().flatten() {
+            let (lo, hi) = (self.pieces[i].a.y, self.pieces[i].b.y);
+            let y = match dir {
+                Direction::Up => lo.max(p.y),
+                Direction::Down => hi.min(p.y),
+            };
+            if y <= hi && y >= lo {
+                consider(y, i);
+            }
+        }
+
+        best.map(|(y, mut keys)| {
+            keys.sort_unstable();
+            keys.dedup();
+            (y, keys)
+        })
+    }
+
+    /// 版本中位于 `p` 严格上方（在 `p.x` 处）的第一个子段。
+    fn first_strictly_above(&self, p: PointRat) -> Option<usize> {
+        let root = self.version_at(p.x)?;
+        self.first_where(root, p.x, &|y| y > p.y)
+    }
+
+    /// 顺序中第一个满足 `pred(y)` 的子段（`pred` 沿顺序单调：先假后真）。
+    fn first_where(
+        &self,
+        mut t: u32,
+        x: Rational,
+        pred: &dyn Fn(Rational) -> bool,
+    ) -> Option<usize> {
+        let mut found = None;
+        while t != NIL {
+            let n = self.nodes[t as usize];
+            if pred(self.y_of(n.piece as usize, x)) {
+                found = Some(n.piece as usize);
+                t = n.left;
+            } else {
+                t = n.right;
+            }
+        }
+        found
+    }
+
+    /// 顺序中最后一个满足 `pred(y)` 的子段（`pred` 沿顺序单调：先真后假）。
+    fn last_where(
+        &self,
+        mut t: u32,
+        x: Rational,
+        pred: &dyn Fn(Rational) -> bool,
+    ) -> Option<usize> {
+        let mut found = None;
+        while t != NIL {
+            let n = self.nodes[t as usize];
+            if pred(self.y_of(n.piece as usize, x)) {
+                found = Some(n.piece as usize);
+                t = n.right;
+            } else {
+                t = n.left;
+            }
+        }
+        found
+    }
+
+    /// 在 `x` 处经过 `y` 的全部子段（同一顺序中连续）。
+    fn collect_at(&self, t: u32, x: Rational, y: Rational, out: &mut Vec<usize>) {
+        if t == NIL {
+            return;
+        }
+        let n = self.nodes[t as usize];
+        let ny = self.y_of(n.piece as usize, x);
+        if ny >= y {
+            self.collect_at(n.left, x, y, out);
+        }
+        if ny == y {
+            out.push(n.piece as usize);
+        }
+        if ny <= y {
+            self.collect_at(n.right, x, y, out);
+        }
+    }
+}
+
+fn priority(piece: u32) -> u64 {
+    // splitmix64（同 `TreapSweepStatus`）：确定性的固定优先级。
+    let mut x = (piece as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz::XorShift64;
+    use crate::geom::fixed::Coord;
+    use crate::geom::intersection::{QueryShape, ShapeIntersection, intersect_segments_with_shape};
+    use crate::geom::predicates::orient;
+    use crate::geom::segment::segments_from;
+
+    fn p(x: Coord, y: Coord) -> PointI64 {
+        PointI64 { x, y }
+    }
+
+    fn hit(y: (i128, i128), ids: &[usize]) -> Option<RayHit> {
+        Some(RayHit {
+            y: Rational::new(y.0, y.1),
+            segments: ids.iter().map(|&i| SegmentId(i)).collect(),
+        })
+    }
+
+    /// 暴力：与竖直线 `x = p.x` 求交，取射线方向上最近的命中点。
+    fn brute(segments: &Segments, q: PointI64, up: bool) -> Option<RayHit> {
+        let line = QueryShape::Line {
+            a: q,
+            b: p(q.x, q.y + 1),
+        };
+        let qy = Rational::from_int(q.y as i128);
+        let mut best: BTreeMap<Rational, Vec<SegmentId>> = BTreeMap::new();
+        for (id, shape) in intersect_segments_with_shape(segments, &line) {
+            let y = match shape {
+                ShapeIntersection::Point(pt) => Some(pt.y),
+                ShapeIntersection::Segment(a, b) if up => (b.y >= qy).then(|| a.y.max(qy)),
+                ShapeIntersection::Segment(a, b) => (a.y <= qy).then(|| b.y.min(qy)),
+            };
+            if let Some(y) = y.filter(|y| if up { *y >= qy } else { *y <= qy }) {
+                best.entry(y).or_default().push(id);
+            }
+        }
+        let (y, segments) = if up {
+            best.pop_first()?
+        } else {
+            best.pop_last()?
+        };
+        Some(RayHit { y, segments })
+    }
+
+    #[test]
+    fn shoots_rays_through_crossings_endpoints_and_verticals() {
+        let segments = segments_from(&[
+            (0, 0, 4, 4),
+            (0, 4, 4, 0),
+            (2, 6, 2, 8),
+            (-2, 6, 2, 6),
+            (4, 4, 6, 4),
+        ]);
+        let locator = SegmentLocator::from_segments(&segments, Limits::default()).unwrap();
+
+        // 正上方恰为交点 (2,2)：两条线段同时命中。
+        assert_eq!(locator.above(p(2, -1)), hit((2, 1), &[0, 1]));
+        // 竖直线段的下端点、水平线段的右端点都在 (2,6)。
+        assert_eq!(locator.above(p(2, 3)), hit((6, 1), &[2, 3]));
+        // 查询点在竖直线段上：命中点即查询点本身。
+        assert_eq!(locator.below(p(2, 7)), hit((7, 1), &[2]));
+        assert_eq!(locator.below(p(1, 5)), hit((3, 1), &[1]));
+        assert_eq!(locator.above(p(1, 1)), hit((1, 1), &[0]));
+        // 查询点在第 1 条线段上。
+        assert_eq!(locator.above(p(3, 1)), hit((1, 1), &[1]));
+        assert_eq!(locator.above(p(3, 2)), hit((3, 1), &[0]));
+        assert_eq!(locator.below(p(5, 9)), hit((4, 1), &[4]));
+        assert_eq!(locator.above(p(7, 0)), None);
+        assert_eq!(locator.below(p(-1, 5)), None);
+    }
+
+    #[test]
+    fn random_ray_queries_match_brute_force() {
+        let mut rng = XorShift64::new(25);
+        for _ in 0..30 {
+            let grid = rng.range(3, 10);
+            let n = rng.range(1, 25) as usize;
+            let list: Vec<_> = (0..n)
+                .map(|_| {
+                    (
+                        rng.range(-grid, grid),
+                        rng.range(-grid, grid),
+                        rng.range(-grid, grid),
+                        rng.range(-grid, grid),
+                    )
+                })
+                .collect();
+            let segments = segments_from(&list);
+            let locator = SegmentLocator::from_segments(&segments, Limits::default()).unwrap();
+            for x in -grid - 1..=grid + 1 {
+                for y in -grid - 1..=grid + 1 {
+                    let q = p(x, y);
+                    assert_eq!(locator.above(q), brute(&segments, q, true), "{q:?}");
+                    assert_eq!(locator.below(q), brute(&segments, q, false), "{q:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn locates_vertices_edges_and_faces_with_holes() {
+        let square = |x0: Coord, y0: Coord, s: Coord| {
+            vec![p(x0, y0), p(x0 + s, y0), p(x0 + s, y0 + s), p(x0, y0 + s)]
+        };
+        let ring = PolygonI64::new(square(0, 0, 10)).with_hole(square(3, 3, 4));
+        let locator = PolygonLocator::new(&[ring], Limits::default()).unwrap();
+        let arr = &locator.arrangement;
+
+        let Location::Face(inner) = locator.locator.locate(p(5, 5)) else {
+            panic!("(5,5) 应在面内");
+        };
+        let Location::Face(band) = locator.locator.locate(p(1, 8)) else {
+            panic!("(1,8) 应在面内");
+        };
+        assert_eq!(arr.face(inner).holes.len(), 0);
+        assert_eq!(arr.face(band).holes.len(), 1);
+        // 与洞的顶点同一竖直线上、位于顶点下方的点。
+        assert_eq!(locator.locator.locate(p(3, 1)), Location::Face(band));
+        assert_eq!(
+            locator.locator.locate(p(20, 5)),
+            Location::Face(Arrangement::UNBOUNDED_FACE)
+        );
+        assert!(matches!(
+            locator.locator.locate(p(0, 0)),
+            Location::Vertex(_)
+        ));
+        assert!(matches!(locator.locator.locate(p(0, 5)), Location::Edge(_)));
+        assert!(matches!(locator.locator.locate(p(5, 3)), Location::Edge(_)));
+
+        assert_eq!(locator.contains(p(1, 8)), Containment::Inside);
+        assert_eq!(locator.contains(p(5, 5)), Containment::Outside);
+        assert_eq!(locator.contains(p(3, 5)), Containment::Boundary);
+        assert_eq!(locator.contains(p(10, 10)), Containment::Boundary);
+        assert_eq!(locator.contains(p(-1, 5)), Containment::Outside);
+    }
+
+    #[test]
+    fn random_point_in_polygon_matches_crossing_parity() {
+        let mut rng = XorShift64::new(125);
+        for _ in 0..30 {
+            let grid = rng.range(3, 8);
+            let polygons: Vec<PolygonI64> = (0..rng.range(1, 3))
+                .map(|_| {
+                    let n = rng.range(3, 7) as usize;
+                    PolygonI64::new(
+                        (0..n)
+                            .map(|_| p(rng.range(-grid, grid), rng.range(-grid, grid)))
+                            .collect(),
+                    )
+                })
+                .collect();
+            let locator = PolygonLocator::new(&polygons, Limits::default()).unwrap();
+            let edges: Vec<(PointI64, PointI64)> = polygons
+                .iter()
+                .flat_map(|poly| {
+                    let r = &poly.exterior;
+                    (0..r.len()).map(move |i| (r[i], r[(i + 1) % r.len()]))
+                })
+                .filter(|(a, b)| a != b)
+                .collect();
+            for x in -grid - 1..=grid + 1 {
+                for y in -grid - 1..=grid + 1 {
+                    let q = p(x, y);
+                    let on_edge = edges
+                        .iter()
+                        .any(|&(a, b)| crate::geom::predicates::on_segment(a, b, q));
+                    let got = locator.contains(q);
+                    if on_edge {
+                        // 边上的点：重合边相互抵消时也可能在内部或外部。
+                        continue;
+                    }
+                    // 向右的射线，半开规则统计穿过的边数。
+                    let crossings = edges
+                        .iter()
+                        .filter(|&&(a, b)| {
+                            let (lo, hi) = if a.y < b.y { (a, b) } else { (b, a) };
+                            lo.y <= y && y < hi.y && orient(lo, hi, q) > 0
+                        })
+                        .count();
+                    let expected = if crossings % 2 == 1 {
+                        Containment::Inside
+                    } else {
+                        Containment::Outside
+                    };
+                    assert_eq!(got, expected, "{q:?} {polygons:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,485 @@
+//! 结果映射：把网格坐标上的点交结果映射回调用方的输入坐标系与原始输入下标。
+//!
+//! 约定：
+//! - 坐标按 `PreprocessOutput.quantization` 逆变换：`v = origin + grid / grid_per_unit`；
+//! - `f64` 结果为近似值；可选的十进制字符串在给定小数位数内是精确的（能有限表示时输出精确值，否则按位数四舍六入五成双）；
+//! - 参与线段以原始输入下标（`Segment.source_index`）表示，调用方无需接触 `SCALE` 或 `SegmentId`；
+//!   开启 `MapOptions.expand_duplicates` 时，被去重丢弃的输入也一并列出。
+
+use std::collections::BTreeSet;
+
+use crate::atomic::{AtomicIntersectionGroupRecord, AtomicSegments};
+use crate::bigint::BigUint;
+use crate::geom::fixed::Quantization;
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::point::PointRat;
+use crate::geom::segment::SegmentId;
+use crate::preprocess::PreprocessOutput;
+use crate::rational::Rational;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MapOptions {
+    /// 是否额外输出十进制字符串；`Some(k)` 表示小数部分最多 `k` 位。
+    pub decimal_digits: Option<usize>,
+    /// 是否把预处理丢弃的重复输入展开回参与下标（与保留者几何相同，因此以相同角色参与）。
+    pub expand_duplicates: bool,
+}
+
+/// 十进制坐标文本。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecimalCoord {
+    pub text: String,
+    /// `true` 表示 `text` 即精确值；`false` 表示已按小数位数舍入。
+    pub exact: bool,
+}
+
+/// 映射到调用方坐标系的点交记录。
+#[derive(Clone, Debug, PartialEq)]
+pub struct MappedIntersection {
+    pub x: f64,
+    pub y: f64,
+    /// 仅当 `MapOptions.decimal_digits` 为 `Some` 时输出。
+    pub x_decimal: Option<DecimalCoord>,
+    pub y_decimal: Option<DecimalCoord>,
+    /// 以端点参与的原始输入下标（升序、去重）。
+    pub endpoint_inputs: Vec<usize>,
+    /// 以内部点参与的原始输入下标（升序、去重）。
+    pub interior_inputs: Vec<usize>,
+}
+
+/// 映射 phase1 点交结果（`SegmentId` 即预处理后线段）。
+pub fn map_intersections(
+    preprocess: &PreprocessOutput,
+    records: &[PointIntersectionGroupRecord],
+    options: &MapOptions,
+) -> Vec<MappedIntersection> {
+    let inputs = SegmentInputs::new(preprocess, options);
+    let source = |ids: &[SegmentId]| -> Vec<usize> {
+        let mut set: BTreeSet<usize> = BTreeSet::new();
+        for id in ids {
+            inputs.extend_into(*id, &mut set);
+        }
+        set.into_iter().collect()
+    };
+    records
+        .iter()
+        .map(|r| {
+            map_point(
+                &preprocess.quantization,
+                r.point,
+                source(&r.endpoint_segments),
+                source(&r.interior_segments),
+                options,
+            )
+        })
+        .collect()
+}
+
+/// 映射 phase2 点交结果（`AtomicSegmentId` 经溯源展开为预处理后线段）。
+///
+/// 说明：角色按原始线段重新判定——交点落在原子子段端点但不是原始线段端点时（切分点），记为内部点。
+pub fn map_atomic_intersections(
+    preprocess: &PreprocessOutput,
+    atomic: &AtomicSegments,
+    records: &[AtomicIntersectionGroupRecord],
+    options: &MapOptions,
+) -> Vec<MappedIntersection> {
+    let inputs = SegmentInputs::new(preprocess, options);
+    records
+        .iter()
+        .map(|r| {
+            let mut endpoint: BTreeSet<usize> = BTreeSet::new();
+            let mut interior: BTreeSet<usize> = BTreeSet::new();
+            for id in r.endpoint_segments.iter().chain(&r.interior_segments) {
+                for seg_id in atomic.provenance(*id) {
+                    let seg = preprocess.segments.get(*seg_id);
+                    if r.point == PointRat::from_i64(seg.a) || r.point == PointRat::from_i64(seg.b)
+                    {
+                        inputs.extend_into(*seg_id, &mut endpoint);
+                    } else {
+                        inputs.extend_into(*seg_id, &mut interior);
+                    }
+                }
+            }
+            map_point(
+                &preprocess.quantization,
+                r.point,
+                endpoint.into_iter().collect(),
+                interior.into_iter().collect(),
+                options,
+            )
+        })
+        .collect()
+}
+
+/// `SegmentId -> 原始输入下标` 的查询（按选项决定是否包含被去重丢弃的输入）。
+struct SegmentInputs<'a> {
+    preprocess: &'a PreprocessOutput,
+    expanded: Option<Vec<Vec<usize>>>,
+}
+
+impl<'a> SegmentInputs<'a> {
+    fn new(preprocess: &'a PreprocessOutput, options: &MapOptions) -> Self {
+        Self {
+            preprocess,
+            expanded: options
+                .expand_duplicates
+                .then(|| preprocess.segment_inputs()),
+        }
+    }
+
+    fn extend_into(&self, id: SegmentId, out: &mut BTreeSet<usize>) {
+        match &self.expanded {
+            Some(expanded) => out.extend(expanded[id.0].iter().copied()),
+            None => {
+                out.insert(self.preprocess.segments.get(id).source_index);
+            }
+        }
+    }
+}
+
+fn map_point(
+    quantization: &Quantization,
+    point: PointRat,
+    endpoint_inputs: Vec<usize>,
+    interior_inputs: Vec<usize>,
+    options: &MapOptions,
+) -> MappedIntersection {
+    let (x, y) = quantization.unquantize(point.x.to_f64(), point.y.to_f64());
+    let decimal = |grid: Rational, origin: f64| {
+        options
+            .decimal_digits
+            .map(|digits| grid_to_input_decimal(grid, origin, quantization.grid_per_unit, digits))
+    };
+    MappedIntersection {
+        x,
+        y,
+        x_decimal: decimal(point.x, quantization.origin_x),
+        y_decimal: decimal(point.y, quantization.origin_y),
+        endpoint_inputs,
+        interior_inputs,
+    }
+}
+
+/// 计算 `origin + grid / grid_per_unit` 的十进制文本（小数部分最多 `max_fraction_digits` 位）。
+///
+/// 说明：`origin` 与 `grid_per_unit` 按 `f64` 的精确二进制值参与运算，因此结果与 `f64` 输入严格对应。
+pub fn grid_to_input_decimal(
+    grid: Rational,
+    origin: f64,
+    grid_per_unit: f64,
+    max_fraction_digits: usize,
+) -> DecimalCoord {
+    assert!(
+        origin.is_finite() && grid_per_unit.is_finite() && grid_per_unit > 0.0,
+        "量化变换必须是有限值且 grid_per_unit > 0"
+    );
+    let (origin_neg, origin_m, origin_e) = decompose_f64(origin);
+    let (_, scale_m, scale_e) = decompose_f64(grid_per_unit);
+
+    // v = origin_m·2^origin_e + num / (den·scale_m·2^scale_e)，统一到分母 den·scale_m·2^k。
+    let k = 0.max(-origin_e).max(scale_e) as usize;
+    let den = BigUint::from_u128(grid.den() as u128).mul(&BigUint::from_u128(scale_m as u128));
+    let origin_term = BigUint::from_u128(origin_m as u128)
+        .mul(&den)
+        .shl((origin_e + k as i32) as usize);
+    let grid_term =
+        BigUint::from_u128(grid.num().unsigned_abs()).shl((k as i32 - scale_e) as usize);
+    let denominator = den.shl(k);
+
+    let (negative, numerator) = signed_add(origin_neg, origin_term, grid.num() < 0, grid_term);
+    format_decimal(negative, &numerator, &denominator, max_fraction_digits)
+}
+
+/// 把有限 `f64` 拆成 `(是否为负, m, e)`，满足 `|v| = m·2^e`。
+///
+/// 说明：`m` 为奇数（去掉末尾的 0 位）或 `±0.0` 时为 `(false, 0, 0)`，使后续公分母的 `2^k` 尽量小。
+fn decompose_f64(v: f64) -> (bool, u64, i32) {
+    let bits = v.to_bits();
+    let negative = bits >> 63 == 1;
+    let exp = ((bits >> 52) & 0x7FF) as i32;
+    let frac = bits & ((1_u64 << 52) - 1);
+    let (m, e) = if exp == 0 {
+        (frac, -1074)
+    } else {
+        (frac | (1_u64 << 52), exp - 1075)
+    };
+    if m == 0 {
+        return (false, 0, 0);
+    }
+    let zeros = m.trailing_zeros();
+    (negative, m >> zeros, e + zeros as i32)
+}
+
+fn signed_add(a_neg: bool, a: BigUint, b_neg: bool, b: BigUint) -> (bool, BigUint) {
+    if a_neg == b_neg {
+        return (a_neg, a.add(&b));
+    }
+    if a >= b {
+        (a_neg, a.sub(&b))
+    } else {
+        (b_neg, b.sub(&a))
+    }
+}
+
+fn format_decimal(
+    negative: bool,
+    numerator: &BigUint,
+    denominator: &BigUint,
+    max_fraction_digits: usize,
+) -> DecimalCoord {
+    let (mut int_part, mut rem) = numerator.div_rem(denominator);
+    let mut digits: Vec<u8> = Vec::new();
+    while digits.len() < max_fraction_digits && !rem.is_zero() {
+        let (d, r) = rem.mul_small(10).div_rem_small_quotient(denominator, 10);
+        digits.push(d as u8);
+        rem = r;
+    }
+
+    let exact = rem.is_zero();
+    if !exact {
+        let twice = rem.shl(1);
+        let last_odd = match digits.last() {
+            Some(d) => d % 2 == 1,
+            None => int_part.div_small(2).1 == 1,
+        };
+        if twice > *denominator || (twice == *denominator && last_odd) {
+            // 向上进位：从最后一位小数开始传播，必要时进到整数部分。
+            let mut carry = true;
+            for d in digits.iter_mut().rev() {
+                if *d == 9 {
+                    *d = 0;
+                } else {
+                    *d += 1;
+                    carry = false;
+                    break;
+                }
+            }
+            if carry {
+                int_part = int_part.add(&BigUint::from_u128(1));
+            }
+        }
+    }
+
+    let is_zero = int_part.is_zero() && digits.iter().all(|d| *d == 0);
+    let mut text = String::new();
+    if negative && !is_zero {
+        text.push('-');
+    }
+    text.push_str(&int_part.to_decimal_string());
+    if !digits.is_empty() {
+        text.push('.');
+        text.extend(digits.iter().map(|d| (b'0' + d) as char));
+    }
+    DecimalCoord { text, exact }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocess::InputSegmentF64;
+    use crate::run::{Phase1Options, run_phase1_with_options, run_phase2};
+    use crate::{QuantizationMode, geom::fixed::SCALE};
+
+    fn decimal(grid: Rational, origin: f64, grid_per_unit: f64, digits: usize) -> (String, bool) {
+        let d = grid_to_input_decimal(grid, origin, grid_per_unit, digits);
+        (d.text, d.exact)
+    }
+
+    #[test]
+    fn formats_exact_and_rounded_decimals() {
+        let scale = SCALE as f64;
+        assert_eq!(
+            decimal(Rational::new(SCALE as i128, 2), 0.0, scale, 12),
+            ("0.5".to_string(), true)
+        );
+        assert_eq!(
+            decimal(Rational::new(-(SCALE as i128), 3), 0.0, scale, 6),
+            ("-0.333333".to_string(), false)
+        );
+        assert_eq!(
+            decimal(Rational::new(2 * SCALE as i128, 3), 0.0, scale, 3),
+            ("0.667".to_string(), false)
+        );
+        // 恰好一半时舍入到偶数：0.125 -> 0.12；0.375 -> 0.38。
+        assert_eq!(
+            decimal(Rational::new(1, 8), 0.0, 1.0, 2),
+            ("0.12".to_string(), false)
+        );
+        assert_eq!(
+            decimal(Rational::new(3, 8), 0.0, 1.0, 2),
+            ("0.38".to_string(), false)
+        );
+        // 舍入为 0 时不保留负号。
+        assert_eq!(
+            decimal(Rational::new(-1, 3), 0.0, 1.0, 0),
+            ("0".to_string(), false)
+        );
+        // 进位传播到整数部分。
+        assert_eq!(
+            decimal(Rational::new(1999, 1000), 0.0, 1.0, 2),
+            ("2.00".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn decomposes_f64_into_odd_mantissas() {
+        assert_eq!(decompose_f64(0.0), (false, 0, 0));
+        assert_eq!(decompose_f64(-0.0), (false, 0, 0));
+        assert_eq!(decompose_f64(1.0), (false, 1, 0));
+        assert_eq!(decompose_f64(-6.0), (true, 3, 1));
+        assert_eq!(decompose_f64(0.375), (false, 3, -3));
+        assert_eq!(decompose_f64(f64::from_bits(1)), (false, 1, -1074));
+        assert_eq!(decompose_f64(f64::from_bits(4)), (false, 1, -1072));
+        // 原点为 0 时公分母不再带上 2^1074。
+        assert_eq!(
+            decimal(Rational::new(7, 4), -0.0, 1.0, 12),
+            ("1.75".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn uses_exact_binary_value_of_origin() {
+        // 0.1 的精确二进制值展开后有 55 位小数。
+        let (text, exact) = decimal(Rational::from_int(0), 0.1, 1.0, 60);
+        assert_eq!(
+            text,
+            "0.1000000000000000055511151231257827021181583404541015625"
+        );
+        assert!(exact);
+        assert_eq!(
+            decimal(Rational::from_int(-250), 1000.0, 1000.0, 10),
+            ("999.75".to_string(), true)
+        );
+    }
+
+    #[test]
+    fn maps_phase1_points_and_input_indices() {
+        let input = [
+            InputSegmentF64 {
+                ax: 1000.0,
+                ay: 1000.0,
+                bx: 1000.0,
+                by: 1000.0,
+            },
+            InputSegmentF64 {
+                ax: 1000.0,
+                ay: 2000.0,
+                bx: 3000.0,
+                by: 2000.0,
+            },
+            InputSegmentF64 {
+                ax: 2000.0,
+                ay: 1000.0,
+                bx: 2000.0,
+                by: 3000.0,
+            },
+        ];
+        let options = Phase1Options {
+            quantization: QuantizationMode::Auto,
+            ..Default::default()
+        };
+        let out = run_phase1_with_options(&input, &options).unwrap();
+        let mapped = out.map_intersections(&MapOptions {
+            decimal_digits: Some(6),
+            ..Default::default()
+        });
+        assert_eq!(mapped.len(), 1);
+        let hit = &mapped[0];
+        assert_eq!((hit.x, hit.y), (2000.0, 2000.0));
+        assert_eq!(hit.x_decimal.as_ref().unwrap().text, "2000");
+        assert!(hit.endpoint_inputs.is_empty());
+        assert_eq!(hit.interior_inputs, vec![1, 2]);
+    }
+
+    #[test]
+    fn reclassifies_atomic_split_points_as_interior() {
+        // 0/1 共线重叠；2 的端点落在重叠段内部（原子切分点之外）；3 穿过切分点 x=0.5。
+        let input = [
+            InputSegmentF64 {
+                ax: -1.0,
+                ay: 0.0,
+                bx: 0.5,
+                by: 0.0,
+            },
+            InputSegmentF64 {
+                ax: -0.5,
+                ay: 0.0,
+                bx: 1.0,
+                by: 0.0,
+            },
+            InputSegmentF64 {
+                ax: 0.0,
+                ay: 0.0,
+                bx: 0.0,
+                by: 1.0,
+            },
+            InputSegmentF64 {
+                ax: 0.25,
+                ay: -1.0,
+                bx: 0.75,
+                by: 1.0,
+            },
+        ];
+        let out = run_phase2(&input).unwrap();
+        let mapped = map_atomic_intersections(
+            &out.preprocess,
+            &out.atomic,
+            &out.intersections,
+            &MapOptions::default(),
+        );
+        let summary: Vec<(f64, f64, Vec<usize>, Vec<usize>)> = mapped
+            .iter()
+            .map(|m| {
+                (
+                    m.x,
+                    m.y,
+                    m.endpoint_inputs.clone(),
+                    m.interior_inputs.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0.0, 0.0, vec![2], vec![0, 1]),
+                (0.5, 0.0, vec![0], vec![1, 3]),
+            ]
+        );
+        assert!(mapped[0].x_decimal.is_none());
+    }
+
+    #[test]
+    fn expands_dropped_duplicates_to_all_input_indices() {
+        let h = |ax, bx| InputSegmentF64 {
+            ax,
+            ay: 0.0,
+            bx,
+            by: 0.0,
+        };
+        let v = InputSegmentF64 {
+            ax: 0.0,
+            ay: -0.5,
+            bx: 0.0,
+            by: 0.5,
+        };
+        // 1 与 0 重复（反向），3 与 0 重复；2 与 0 相交。
+        let input = [h(-0.5, 0.5), h(0.5, -0.5), v, h(-0.5, 0.5)];
+        let out = crate::run::run_phase1(&input).unwrap();
+        assert_eq!(
+            out.preprocess.segment_inputs(),
+            vec![vec![0, 1, 3], vec![2]]
+        );
+
+        let plain = out.map_intersections(&MapOptions::default());
+        assert_eq!(plain[0].interior_inputs, vec![0, 2]);
+
+        let expanded = out.map_intersections(&MapOptions {
+            expand_duplicates: true,
+            ..Default::default()
+        });
+        assert_eq!(expanded.len(), 1);
+        assert_eq!(expanded[0].interior_inputs, vec![0, 1, 2, 3]);
+        assert!(expanded[0].endpoint_inputs.is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+//! 暴力 `O(n²)` 参考实现与差异比对（用于独立校验扫描线的点交输出）。
+//!
+//! 说明：
+//! - 参考实现对所有线段对调用 `intersect_segments`，再按点聚合为 `PointIntersectionGroupRecord`，
+//!   不依赖事件队列、状态结构或垂直线段的批末查询，因此可作为扫描线的“裁判”；
+//! - 语义与 `sweep::bo::enumerate_point_intersections` 一致：点交（含端点接触）按点输出一次；
+//!   共线重叠段不输出（见 `crate::overlap`），但两条共线线段的端点重合时仍按端点接触输出；
+//! - 仅适合小规模输入（回归测试、随机对拍、线上抽检），不受 `Limits` 约束。
+
+use core::fmt;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::geom::intersection::{
+    PointIntersectionGroupRecord, SegmentIntersection, intersect_segments,
+};
+use crate::geom::point::PointRat;
+use crate::geom::segment::{SegmentId, Segments};
+use crate::spatial::SegmentIndex;
+
+/// 暴力枚举点交（按点升序，每个点只输出一次）。
+pub fn enumerate_point_intersections_naive(
+    segments: &Segments,
+) -> Vec<PointIntersectionGroupRecord> {
+    let n = segments.len();
+    let pairs = (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (SegmentId(i), SegmentId(j))));
+    group_pairs(segments, pairs)
+}
+
+/// 同 `enumerate_point_intersections_naive`，但只比较包围盒相交的线段对（由 `SegmentIndex` 给出），
+/// 结果完全相同；稀疏输入下远快于全部 `O(n²)` 对。
+pub fn enumerate_point_intersections_naive_indexed(
+    segments: &Segments,
+) -> Vec<PointIntersectionGroupRecord> {
+    let pairs = SegmentIndex::build(segments).candidate_pairs(segments);
+    group_pairs(segments, pairs.into_iter())
+}
+
+fn group_pairs(
+    segments: &Segments,
+    pairs: impl Iterator<Item = (SegmentId, SegmentId)>,
+) -> Vec<PointIntersectionGroupRecord> {
+    let mut groups: BTreeMap<PointRat, BTreeSet<SegmentId>> = BTreeMap::new();
+    for (i, j) in pairs {
+        let (a, b) = (segments.get(i), segments.get(j));
+        match intersect_segments(a, b) {
+            None => {}
+            Some(SegmentIntersection::Point { point, .. }) => {
+                let group = groups.entry(point).or_default();
+                group.insert(i);
+                group.insert(j);
+            }
+            Some(SegmentIntersection::CollinearOverlap { .. }) => {
+                // 重叠段本身留给 phase2；只保留“两端点重合”的端点接触。
+                for p in [a.a, a.b] {
+                    if p == b.a || p == b.b {
+                        let group = groups.entry(PointRat::from_i64(p)).or_default();
+                        group.insert(i);
+                        group.insert(j);
+                    }
+                }
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(point, ids)| {
+            let mut endpoint_segments = Vec::new();
+            let mut interior_segments = Vec::new();
+            for id in ids {
+                let seg = segments.get(id);
+                if point == PointRat::from_i64(seg.a) || point == PointRat::from_i64(seg.b) {
+                    endpoint_segments.push(id);
+                } else {
+                    interior_segments.push(id);
+                }
+            }
+            PointIntersectionGroupRecord {
+                point,
+                endpoint_segments,
+                interior_segments,
+            }
+        })
+        .collect()
+}
+
+/// 同一点上参考结果与被测结果的线段集合或角色不一致。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MisclassifiedGroup {
+    pub reference: PointIntersectionGroupRecord,
+    pub candidate: PointIntersectionGroupRecord,
+}
+
+/// 参考结果与被测结果的差异（各列表均按点升序）。
+///
+/// 说明：
+/// - `missing`：参考结果有、被测结果没有的点；
+/// - `extra`：被测结果有、参考结果没有的点；
+/// - `misclassified`：两边都有该点，但 `endpoint_segments`/`interior_segments` 不一致；
+/// - `duplicated`：被测结果中出现不止一次的点（违反“同一点只输出一次”），比对时按首次出现计。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntersectionDiff {
+    pub missing: Vec<PointIntersectionGroupRecord>,
+    pub extra: Vec<PointIntersectionGroupRecord>,
+    pub misclassified: Vec<MisclassifiedGroup>,
+    pub duplicated: Vec<PointRat>,
+}
+
+impl IntersectionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.misclassified.is_empty()
+            && self.duplicated.is_empty()
+    }
+}
+
+impl fmt::Display for IntersectionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "无差异");
+        }
+        writeln!(
+            f,
+            "缺失 {} 个点，多出 {} 个点，分类不一致 {} 个点，重复输出 {} 个点",
+            self.missing.len(),
+            self.extra.len(),
+            self.misclassified.len(),
+            self.duplicated.len()
+        )?;
+        for r in &self.missing {
+            writeln!(f, "  缺失 {}", format_group(r))?;
+        }
+        for r in &self.extra {
+            writeln!(f, "  多出 {}", format_group(r))?;
+        }
+        for m in &self.misclassified {
+            writeln!(
+                f,
+                "  分类不一致 参考 {} / 被测 {}",
+                format_group(&m.reference),
+                format_group(&m.candidate)
+            )?;
+        }
+        for p in &self.duplicated {
+            writeln!(f, "  重复输出 ({}, {})", p.x, p.y)?;
+        }
+        Ok(())
+    }
+}
+
+/// 比对参考结果与被测结果（两者顺序任意）。
+pub fn compare_point_intersections(
+    reference: &[PointIntersectionGroupRecord],
+    candidate: &[PointIntersectionGroupRecord],
+) -> IntersectionDiff {
+    let mut diff = IntersectionDiff::default();
+
+    let reference = index_by_point(reference, None);
+    let candidate = index_by_point(candidate, Some(&mut diff.duplicated));
+
+    for (point, r) in &reference {
+        match candidate.get(point) {
+            None => diff.missing.push((*r).clone()),
+            Some(c) => {
+                if normalized(r) != normalized(c) {
+                    diff.misclassified.push(MisclassifiedGroup {
+                        reference: (*r).clone(),
+                        candidate: (*c).clone(),
+                    });
+                }
+            }
+        }
+    }
+    for (point, c) in &candidate {
+        if !reference.contains_key(point) {
+            diff.extra.push((*c).clone());
+        }
+    }
+    diff
+}
+
+/// 对同一组线段运行扫描线与暴力参考实现（全部线段对）并比对。
+pub fn check_against_naive(
+    segments: &Segments,
+) -> Result<IntersectionDiff, crate::sweep::bo::BoError> {
+    let candidate = crate::sweep::bo::enumerate_point_intersections(segments)?;
+    let reference = enumerate_point_intersections_naive(segments);
+    Ok(compare_point_intersections(&reference, &candidate))
+}
+
+/// 同 `check_against_naive`，但参考结果改用包围盒筛选版（`enumerate_point_intersections_naive_indexed`）；
+/// 适合较大的稀疏输入，代价是裁判本身依赖 `SegmentIndex`。
+pub fn check_against_naive_indexed(
+    segments: &Segments,
+) -> Result<IntersectionDiff, crate::sweep::bo::BoError> {
+    let candidate = crate::sweep::bo::enumerate_point_intersections(segments)?;
+    let reference = enumerate_point_intersections_naive_indexed(segments);
+    Ok(compare_point_intersections(&reference, &candidate))
+}
+
+fn index_by_point<'a>(
+    records: &'a [PointIntersectionGroupRecord],
+    mut duplicated: Option<&mut Vec<PointRat>>,
+) -> BTreeMap<PointRat, &'a PointIntersectionGroupRecord> {
+    let mut out: BTreeMap<PointRat, &PointIntersectionGroupRecord> = BTreeMap::new();
+    let mut seen_twice: BTreeSet<PointRat> = BTreeSet::new();
+    for r in records {
+        match out.entry(r.point) {
+            Entry::Occupied(_) => {
+                seen_twice.insert(r.point);
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(r);
+            }
+        }
+    }
+    if let Some(duplicated) = duplicated.as_mut() {
+        duplicated.extend(seen_twice);
+    }
+    out
+}
+
+fn normalized(r: &PointIntersectionGroupRecord) -> (Vec<SegmentId>, Vec<SegmentId>) {
+    let mut endpoint = r.endpoint_segments.clone();
+    let mut interior = r.interior_segments.clone();
+    endpoint.sort();
+    endpoint.dedup();
+    interior.sort();
+    interior.dedup();
+    (endpoint, interior)
+}
+
+fn format_group(r: &PointIntersectionGroupRecord) -> String {
+    let ids = |ids: &[SegmentId]| {
+        ids.iter()
+            .map(|id| id.0.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    format!(
+        "({}, {}) endpoint=[{}] interior=[{}]",
+        r.point.x,
+        r.point.y,
+        ids(&r.endpoint_segments),
+        ids(&r.interior_segments)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::segment::segments_from;
+    use crate::rational::Rational;
+
+    #[test]
+    fn sweep_matches_naive_when_vertical_passes_through_crossing() {
+        // 回归：竖线穿过两条斜线的交点时，该点曾被 PointBatch 与 VerticalFlush 各输出一次。
+        let segments = segments_from(&[(-1, 0, 1, 0), (0, -1, 0, 1), (-1, -1, 1, 1)]);
+        let diff = check_against_naive(&segments).unwrap();
+        assert!(diff.is_empty(), "{diff}");
+        let diff = check_against_naive_indexed(&segments).unwrap();
+        assert!(diff.is_empty(), "{diff}");
+
+        let reference = enumerate_point_intersections_naive(&segments);
+        assert_eq!(
+            reference,
+            vec![PointIntersectionGroupRecord {
+                point: PointRat {
+                    x: Rational::from_int(0),
+                    y: Rational::from_int(0),
+                },
+                endpoint_segments: vec![],
+                interior_segments: vec![SegmentId(0), SegmentId(1), SegmentId(2)],
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_missing_extra_misclassified_and_duplicated() {
+        let at = |x: i128, y: i128, endpoint: &[usize], interior: &[usize]| {
+            PointIntersectionGroupRecord {
+                point: PointRat {
+                    x: Rational::from_int(x),
+                    y: Rational::from_int(y),
+                },
+                endpoint_segments: endpoint.iter().map(|&i| SegmentId(i)).collect(),
+                interior_segments: interior.iter().map(|&i| SegmentId(i)).collect(),
+            }
+        };
+        let reference = vec![
+            at(0, 0, &[], &[0, 1]),
+            at(1, 1, &[0], &[2]),
+            at(2, 2, &[1, 2], &[]),
+        ];
+        let candidate = vec![
+            at(3, 3, &[], &[0, 3]),
+            at(1, 1, &[0, 2], &[]),
+            at(2, 2, &[2, 1], &[]),
+            at(2, 2, &[1, 2], &[]),
+        ];
+
+        let diff = compare_point_intersections(&reference, &candidate);
+        assert_eq!(diff.missing, vec![reference[0].clone()]);
+        assert_eq!(diff.extra, vec![candidate[0].clone()]);
+        assert_eq!(
+            diff.misclassified,
+            vec![MisclassifiedGroup {
+                reference: reference[1].clone(),
+                candidate: candidate[1].clone(),
+            }]
+        );
+        assert_eq!(diff.duplicated, vec![reference[2].point]);
+        assert!(diff.to_string().starts_with("缺失 1 个点，多出 1 个点"));
+    }
+
+    #[test]
+    fn sweep_matches_naive_on_dense_small_grid() {
+        // 在 5x5 网格上确定性地枚举大量退化组合（垂直、水平、共线、同点多线、端点落在内部）。
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move |n: i64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n as u64) as i64
+        };
+        for _ in 0..300 {
+            let count = 2 + next(7) as usize;
+            let mut coords = Vec::new();
+            while coords.len() < count {
+                let c = (next(5), next(5), next(5), next(5));
+                // 扫描线的输入契约：非零长度、无完全重复（含反向）——与预处理一致。
+                let reversed = (c.2, c.3, c.0, c.1);
+                if (c.0, c.1) != (c.2, c.3) && !coords.contains(&c) && !coords.contains(&reversed) {
+                    coords.push(c);
+                }
+            }
+            let segments = segments_from(&coords);
+            let diff = check_against_naive(&segments).unwrap();
+            assert!(diff.is_empty(), "输入 {coords:?}\n{diff}");
+        }
+    }
+}
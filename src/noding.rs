@@ -0,0 +1,255 @@
+//! 线段切分（noding）：把每条线段在其上的全部交点处切开，得到首尾相接的子段。
+//!
+//! 约定：
+//! - 切分点 = 线段两端点 + 该线段以 `interior_segments` 身份参与的全部点组（端点接触不产生新的切分点）
+//!   + 落在其内部的共线线段端点；
+//! - 切分点沿线段方向 `a -> b` 排列（即 `PointRat` 升序）、去重，子段端点为精确有理坐标；
+//! - 扫描线与 `crate::naive` 一样，不把“端点落在共线线段内部”作为点组输出，这类切分点由
+//!   `crate::overlap::collinear_groups` 补上，因此重叠部分在每条参与线段上切出相同的子段
+//!   （重叠段本身见 `crate::overlap`）。
+
+use crate::geom::fixed::{Coord, PointI64};
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::point::PointRat;
+use crate::geom::segment::{SegmentId, Segments};
+use crate::overlap::collinear_groups;
+use crate::rational::Rational;
+
+/// 一条线段的切分结果。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodedSegment {
+    pub segment: SegmentId,
+    /// 沿线段方向的切分点（首尾为线段端点，至少 2 个）。
+    pub split_points: Vec<PointRat>,
+}
+
+/// 精确子段（`a < b`，沿原线段方向）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NodedPiece {
+    pub a: PointRat,
+    pub b: PointRat,
+}
+
+/// 吸附到整数网格后的子段。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnappedPiece {
+    pub a: PointI64,
+    pub b: PointI64,
+}
+
+impl NodedSegment {
+    /// 相邻切分点之间的子段（按线段方向）。
+    pub fn pieces(&self) -> Vec<NodedPiece> {
+        self.split_points
+            .windows(2)
+            .map(|w| NodedPiece { a: w[0], b: w[1] })
+            .collect()
+    }
+
+    /// 把切分点逐个吸附到整数网格（见 `snap_to_grid`）后的子段。
+    ///
+    /// 说明：
+    /// - 两端吸附到同一网格点的子段被丢弃，其余子段仍首尾相接；
+    /// - 吸附只是逐点取整，可能引入新的交叉/重叠，不保证结果仍是切分好的线网
+    ///   （需要拓扑保证时应使用完整的 snap rounding）。
+    pub fn snapped_pieces(&self) -> Vec<SnappedPiece> {
+        let points: Vec<PointI64> = self.split_points.iter().map(|p| snap_to_grid(*p)).collect();
+        points
+            .windows(2)
+            .filter(|w| w[0] != w[1])
+            .map(|w| SnappedPiece { a: w[0], b: w[1] })
+            .collect()
+    }
+}
+
+/// 按 `SegmentId` 顺序输出每条线段的切分结果（`records` 应为 `segments` 的完整点交结果）。
+pub fn node_segments(
+    segments: &Segments,
+    records: &[PointIntersectionGroupRecord],
+) -> Vec<NodedSegment> {
+    let mut split_points: Vec<Vec<PointRat>> = segments
+        .iter()
+        .map(|s| vec![PointRat::from_i64(s.a), PointRat::from_i64(s.b)])
+        .collect();
+    for r in records {
+        for id in &r.interior_segments {
+            split_points[id.0].push(r.point);
+        }
+    }
+    // 共线组内：其他线段落在本线段内部的端点（按 1D 投影去重后二分查找开区间）。
+    for group in collinear_groups(segments) {
+        let mut ends: Vec<_> = group
+            .segments
+            .iter()
+            .flat_map(|&id| {
+                let seg = segments.get(id);
+                [seg.a, seg.b]
+            })
+            .map(|p| (group.project(p), p))
+            .collect();
+        ends.sort();
+        ends.dedup();
+        for &id in &group.segments {
+            let (lo, hi) = group.span(segments.get(id));
+            let from = ends.partition_point(|&(t, _)| t <= lo);
+            let to = ends.partition_point(|&(t, _)| t < hi);
+            split_points[id.0].extend(ends[from..to].iter().map(|&(_, p)| PointRat::from_i64(p)));
+        }
+    }
+
+    split_points
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut points)| {
+            points.sort();
+            points.dedup();
+            NodedSegment {
+                segment: SegmentId(i),
+                split_points: points,
+            }
+        })
+        .collect()
+}
+
+/// 有理点吸附到最近的整数网格点。
+///
+/// 取整规则：每个坐标独立取 `floor(v + 1/2)`，即四舍五入、恰好一半时向 `+∞` 取整
+/// （`1/2 -> 1`，`-1/2 -> 0`），与输入顺序和线段方向无关，保证同一交点在所有线段上吸附结果一致。
+pub fn snap_to_grid(point: PointRat) -> PointI64 {
+    PointI64 {
+        x: round_half_up(point.x, 1),
+        y: round_half_up(point.y, 1),
+    }
+}
+
+/// 取最近的 `s` 的整数倍：`s * floor(v/s + 1/2)`（`1 <= s <= 2 * MAX_ABS_COORD`，恰好一半时向 `+∞` 取整）。
+pub(crate) fn round_half_up(v: Rational, s: Coord) -> Coord {
+    // floor((2*num + s*den) / (2*s*den))；分母恒为正。交点分子约为坐标的三次方量级、分母约为平方量级，
+    // 在上述 `s` 范围内中间量不会溢出 i128，结果的绝对值不超过 `|v| + s`。
+    let (num, den, s) = (v.num(), v.den(), s as i128);
+    ((2 * num + s * den).div_euclid(2 * s * den) * s) as Coord
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocess::InputSegmentI64;
+    use crate::run::{Phase1Options, run_phase1_i64_with_options};
+
+    fn at(x: (i128, i128), y: (i128, i128)) -> PointRat {
+        PointRat {
+            x: Rational::new(x.0, x.1),
+            y: Rational::new(y.0, y.1),
+        }
+    }
+
+    #[test]
+    fn splits_at_interior_hits_and_snaps_half_up() {
+        let seg = |ax, ay, bx, by| InputSegmentI64 { ax, ay, bx, by };
+        let input = [
+            seg(0, 0, 3, 1),
+            seg(0, 1, 3, 0),
+            seg(1, 0, 1, 2),
+            // 端点落在第 0 条线段的端点上：不产生新的切分点。
+            seg(3, 1, 4, 4),
+        ];
+        let out = run_phase1_i64_with_options(&input, &Phase1Options::default()).unwrap();
+        let noded = out.node_segments();
+        assert_eq!(noded.len(), 4);
+
+        assert_eq!(
+            noded[0].split_points,
+            vec![
+                at((0, 1), (0, 1)),
+                at((1, 1), (1, 3)),
+                at((3, 2), (1, 2)),
+                at((3, 1), (1, 1)),
+            ]
+        );
+        assert_eq!(
+            noded[2].split_points,
+            vec![
+                at((1, 1), (0, 1)),
+                at((1, 1), (1, 3)),
+                at((1, 1), (2, 3)),
+                at((1, 1), (2, 1)),
+            ]
+        );
+        assert_eq!(noded[3].pieces().len(), 1);
+        assert_eq!(
+            noded[0].pieces()[1],
+            NodedPiece {
+                a: at((1, 1), (1, 3)),
+                b: at((3, 2), (1, 2)),
+            }
+        );
+
+        let p = |x, y| PointI64 { x, y };
+        // (1,1/3) -> (1,0)，(3/2,1/2) -> (2,1)。
+        assert_eq!(
+            noded[0].snapped_pieces(),
+            vec![
+                SnappedPiece {
+                    a: p(0, 0),
+                    b: p(1, 0),
+                },
+                SnappedPiece {
+                    a: p(1, 0),
+                    b: p(2, 1),
+                },
+                SnappedPiece {
+                    a: p(2, 1),
+                    b: p(3, 1),
+                },
+            ]
+        );
+        // 竖线的第一段两端都吸附到 (1,0)，被丢弃。
+        assert_eq!(
+            noded[2].snapped_pieces(),
+            vec![
+                SnappedPiece {
+                    a: p(1, 0),
+                    b: p(1, 1),
+                },
+                SnappedPiece {
+                    a: p(1, 1),
+                    b: p(1, 2),
+                },
+            ]
+        );
+        assert_eq!(snap_to_grid(at((-1, 2), (-3, 2))), p(0, -1));
+    }
+
+    #[test]
+    fn splits_collinear_overlaps_at_each_others_endpoints() {
+        let seg = |ax, ay, bx, by| InputSegmentI64 { ax, ay, bx, by };
+        let input = [
+            seg(0, 0, 4, 4),
+            seg(2, 2, 6, 6),
+            seg(1, 1, 3, 3),
+            // 同一直线上但不相交：不影响其他线段。
+            seg(8, 8, 9, 9),
+            seg(0, 10, 5, 10),
+            seg(1, 10, 2, 10),
+        ];
+        let out = run_phase1_i64_with_options(&input, &Phase1Options::default()).unwrap();
+        let noded = out.node_segments();
+        let on_diagonal = |v: &[i128]| v.iter().map(|&t| at((t, 1), (t, 1))).collect::<Vec<_>>();
+        let on_row = |v: &[i128]| v.iter().map(|&t| at((t, 1), (10, 1))).collect::<Vec<_>>();
+
+        let by_start = |x: i64, y: i64| {
+            noded
+                .iter()
+                .find(|n| n.split_points[0] == PointRat::from_i64(PointI64 { x, y }))
+                .unwrap()
+                .split_points
+                .clone()
+        };
+        assert_eq!(by_start(0, 0), on_diagonal(&[0, 1, 2, 3, 4]));
+        assert_eq!(by_start(2, 2), on_diagonal(&[2, 3, 4, 6]));
+        assert_eq!(by_start(1, 1), on_diagonal(&[1, 2, 3]));
+        assert_eq!(by_start(8, 8), on_diagonal(&[8, 9]));
+        assert_eq!(by_start(0, 10), on_row(&[0, 1, 2, 5]));
+        assert_eq!(by_start(1, 10), on_row(&[1, 2]));
+    }
+}
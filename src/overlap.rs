@@ -0,0 +1,456 @@
+//! Phase 2：共线重叠（“覆盖≥2 的极大连续区间”）。
+//!
+//! 语义见 `plans/phase2-precheck.md` 的 1)/2)/4)：
+//! - 对每组共线线段做 1D 投影，记覆盖次数为 `c(t)`（闭区间，包含端点）；
+//! - `c(t) ≥ 2` 且长度>0 的连通分量输出为“重叠段”；
+//! - 仅在单点处满足 `c(t) ≥ 2` 的退化连通分量输出为“重叠点”；
+//! - 覆盖次数仅基于预处理后的线段集合（重复输入已在预处理阶段去重）。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::geom::fixed::{Coord, PointI64};
+use crate::geom::segment::{Segment, SegmentId, Segments};
+
+/// 共线重叠段：某组共线线段上 `c(t) ≥ 2` 的极大连续区间（长度>0）。
+///
+/// 说明：
+/// - `a < b`（按 `(x,y)` 字典序），两端点均为输入线段的端点（整数网格点）；
+/// - `segments`：与该区间有长度>0 重叠的线段集合（去重、升序）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverlapSegmentRecord {
+    pub a: PointI64,
+    pub b: PointI64,
+    pub segments: Vec<SegmentId>,
+}
+
+/// 共线重叠点：仅在单点处满足 `c(t) ≥ 2`（共线线段首尾相接）。
+///
+/// 说明：`segments` 为在该点以端点参与的线段集合（去重、升序）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverlapPointRecord {
+    pub point: PointI64,
+    pub segments: Vec<SegmentId>,
+}
+
+/// 所有共线组的重叠结果（按几何位置升序，便于回归与复现）。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CollinearOverlaps {
+    pub segments: Vec<OverlapSegmentRecord>,
+    pub points: Vec<OverlapPointRecord>,
+}
+
+/// 覆盖分层：某组共线线段上覆盖线段集合恒为 `segments`（覆盖次数 `coverage` 即其大小）的极大连续区间（长度>0）。
+///
+/// 说明：
+/// - 覆盖次数按相邻断点之间的开区间计（断点本身的覆盖可能更高，例如首尾相接处）；
+/// - `segments`：与该区间有长度>0 重叠的线段集合（去重、升序）；覆盖次数相同但线段集合不同的相邻区间分属不同的层。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageLayerRecord {
+    pub a: PointI64,
+    pub b: PointI64,
+    pub coverage: usize,
+    pub segments: Vec<SegmentId>,
+}
+
+/// 一组共线线段（至少 2 条，`segments` 升序）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollinearGroup {
+    pub vertical: bool,
+    pub segments: Vec<SegmentId>,
+}
+
+impl CollinearGroup {
+    /// 线段端点在该组支撑直线上的 1D 投影：非垂直取 `x`，垂直取 `y`。
+    pub fn project(&self, p: PointI64) -> Coord {
+        if self.vertical { p.y } else { p.x }
+    }
+
+    /// 线段在该组 1D 投影上的闭区间 `[lo, hi]`（端点已规范化，因此 `lo < hi`）。
+    pub fn span(&self, seg: &Segment) -> (Coord, Coord) {
+        (self.project(seg.a), self.project(seg.b))
+    }
+}
+
+/// 支撑直线的规范表示：约分后的方向 `(dx, dy)`（`dx > 0`，或 `dx == 0 && dy > 0`）+ 截距项 `dy*x - dx*y`。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct LineKey {
+    dx: i128,
+    dy: i128,
+    offset: i128,
+}
+
+fn line_key(seg: &Segment) -> LineKey {
+    let dx = (seg.b.x as i128) - (seg.a.x as i128);
+    let dy = (seg.b.y as i128) - (seg.a.y as i128);
+    debug_assert!(
+        dx > 0 || (dx == 0 && dy > 0),
+        "线段端点应已按 (x,y) 字典序规范化"
+    );
+
+    let g = gcd_u128(dx.unsigned_abs(), dy.unsigned_abs()) as i128;
+    let (dx, dy) = (dx / g, dy / g);
+    let offset = dy * (seg.a.x as i128) - dx * (seg.a.y as i128);
+    LineKey { dx, dy, offset }
+}
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// 按支撑直线对线段分组，只返回包含至少 2 条线段的组（组间按直线键升序，组内按 `SegmentId` 升序）。
+///
+/// 说明：同一直线上的线段未必相互重叠（可能相离或首尾相接）。
+pub fn collinear_groups(segments: &Segments) -> Vec<CollinearGroup> {
+    let mut by_line: BTreeMap<LineKey, Vec<SegmentId>> = BTreeMap::new();
+    for (i, seg) in segments.iter().enumerate() {
+        by_line.entry(line_key(seg)).or_default().push(SegmentId(i));
+    }
+
+    by_line
+        .into_iter()
+        .filter(|(_, ids)| ids.len() >= 2)
+        .map(|(key, ids)| CollinearGroup {
+            vertical: key.dx == 0,
+            segments: ids,
+        })
+        .collect()
+}
+
+/// 计算所有共线组的“最大重叠段集合”与“重叠点”。
+pub fn enumerate_collinear_overlaps(segments: &Segments) -> CollinearOverlaps {
+    let mut out = CollinearOverlaps::default();
+    for group in collinear_groups(segments) {
+        collect_group_overlaps(segments, &group, &mut out);
+    }
+    out.segments.sort_by_key(|r| (r.a, r.b));
+    out.points.sort_by_key(|r| r.point);
+    out
+}
+
+/// 共线组在 1D 投影上的一个断点（某些线段的起点和/或终点）。
+#[derive(Default)]
+pub(crate) struct Breakpoint {
+    pub(crate) point: Option<PointI64>,
+    pub(crate) starts: Vec<SegmentId>,
+    pub(crate) ends: Vec<SegmentId>,
+}
+
+/// 收集共线组的所有断点（按投影坐标升序）。
+pub(crate) fn group_breakpoints(
+    segments: &Segments,
+    group: &CollinearGroup,
+) -> BTreeMap<Coord, Breakpoint> {
+    let mut breakpoints: BTreeMap<Coord, Breakpoint> = BTreeMap::new();
+    for &id in &group.segments {
+        let seg = segments.get(id);
+        let (lo, hi) = group.span(seg);
+        let start = breakpoints.entry(lo).or_default();
+        start.point = Some(seg.a);
+        start.starts.push(id);
+        let end = breakpoints.entry(hi).or_default();
+        end.point = Some(seg.b);
+        end.ends.push(id);
+    }
+    breakpoints
+}
+
+/// 相邻断点之间开区间的覆盖次数 `c(t)`（长度为断点数 - 1）。
+///
+/// 闭区间语义下，断点右侧的覆盖 = 左侧覆盖 + 起点数 - 终点数。
+fn gap_coverage(breakpoints: &BTreeMap<Coord, Breakpoint>) -> Vec<usize> {
+    let mut gap_cover: Vec<usize> = Vec::with_capacity(breakpoints.len().saturating_sub(1));
+    let mut cover = 0_usize;
+    for (i, bp) in breakpoints.values().enumerate() {
+        cover = cover + bp.starts.len() - bp.ends.len();
+        if i + 1 < breakpoints.len() {
+            gap_cover.push(cover);
+        }
+    }
+    gap_cover
+}
+
+/// 计算所有共线组的覆盖分层（按 `(a,b)` 升序）：可直接回答“哪一段恰好被 k 条线段覆盖、被哪些线段覆盖”。
+///
+/// 说明：只处理包含至少 2 条线段的共线组；其余线段的覆盖次数恒为 1。
+pub fn coverage_layers(segments: &Segments) -> Vec<CoverageLayerRecord> {
+    let mut out = Vec::new();
+    for group in collinear_groups(segments) {
+        let breakpoints = group_breakpoints(segments, &group);
+        let gap_cover = gap_coverage(&breakpoints);
+        let points: Vec<PointI64> = breakpoints
+            .values()
+            .map(|bp| bp.point.expect("断点应来自线段端点"))
+            .collect();
+
+        let mut active: BTreeSet<SegmentId> = BTreeSet::new();
+        let mut current: Option<CoverageLayerRecord> = None;
+        for (i, bp) in breakpoints.values().enumerate() {
+            for id in &bp.ends {
+                active.remove(id);
+            }
+            active.extend(bp.starts.iter().copied());
+
+            let Some(&coverage) = gap_cover.get(i) else {
+                break;
+            };
+            match current.as_mut() {
+                Some(layer) if layer.segments.iter().eq(active.iter()) => {
+                    layer.b = points[i + 1];
+                }
+                _ => {
+                    if let Some(layer) = current.take() {
+                        out.push(layer);
+                    }
+                    if coverage != 0 {
+                        current = Some(CoverageLayerRecord {
+                            a: points[i],
+                            b: points[i + 1],
+                            coverage,
+                            segments: active.iter().copied().collect(),
+                        });
+                    }
+                }
+            }
+        }
+        if let Some(layer) = current.take() {
+            out.push(layer);
+        }
+    }
+    out.sort_by_key(|r| (r.a, r.b));
+    out
+}
+
+fn collect_group_overlaps(
+    segments: &Segments,
+    group: &CollinearGroup,
+    out: &mut CollinearOverlaps,
+) {
+    let breakpoints = group_breakpoints(segments, group);
+
+    let ts: Vec<Coord> = breakpoints.keys().copied().collect();
+    let gap_cover = gap_coverage(&breakpoints);
+
+    // 连续的 `c ≥ 2` 开区间（及其共享断点）合并为一个极大重叠段。
+    let mut components: Vec<(Coord, Coord)> = Vec::new();
+    let mut i = 0;
+    while i < gap_cover.len() {
+        if gap_cover[i] < 2 {
+            i += 1;
+            continue;
+        }
+        let lo = ts[i];
+        while i < gap_cover.len() && gap_cover[i] >= 2 {
+            i += 1;
+        }
+        components.push((lo, ts[i]));
+    }
+
+    let mut contributors: Vec<Vec<SegmentId>> = vec![Vec::new(); components.len()];
+    for &id in &group.segments {
+        let (lo, hi) = group.span(segments.get(id));
+        let first = components.partition_point(|&(_, c_hi)| c_hi <= lo);
+        for (k, &(c_lo, _)) in components.iter().enumerate().skip(first) {
+            if c_lo >= hi {
+                break;
+            }
+            contributors[k].push(id);
+        }
+    }
+
+    for ((lo, hi), ids) in components.into_iter().zip(contributors) {
+        out.segments.push(OverlapSegmentRecord {
+            a: breakpoints[&lo].point.expect("断点应来自线段端点"),
+            b: breakpoints[&hi].point.expect("断点应来自线段端点"),
+            segments: ids,
+        });
+    }
+
+    // 退化分量：两侧开区间覆盖都 < 2，但该点同时是某线段终点与另一线段起点。
+    // 此时不存在跨越该点的线段（否则必有一侧覆盖 ≥ 2），因此参与者恰为端点集合。
+    for (i, bp) in breakpoints.values().enumerate() {
+        let left = if i == 0 { 0 } else { gap_cover[i - 1] };
+        let right = gap_cover.get(i).copied().unwrap_or(0);
+        if left >= 2 || right >= 2 || bp.starts.is_empty() || bp.ends.is_empty() {
+            continue;
+        }
+        let mut ids: Vec<SegmentId> = bp.starts.iter().chain(&bp.ends).copied().collect();
+        ids.sort();
+        out.points.push(OverlapPointRecord {
+            point: bp.point.expect("断点应来自线段端点"),
+            segments: ids,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(segments: &mut Segments, ax: i64, ay: i64, bx: i64, by: i64) -> SegmentId {
+        let (a, b) = (PointI64 { x: ax, y: ay }, PointI64 { x: bx, y: by });
+        let (a, b) = if b < a { (b, a) } else { (a, b) };
+        let source_index = segments.len();
+        segments.push(Segment { a, b, source_index })
+    }
+
+    #[test]
+    fn outputs_maximal_coverage_two_interval() {
+        // `plans/phase2-precheck.md` 的裁判例子：s1=[0,8]，s2=[2,8]，s3=[6,12] => [2,8]。
+        let mut segments = Segments::new();
+        let s1 = push(&mut segments, 0, 0, 8, 8);
+        let s2 = push(&mut segments, 2, 2, 8, 8);
+        let s3 = push(&mut segments, 6, 6, 12, 12);
+        push(&mut segments, 0, 1, 12, 1);
+
+        let out = enumerate_collinear_overlaps(&segments);
+        assert_eq!(
+            out.segments,
+            vec![OverlapSegmentRecord {
+                a: PointI64 { x: 2, y: 2 },
+                b: PointI64 { x: 8, y: 8 },
+                segments: vec![s1, s2, s3],
+            }]
+        );
+        assert!(out.points.is_empty());
+    }
+
+    #[test]
+    fn outputs_overlap_point_for_collinear_end_to_start_touch() {
+        let mut segments = Segments::new();
+        let a = push(&mut segments, 0, 0, 0, 5);
+        let b = push(&mut segments, 0, 10, 0, 5);
+        push(&mut segments, 0, 20, 0, 30);
+
+        let out = enumerate_collinear_overlaps(&segments);
+        assert!(out.segments.is_empty());
+        assert_eq!(
+            out.points,
+            vec![OverlapPointRecord {
+                point: PointI64 { x: 0, y: 5 },
+                segments: vec![a, b],
+            }]
+        );
+    }
+
+    #[test]
+    fn splits_components_where_coverage_drops_below_two() {
+        let mut segments = Segments::new();
+        let long = push(&mut segments, 0, 0, 30, 0);
+        let left = push(&mut segments, 5, 0, 10, 0);
+        let right = push(&mut segments, 20, 0, 25, 0);
+        let touching = push(&mut segments, 25, 0, 40, 0);
+
+        let out = enumerate_collinear_overlaps(&segments);
+        assert_eq!(
+            out.segments,
+            vec![
+                OverlapSegmentRecord {
+                    a: PointI64 { x: 5, y: 0 },
+                    b: PointI64 { x: 10, y: 0 },
+                    segments: vec![long, left],
+                },
+                OverlapSegmentRecord {
+                    a: PointI64 { x: 20, y: 0 },
+                    b: PointI64 { x: 30, y: 0 },
+                    segments: vec![long, right, touching],
+                },
+            ]
+        );
+        assert!(out.points.is_empty());
+    }
+
+    #[test]
+    fn layers_collinear_groups_by_exact_coverage() {
+        let mut segments = Segments::new();
+        let s1 = push(&mut segments, 0, 0, 8, 0);
+        let s2 = push(&mut segments, 2, 0, 8, 0);
+        let s3 = push(&mut segments, 6, 0, 12, 0);
+        let s4 = push(&mut segments, 20, 0, 25, 0);
+
+        let layers = coverage_layers(&segments);
+        assert_eq!(
+            layers,
+            vec![
+                CoverageLayerRecord {
+                    a: PointI64 { x: 0, y: 0 },
+                    b: PointI64 { x: 2, y: 0 },
+                    coverage: 1,
+                    segments: vec![s1],
+                },
+                CoverageLayerRecord {
+                    a: PointI64 { x: 2, y: 0 },
+                    b: PointI64 { x: 6, y: 0 },
+                    coverage: 2,
+                    segments: vec![s1, s2],
+                },
+                CoverageLayerRecord {
+                    a: PointI64 { x: 6, y: 0 },
+                    b: PointI64 { x: 8, y: 0 },
+                    coverage: 3,
+                    segments: vec![s1, s2, s3],
+                },
+                CoverageLayerRecord {
+                    a: PointI64 { x: 8, y: 0 },
+                    b: PointI64 { x: 12, y: 0 },
+                    coverage: 1,
+                    segments: vec![s3],
+                },
+                CoverageLayerRecord {
+                    a: PointI64 { x: 20, y: 0 },
+                    b: PointI64 { x: 25, y: 0 },
+                    coverage: 1,
+                    segments: vec![s4],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_adjacent_gaps_with_equal_coverage_but_different_segments() {
+        let mut segments = Segments::new();
+        let a = push(&mut segments, 0, 0, 0, 10);
+        let b = push(&mut segments, 0, 0, 0, 5);
+        let c = push(&mut segments, 0, 5, 0, 10);
+
+        let layers = coverage_layers(&segments);
+        assert_eq!(
+            layers,
+            vec![
+                CoverageLayerRecord {
+                    a: PointI64 { x: 0, y: 0 },
+                    b: PointI64 { x: 0, y: 5 },
+                    coverage: 2,
+                    segments: vec![a, b],
+                },
+                CoverageLayerRecord {
+                    a: PointI64 { x: 0, y: 5 },
+                    b: PointI64 { x: 0, y: 10 },
+                    coverage: 2,
+                    segments: vec![a, c],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_by_supporting_line_with_reduced_direction() {
+        let mut segments = Segments::new();
+        let a = push(&mut segments, 0, 0, 2, 4);
+        let b = push(&mut segments, 1, 2, 3, 6);
+        push(&mut segments, 0, 1, 2, 5);
+
+        let groups = collinear_groups(&segments);
+        assert_eq!(
+            groups,
+            vec![CollinearGroup {
+                vertical: false,
+                segments: vec![a, b],
+            }]
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use core::fmt;
 use std::collections::BTreeMap;
 
-use crate::geom::fixed::{PointI64, QuantizeError, quantize_coord};
-use crate::geom::segment::{Segment, SegmentId, SegmentKey, Segments};
+use crate::geom::fixed::{Coord, MAX_ABS_COORD, PointI64, Quantization, QuantizeError};
+use crate::geom::segment::{LayerId, Segment, SegmentId, SegmentKey, Segments};
 
 #[derive(Clone, Copy, Debug)]
 pub struct InputSegmentF64 {
@@ -12,6 +12,15 @@ pub struct InputSegmentF64 {
     pub by: f64,
 }
 
+/// 整数网格坐标的输入线段（绕过 `f64` 量化，见 `preprocess_segments_i64`）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputSegmentI64 {
+    pub ax: Coord,
+    pub ay: Coord,
+    pub bx: Coord,
+    pub by: Coord,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InputCoord {
     Ax,
@@ -71,73 +80,188 @@ impl fmt::Display for Warning {
 
 #[derive(Clone, Debug, Default)]
 pub struct PreprocessOutput {
+    /// 本次预处理使用的量化变换（用于把网格坐标映射回调用方坐标系）。
+    pub quantization: Quantization,
     pub segments: Segments,
     pub input_to_segment: Vec<Option<SegmentId>>,
     pub warnings: Vec<Warning>,
 }
 
+impl PreprocessOutput {
+    /// 按原始输入的图层得到每条预处理后线段的图层（下标与 `SegmentId` 一一对应）。
+    ///
+    /// 说明：跨图层的完全重复输入在去重时只保留第一条，其图层即为保留者的图层。
+    pub fn segment_layers(&self, input_layers: &[LayerId]) -> Vec<LayerId> {
+        self.segments
+            .iter()
+            .map(|s| input_layers[s.source_index])
+            .collect()
+    }
+
+    /// 每条预处理后线段对应的全部原始输入下标（升序）：保留者本身 + 被判为其重复而丢弃的输入。
+    ///
+    /// 下标与 `SegmentId` 一一对应。
+    pub fn segment_inputs(&self) -> Vec<Vec<usize>> {
+        let mut out: Vec<Vec<usize>> = self.segments.iter().map(|s| vec![s.source_index]).collect();
+        let mut kept_to_segment: BTreeMap<usize, SegmentId> = BTreeMap::new();
+        for (input_index, id) in self.input_to_segment.iter().enumerate() {
+            if let Some(id) = id {
+                kept_to_segment.insert(input_index, *id);
+            }
+        }
+        for w in &self.warnings {
+            if let WarningKind::DroppedDuplicate { kept_input_index } = w.kind
+                && let Some(id) = kept_to_segment.get(&kept_input_index)
+            {
+                out[id.0].push(w.input_index);
+            }
+        }
+        for inputs in &mut out {
+            inputs.sort_unstable();
+        }
+        out
+    }
+}
+
+/// 量化变换的选择方式。
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum QuantizationMode {
+    /// 输入坐标限定在 `[-1, 1]`，乘以 `SCALE`（历史默认行为）。
+    #[default]
+    Unit,
+    /// 由输入包围盒自动确定（见 `fit_quantization`；包围盒退化为单点时的回退见 `Quantization::fit_bounds`）。
+    Auto,
+    /// 由调用方给定。
+    Fixed(Quantization),
+}
+
+impl QuantizationMode {
+    pub fn resolve(&self, input: &[InputSegmentF64]) -> Quantization {
+        match self {
+            QuantizationMode::Unit => Quantization::unit(),
+            QuantizationMode::Auto => fit_quantization(input),
+            QuantizationMode::Fixed(q) => *q,
+        }
+    }
+}
+
+/// 按输入包围盒自动选择量化变换：居中并等比放大到 `[-SCALE, SCALE]`。
+///
+/// 说明：
+/// - 包围盒只统计有限坐标（非有限坐标仍会在预处理中作为无效坐标丢弃）；没有任何有限坐标时返回 `Quantization::unit()`；
+/// - 全部有限坐标为同一点时按 `Quantization::fit_bounds` 的单点回退处理（以该点为原点、`grid_per_unit = SCALE`）。
+pub fn fit_quantization(input: &[InputSegmentF64]) -> Quantization {
+    let mut bounds: Option<(f64, f64, f64, f64)> = None;
+    for seg in input {
+        for (x, y) in [(seg.ax, seg.ay), (seg.bx, seg.by)] {
+            let x = x.is_finite().then_some(x);
+            let y = y.is_finite().then_some(y);
+            let (min_x, min_y, max_x, max_y) = bounds.get_or_insert((
+                x.unwrap_or(f64::INFINITY),
+                y.unwrap_or(f64::INFINITY),
+                x.unwrap_or(f64::NEG_INFINITY),
+                y.unwrap_or(f64::NEG_INFINITY),
+            ));
+            if let Some(x) = x {
+                *min_x = min_x.min(x);
+                *max_x = max_x.max(x);
+            }
+            if let Some(y) = y {
+                *min_y = min_y.min(y);
+                *max_y = max_y.max(y);
+            }
+        }
+    }
+    let Some((min_x, min_y, max_x, max_y)) = bounds else {
+        return Quantization::unit();
+    };
+    // 只有某一维完全没有有限坐标时，该维退化为 0。
+    let (min_x, max_x) = if min_x <= max_x {
+        (min_x, max_x)
+    } else {
+        (0.0, 0.0)
+    };
+    let (min_y, max_y) = if min_y <= max_y {
+        (min_y, max_y)
+    } else {
+        (0.0, 0.0)
+    };
+    Quantization::fit_bounds(min_x, min_y, max_x, max_y).unwrap_or_else(Quantization::unit)
+}
+
 pub fn preprocess_segments(input: &[InputSegmentF64]) -> PreprocessOutput {
+    preprocess_segments_with_quantization(input, &Quantization::unit())
+}
+
+/// 与 `preprocess_segments` 相同，但使用给定的量化变换（映射后超出 `[-SCALE, SCALE]` 的坐标按无效坐标丢弃）。
+pub fn preprocess_segments_with_quantization(
+    input: &[InputSegmentF64],
+    quantization: &Quantization,
+) -> PreprocessOutput {
+    let quantized = input.iter().map(|seg| {
+        let ax = quantization
+            .quantize_x(seg.ax)
+            .map_err(|e| (InputCoord::Ax, e))?;
+        let ay = quantization
+            .quantize_y(seg.ay)
+            .map_err(|e| (InputCoord::Ay, e))?;
+        let bx = quantization
+            .quantize_x(seg.bx)
+            .map_err(|e| (InputCoord::Bx, e))?;
+        let by = quantization
+            .quantize_y(seg.by)
+            .map_err(|e| (InputCoord::By, e))?;
+        Ok((PointI64 { x: ax, y: ay }, PointI64 { x: bx, y: by }))
+    });
+    build_segments(input.len(), quantized, *quantization)
+}
+
+/// 整数坐标输入的预处理：不经过 `f64` 量化，直接使用网格坐标。
+///
+/// 说明：
+/// - 坐标绝对值必须不超过 `MAX_ABS_COORD`（`orient`/`y_at_x` 无溢出的前提），超出者按 `OutOfRange` 丢弃并告警；
+/// - 零长度/重复线段的告警与 `preprocess_segments` 相同；
+/// - 输出的 `quantization` 为 `Quantization::identity()`（网格坐标即输入坐标）。
+pub fn preprocess_segments_i64(input: &[InputSegmentI64]) -> PreprocessOutput {
+    let checked = input.iter().map(|seg| {
+        let ax = check_coord(seg.ax).map_err(|e| (InputCoord::Ax, e))?;
+        let ay = check_coord(seg.ay).map_err(|e| (InputCoord::Ay, e))?;
+        let bx = check_coord(seg.bx).map_err(|e| (InputCoord::Bx, e))?;
+        let by = check_coord(seg.by).map_err(|e| (InputCoord::By, e))?;
+        Ok((PointI64 { x: ax, y: ay }, PointI64 { x: bx, y: by }))
+    });
+    build_segments(input.len(), checked, Quantization::identity())
+}
+
+fn check_coord(value: Coord) -> Result<Coord, QuantizeError> {
+    if value.unsigned_abs() > MAX_ABS_COORD.unsigned_abs() {
+        return Err(QuantizeError::OutOfRange);
+    }
+    Ok(value)
+}
+
+/// 规范化端点，过滤零长度与重复线段（按输入顺序保留首次出现者），并生成告警。
+fn build_segments(
+    input_len: usize,
+    points: impl Iterator<Item = Result<(PointI64, PointI64), (InputCoord, QuantizeError)>>,
+    quantization: Quantization,
+) -> PreprocessOutput {
     let mut segments = Segments::new();
     let mut warnings = Vec::new();
-    let mut input_to_segment = vec![None; input.len()];
+    let mut input_to_segment = vec![None; input_len];
     let mut seen: BTreeMap<SegmentKey, (SegmentId, usize)> = BTreeMap::new();
 
-    for (input_index, seg) in input.iter().enumerate() {
-        let ax = match quantize_coord(seg.ax) {
-            Ok(v) => v,
-            Err(error) => {
-                warnings.push(Warning {
-                    input_index,
-                    kind: WarningKind::DroppedInvalidCoordinate {
-                        coord: InputCoord::Ax,
-                        error,
-                    },
-                });
-                continue;
-            }
-        };
-        let ay = match quantize_coord(seg.ay) {
-            Ok(v) => v,
-            Err(error) => {
-                warnings.push(Warning {
-                    input_index,
-                    kind: WarningKind::DroppedInvalidCoordinate {
-                        coord: InputCoord::Ay,
-                        error,
-                    },
-                });
-                continue;
-            }
-        };
-        let bx = match quantize_coord(seg.bx) {
+    for (input_index, item) in points.enumerate() {
+        let (a, b) = match item {
             Ok(v) => v,
-            Err(error) => {
+            Err((coord, error)) => {
                 warnings.push(Warning {
                     input_index,
-                    kind: WarningKind::DroppedInvalidCoordinate {
-                        coord: InputCoord::Bx,
-                        error,
-                    },
+                    kind: WarningKind::DroppedInvalidCoordinate { coord, error },
                 });
                 continue;
             }
         };
-        let by = match quantize_coord(seg.by) {
-            Ok(v) => v,
-            Err(error) => {
-                warnings.push(Warning {
-                    input_index,
-                    kind: WarningKind::DroppedInvalidCoordinate {
-                        coord: InputCoord::By,
-                        error,
-                    },
-                });
-                continue;
-            }
-        };
-
-        let a = PointI64 { x: ax, y: ay };
-        let b = PointI64 { x: bx, y: by };
         let key = SegmentKey::new(a, b);
 
         if key.a == key.b {
@@ -168,6 +292,7 @@ pub fn preprocess_segments(input: &[InputSegmentF64]) -> PreprocessOutput {
     }
 
     PreprocessOutput {
+        quantization,
         segments,
         input_to_segment,
         warnings,
@@ -177,6 +302,7 @@ pub fn preprocess_segments(input: &[InputSegmentF64]) -> PreprocessOutput {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geom::fixed::SCALE;
 
     #[test]
     fn canonicalizes_endpoints() {
@@ -261,4 +387,122 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn auto_quantization_fits_projected_coordinates() {
+        // 投影坐标（米）：远超 [-1, 1]，默认量化会全部丢弃。
+        let input = [
+            InputSegmentF64 {
+                ax: 500_000.0,
+                ay: 4_200_000.0,
+                bx: 500_100.0,
+                by: 4_200_050.0,
+            },
+            InputSegmentF64 {
+                ax: 500_000.0,
+                ay: 4_200_050.0,
+                bx: f64::NAN,
+                by: 4_200_000.0,
+            },
+        ];
+        assert!(preprocess_segments(&input).segments.is_empty());
+
+        let q = QuantizationMode::Auto.resolve(&input);
+        assert_eq!((q.origin_x, q.origin_y), (500_050.0, 4_200_025.0));
+        let out = preprocess_segments_with_quantization(&input, &q);
+        assert_eq!(out.quantization, q);
+        assert_eq!(out.segments.len(), 1);
+        let seg = out.segments.get(SegmentId(0));
+        assert_eq!(
+            seg.a,
+            PointI64 {
+                x: -SCALE,
+                y: -SCALE / 2
+            }
+        );
+        assert_eq!(
+            seg.b,
+            PointI64 {
+                x: SCALE,
+                y: SCALE / 2
+            }
+        );
+        assert_eq!(
+            q.unquantize(seg.b.x as f64, seg.b.y as f64),
+            (500_100.0, 4_200_050.0)
+        );
+        assert_eq!(
+            out.warnings[0].kind,
+            WarningKind::DroppedInvalidCoordinate {
+                coord: InputCoord::Bx,
+                error: QuantizeError::NonFinite
+            }
+        );
+    }
+
+    #[test]
+    fn integer_input_validates_range_and_keeps_exact_coordinates() {
+        let m = MAX_ABS_COORD;
+        let input = [
+            InputSegmentI64 {
+                ax: m,
+                ay: -m,
+                bx: -m,
+                by: m,
+            },
+            InputSegmentI64 {
+                ax: 3,
+                ay: 3,
+                bx: 3,
+                by: 3,
+            },
+            InputSegmentI64 {
+                ax: -m,
+                ay: m,
+                bx: m,
+                by: -m,
+            },
+            InputSegmentI64 {
+                ax: 0,
+                ay: m + 1,
+                bx: 0,
+                by: 0,
+            },
+        ];
+        let out = preprocess_segments_i64(&input);
+        assert_eq!(out.quantization, Quantization::identity());
+        assert_eq!(out.segments.len(), 1);
+        let seg = out.segments.get(SegmentId(0));
+        assert_eq!(
+            (seg.a, seg.b),
+            (PointI64 { x: -m, y: m }, PointI64 { x: m, y: -m })
+        );
+        assert_eq!(
+            out.input_to_segment,
+            vec![Some(SegmentId(0)), None, None, None]
+        );
+        assert_eq!(
+            out.warnings,
+            vec![
+                Warning {
+                    input_index: 1,
+                    kind: WarningKind::DroppedZeroLength,
+                },
+                Warning {
+                    input_index: 2,
+                    kind: WarningKind::DroppedDuplicate {
+                        kept_input_index: 0
+                    },
+                },
+                Warning {
+                    input_index: 3,
+                    kind: WarningKind::DroppedInvalidCoordinate {
+                        coord: InputCoord::Ay,
+                        error: QuantizeError::OutOfRange
+                    },
+                },
+            ]
+        );
+        assert_eq!(check_coord(Coord::MIN), Err(QuantizeError::OutOfRange));
+    }
 }
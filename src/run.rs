@@ -1,7 +1,27 @@
+use crate::atomic::{
+    AtomicIntersectionGroupRecord, AtomicSegments, atomic_intersections_from_records,
+    decompose_atomic_segments,
+};
+use crate::chain::{
+    ChainIntersection, ChainLayout, InputChainF64, chain_intersections, preprocess_chains,
+};
 use crate::geom::intersection::PointIntersectionGroupRecord;
 use crate::limits::{LimitExceeded, Limits};
-use crate::preprocess::{InputSegmentF64, PreprocessOutput, preprocess_segments};
-use crate::session::{session_v2_to_json_string, session_v2_to_json_string_limited};
+use crate::mapping::{MapOptions, MappedIntersection, map_atomic_intersections, map_intersections};
+use crate::noding::{NodedSegment, node_segments};
+use crate::overlap::{
+    CoverageLayerRecord, OverlapPointRecord, OverlapSegmentRecord, coverage_layers,
+    enumerate_collinear_overlaps,
+};
+use crate::preprocess::{
+    InputSegmentF64, InputSegmentI64, PreprocessOutput, QuantizationMode, preprocess_segments_i64,
+    preprocess_segments_with_quantization,
+};
+use crate::session::{
+    session_v2_to_json_string_with_quantization,
+    session_v2_to_json_string_with_quantization_limited, session_v3_to_json_string,
+    session_v3_to_json_string_limited,
+};
 use crate::sweep::bo::{
     BoError, enumerate_point_intersections_with_limits,
     enumerate_point_intersections_with_trace_and_limits,
@@ -14,6 +34,8 @@ pub struct Phase1Options {
     pub trace_enabled: bool,
     /// 输出规模/执行步数上限（任一触发即 fail-fast）。
     pub limits: Limits,
+    /// 输入坐标到整数网格的量化方式（默认 `[-1, 1]` × `SCALE`）。
+    pub quantization: QuantizationMode,
 }
 
 impl Default for Phase1Options {
@@ -21,6 +43,7 @@ impl Default for Phase1Options {
         Self {
             trace_enabled: true,
             limits: Limits::default(),
+            quantization: QuantizationMode::default(),
         }
     }
 }
@@ -41,7 +64,23 @@ pub fn run_phase1_with_options(
     input: &[InputSegmentF64],
     options: &Phase1Options,
 ) -> Result<Phase1Output, BoError> {
-    let preprocess = preprocess_segments(input);
+    let quantization = options.quantization.resolve(input);
+    let preprocess = preprocess_segments_with_quantization(input, &quantization);
+    run_phase1_preprocessed(preprocess, options)
+}
+
+/// 整数坐标输入的第一阶段入口（`options.quantization` 不参与，见 `preprocess_segments_i64`）。
+pub fn run_phase1_i64_with_options(
+    input: &[InputSegmentI64],
+    options: &Phase1Options,
+) -> Result<Phase1Output, BoError> {
+    run_phase1_preprocessed(preprocess_segments_i64(input), options)
+}
+
+fn run_phase1_preprocessed(
+    preprocess: PreprocessOutput,
+    options: &Phase1Options,
+) -> Result<Phase1Output, BoError> {
     let (intersections, mut trace) = if options.trace_enabled {
         enumerate_point_intersections_with_trace_and_limits(&preprocess.segments, options.limits)?
     } else {
@@ -62,18 +101,158 @@ pub fn run_phase1_with_options(
 impl Phase1Output {
     /// 将 phase1 结果打包为 `session.v2` JSON（可直接喂给 `viewer/` 回放器）。
     pub fn to_session_json_string(&self) -> String {
-        session_v2_to_json_string(&self.preprocess.segments, &self.trace)
+        session_v2_to_json_string_with_quantization(
+            &self.preprocess.segments,
+            &self.trace,
+            &self.preprocess.quantization,
+        )
     }
 
     /// 将 phase1 结果打包为 `session.v2` JSON，并检查 `limits.max_session_bytes`（超限则报错）。
     pub fn to_session_json_string_limited(&self, limits: Limits) -> Result<String, LimitExceeded> {
-        session_v2_to_json_string_limited(&self.preprocess.segments, &self.trace, limits)
+        session_v2_to_json_string_with_quantization_limited(
+            &self.preprocess.segments,
+            &self.trace,
+            &self.preprocess.quantization,
+            limits,
+        )
+    }
+
+    /// 把点交结果映射回调用方坐标系与原始输入下标（见 `crate::mapping`）。
+    pub fn map_intersections(&self, options: &MapOptions) -> Vec<MappedIntersection> {
+        map_intersections(&self.preprocess, &self.intersections, options)
+    }
+
+    /// 每条预处理后线段在其全部交点处的切分结果（见 `crate::noding`）。
+    pub fn node_segments(&self) -> Vec<NodedSegment> {
+        node_segments(&self.preprocess.segments, &self.intersections)
+    }
+}
+
+/// 折线 / 多边形环输入的第一阶段结果：`phase1` 为展开成线段后的完整结果（含相邻边的平凡接触）。
+#[derive(Clone, Debug)]
+pub struct ChainPhase1Output {
+    pub phase1: Phase1Output,
+    pub layout: ChainLayout,
+}
+
+impl ChainPhase1Output {
+    /// 去掉相邻边在公共顶点处的平凡接触，以 `(chain, edge)` 报告其余点交（见 `crate::chain`）。
+    pub fn chain_intersections(&self) -> Vec<ChainIntersection> {
+        chain_intersections(
+            &self.layout,
+            &self.phase1.preprocess,
+            &self.phase1.intersections,
+        )
+    }
+}
+
+/// 折线 / 多边形环输入的第一阶段入口（`options.quantization` 按展开后的全部边确定）。
+pub fn run_chains_with_options(
+    input: &[InputChainF64],
+    options: &Phase1Options,
+) -> Result<ChainPhase1Output, BoError> {
+    let (preprocess, layout) = preprocess_chains(input, &options.quantization);
+    Ok(ChainPhase1Output {
+        phase1: run_phase1_preprocessed(preprocess, options)?,
+        layout,
+    })
+}
+
+/// Phase 2 目前与 Phase 1 共用同一组选项（trace 开关 + fail-fast 上限 + 量化方式）。
+pub type Phase2Options = Phase1Options;
+
+#[derive(Clone, Debug)]
+pub struct Phase2Output {
+    pub preprocess: PreprocessOutput,
+    /// 共线重叠段：各共线组上覆盖≥2 的极大连续区间（见 `crate::overlap`）。
+    pub overlap_segments: Vec<OverlapSegmentRecord>,
+    /// 共线重叠点：仅在单点处覆盖≥2 的退化分量。
+    pub overlap_points: Vec<OverlapPointRecord>,
+    /// 原子子段及其溯源（见 `crate::atomic`）；覆盖次数即溯源集合大小。
+    pub atomic: AtomicSegments,
+    /// 共线组的覆盖分层：覆盖次数恒定的极大连续区间。
+    pub coverage_layers: Vec<CoverageLayerRecord>,
+    /// 在原子子段集合上重新扫描得到的点交（引用 `AtomicSegmentId`）。
+    pub intersections: Vec<AtomicIntersectionGroupRecord>,
+    /// 原子子段集合上的扫描 trace（step 中的 `SegmentId` 即原子子段下标）。
+    pub trace: Trace,
+}
+
+/// 第二阶段一站式入口：预处理 + 共线重叠 + 原子子段替换 + 点交枚举（语义见 `plans/phase2-precheck.md`）。
+pub fn run_phase2(input: &[InputSegmentF64]) -> Result<Phase2Output, BoError> {
+    run_phase2_with_options(input, &Phase2Options::default())
+}
+
+pub fn run_phase2_with_options(
+    input: &[InputSegmentF64],
+    options: &Phase2Options,
+) -> Result<Phase2Output, BoError> {
+    let quantization = options.quantization.resolve(input);
+    let preprocess = preprocess_segments_with_quantization(input, &quantization);
+    run_phase2_preprocessed(preprocess, options)
+}
+
+/// 整数坐标输入的第二阶段入口（`options.quantization` 不参与，见 `preprocess_segments_i64`）。
+pub fn run_phase2_i64_with_options(
+    input: &[InputSegmentI64],
+    options: &Phase2Options,
+) -> Result<Phase2Output, BoError> {
+    run_phase2_preprocessed(preprocess_segments_i64(input), options)
+}
+
+fn run_phase2_preprocessed(
+    preprocess: PreprocessOutput,
+    options: &Phase2Options,
+) -> Result<Phase2Output, BoError> {
+    let overlaps = enumerate_collinear_overlaps(&preprocess.segments);
+    let atomic = decompose_atomic_segments(&preprocess.segments);
+    let coverage_layers = coverage_layers(&preprocess.segments);
+
+    let (records, mut trace) = if options.trace_enabled {
+        enumerate_point_intersections_with_trace_and_limits(&atomic.segments, options.limits)?
+    } else {
+        let records = enumerate_point_intersections_with_limits(&atomic.segments, options.limits)?;
+        (records, Trace::default())
+    };
+    trace.warnings = preprocess.warnings.iter().map(|w| w.to_string()).collect();
+    let intersections = atomic_intersections_from_records(&atomic, records);
+
+    Ok(Phase2Output {
+        preprocess,
+        overlap_segments: overlaps.segments,
+        overlap_points: overlaps.points,
+        atomic,
+        coverage_layers,
+        intersections,
+        trace,
+    })
+}
+
+impl Phase2Output {
+    /// 将 phase2 结果打包为 `session.v3` JSON（重叠段/重叠点/原子子段/溯源/最终点交 + trace）。
+    pub fn to_session_json_string(&self) -> String {
+        session_v3_to_json_string(self)
+    }
+
+    /// 将 phase2 结果打包为 `session.v3` JSON，并检查 `limits.max_session_bytes`（超限则报错）。
+    pub fn to_session_json_string_limited(&self, limits: Limits) -> Result<String, LimitExceeded> {
+        session_v3_to_json_string_limited(self, limits)
+    }
+
+    /// 把点交结果映射回调用方坐标系与原始输入下标（原子子段经溯源展开，见 `crate::mapping`）。
+    pub fn map_intersections(&self, options: &MapOptions) -> Vec<MappedIntersection> {
+        map_atomic_intersections(&self.preprocess, &self.atomic, &self.intersections, options)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chain::EdgeRef;
+    use crate::geom::fixed::PointI64;
+    use crate::geom::point::PointRat;
+    use crate::geom::segment::SegmentId;
 
     #[test]
     fn includes_preprocess_warnings_in_trace_json() {
@@ -91,4 +270,160 @@ mod tests {
         let json = out.trace.to_json_string();
         assert!(json.contains("\"warnings\":[\"第 0 条输入："));
     }
+
+    #[test]
+    fn phase2_reports_overlap_segments_with_contributing_segments() {
+        let input = [
+            InputSegmentF64 {
+                ax: 0.0,
+                ay: 0.0,
+                bx: 0.5,
+                by: 0.0,
+            },
+            InputSegmentF64 {
+                ax: 0.25,
+                ay: 0.0,
+                bx: 0.75,
+                by: 0.0,
+            },
+            InputSegmentF64 {
+                ax: 0.75,
+                ay: 0.0,
+                bx: 1.0,
+                by: 0.0,
+            },
+        ];
+        let out = run_phase2(&input).unwrap();
+        assert_eq!(
+            out.overlap_segments,
+            vec![OverlapSegmentRecord {
+                a: PointI64 {
+                    x: 250_000_000,
+                    y: 0
+                },
+                b: PointI64 {
+                    x: 500_000_000,
+                    y: 0
+                },
+                segments: vec![SegmentId(0), SegmentId(1)],
+            }]
+        );
+        assert_eq!(
+            out.overlap_points,
+            vec![OverlapPointRecord {
+                point: PointI64 {
+                    x: 750_000_000,
+                    y: 0
+                },
+                segments: vec![SegmentId(1), SegmentId(2)],
+            }]
+        );
+    }
+
+    #[test]
+    fn phase2_reruns_sweep_on_atomic_segments() {
+        // 两条共线重叠的水平线段 + 一条穿过重叠部分的竖线。
+        let input = [
+            InputSegmentF64 {
+                ax: -1.0,
+                ay: 0.0,
+                bx: 0.5,
+                by: 0.0,
+            },
+            InputSegmentF64 {
+                ax: -0.5,
+                ay: 0.0,
+                bx: 1.0,
+                by: 0.0,
+            },
+            InputSegmentF64 {
+                ax: 0.0,
+                ay: -1.0,
+                bx: 0.0,
+                by: 1.0,
+            },
+        ];
+        let out = run_phase2(&input).unwrap();
+        assert_eq!(out.atomic.len(), 4);
+        assert_eq!(out.atomic.max_coverage(), 2);
+        assert_eq!(
+            out.coverage_layers
+                .iter()
+                .map(|l| l.coverage)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 1]
+        );
+        assert_eq!(out.intersections.len(), 1);
+
+        let hit = &out.intersections[0];
+        assert_eq!(hit.point, PointRat::from_i64(PointI64 { x: 0, y: 0 }));
+        assert!(hit.endpoint_segments.is_empty());
+        let provenance: Vec<&[SegmentId]> = hit
+            .interior_segments
+            .iter()
+            .map(|id| out.atomic.provenance(*id))
+            .collect();
+        assert_eq!(
+            provenance,
+            vec![&[SegmentId(0), SegmentId(1)][..], &[SegmentId(2)][..]]
+        );
+    }
+
+    #[test]
+    fn integer_input_runs_at_coordinate_extremes() {
+        use crate::geom::fixed::MAX_ABS_COORD as M;
+        // 端点都在坐标上限处的长对角线两两相交：交点分母可达 ~8·M²，覆盖最坏情况的中间量。
+        let input = [
+            InputSegmentI64 {
+                ax: -M,
+                ay: -M,
+                bx: M,
+                by: M - 1,
+            },
+            InputSegmentI64 {
+                ax: -M,
+                ay: -M + 1,
+                bx: M,
+                by: M - 2,
+            },
+            InputSegmentI64 {
+                ax: -M,
+                ay: M,
+                bx: M,
+                by: -M,
+            },
+        ];
+        let out = run_phase1_i64_with_options(&input, &Phase1Options::default()).unwrap();
+        assert!(out.preprocess.warnings.is_empty());
+        assert_eq!(out.intersections.len(), 3);
+        for hit in &out.intersections {
+            assert!(hit.endpoint_segments.is_empty());
+            assert!(hit.point.x.to_f64().abs() <= M as f64);
+        }
+    }
+
+    #[test]
+    fn chain_input_filters_trivial_vertex_touches() {
+        let input = [InputChainF64::ring(vec![
+            (0.0, 0.0),
+            (100.0, 100.0),
+            (100.0, 0.0),
+            (0.0, 100.0),
+        ])];
+        let options = Phase1Options {
+            quantization: QuantizationMode::Auto,
+            ..Phase1Options::default()
+        };
+        let out = run_chains_with_options(&input, &options).unwrap();
+        // 4 个顶点处的相邻边接触 + 1 个交叉。
+        assert_eq!(out.phase1.intersections.len(), 5);
+
+        let hits = out.chain_intersections();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].endpoint_edges.is_empty());
+        assert_eq!(
+            hits[0].interior_edges,
+            vec![EdgeRef { chain: 0, edge: 0 }, EdgeRef { chain: 0, edge: 2 },]
+        );
+    }
 }
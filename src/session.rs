@@ -1,40 +1,222 @@
-use crate::geom::fixed::{PointI64, SCALE};
-use crate::geom::segment::{SegmentId, Segments};
+use crate::atomic::{AtomicIntersectionGroupRecord, AtomicSegmentId};
+use crate::geom::fixed::{PointI64, Quantization, SCALE};
+use crate::geom::segment::{Segment, SegmentId, Segments};
+use crate::json::{JsonError, JsonNode, parse_json};
 use crate::limits::{LimitExceeded, LimitKind, Limits};
-use crate::trace::Trace;
+use crate::overlap::{CoverageLayerRecord, OverlapPointRecord, OverlapSegmentRecord};
+use crate::run::Phase2Output;
+use crate::trace::{Trace, read_trace_json, write_point};
 
 pub const SESSION_SCHEMA: &str = "session.v2";
+pub const SESSION_V3_SCHEMA: &str = "session.v3";
 
 /// 将（量化后的）线段集合与 `trace.v2` 打包为 `session.v2` JSON（字段顺序固定，便于回归与复现）。
+///
+/// 说明：`fixed.transform` 记录为 `Quantization::unit()`；其他量化方式见 `session_v2_to_json_string_with_quantization`。
 pub fn session_v2_to_json_string(segments: &Segments, trace: &Trace) -> String {
+    session_v2_to_json_string_with_quantization(segments, trace, &Quantization::unit())
+}
+
+/// 与 `session_v2_to_json_string` 相同，但在 `fixed.transform` 中记录给定的量化变换。
+pub fn session_v2_to_json_string_with_quantization(
+    segments: &Segments,
+    trace: &Trace,
+    quantization: &Quantization,
+) -> String {
     let mut out = String::new();
-    write_session_json(segments, trace, &mut out);
+    write_session_json(segments, trace, quantization, &mut out);
     out
 }
 
+/// 从 `session.v2` JSON 读回的会话（量化后的线段集合 + `trace.v2`）。
+#[derive(Clone, Debug)]
+pub struct SessionV2 {
+    /// `fixed.transform`；旧文件缺少该字段时视为 `Quantization::unit()`。
+    pub quantization: Quantization,
+    pub segments: Segments,
+    pub trace: Trace,
+}
+
+/// 从 `session.v2` JSON 读回线段集合与 trace（`session_v2_to_json_string` 的逆操作）。
+///
+/// 说明：
+/// - schema 必须为 `session.v2`，且 `fixed.scale` 必须与当前 `SCALE` 一致（否则坐标含义不同）；
+/// - `fixed.transform` 可选（早期文件没有该字段），缺省为 `Quantization::unit()`；
+/// - 线段需按 `id` 连续编号、端点已规范化且非零长度；trace 中引用的 `SegmentId` 必须存在；
+/// - 任一校验失败都返回带行/列与字段路径的 `JsonError`。
+pub fn session_v2_from_json_str(json: &str) -> Result<SessionV2, JsonError> {
+    let root = parse_json(json)?;
+
+    let schema = root.get("schema", "$")?;
+    let name = schema.as_str("$.schema")?;
+    if name != SESSION_SCHEMA {
+        return Err(schema.error(
+            "$.schema",
+            format!("不支持的 schema：{name}（期望 {SESSION_SCHEMA}）"),
+        ));
+    }
+
+    let fixed = root.get("fixed", "$")?;
+    let scale = fixed.get("scale", "$.fixed")?;
+    if scale.as_i128_text("$.fixed.scale")? != SCALE as i128 {
+        return Err(scale.error(
+            "$.fixed.scale",
+            format!("scale 与当前实现不一致（期望 {SCALE}）"),
+        ));
+    }
+    let quantization = match fixed.get_opt("transform", "$.fixed")? {
+        Some(node) => read_transform_json(node, "$.fixed.transform")?,
+        None => Quantization::unit(),
+    };
+
+    let mut segments = Segments::new();
+    for (i, node) in root
+        .get("segments", "$")?
+        .as_array("$.segments")?
+        .iter()
+        .enumerate()
+    {
+        segments.push(read_segment_json(node, i, &format!("$.segments[{i}]"))?);
+    }
+
+    let trace_node = root.get("trace", "$")?;
+    let trace = read_trace_json(trace_node, "$.trace")?;
+    check_trace_segment_refs(trace_node, segments.len())?;
+
+    Ok(SessionV2 {
+        quantization,
+        segments,
+        trace,
+    })
+}
+
+fn read_transform_json(node: &JsonNode, path: &str) -> Result<Quantization, JsonError> {
+    let read_f64 = |key: &str| -> Result<f64, JsonError> {
+        let field_path = format!("{path}.{key}");
+        let field = node.get(key, path)?;
+        let text = field.as_str(&field_path)?;
+        text.parse::<f64>()
+            .map_err(|_| field.error(&field_path, format!("不是有效的数字文本：{text:?}")))
+    };
+    let (origin_x, origin_y, grid_per_unit) = (
+        read_f64("origin_x")?,
+        read_f64("origin_y")?,
+        read_f64("grid_per_unit")?,
+    );
+    Quantization::new(origin_x, origin_y, grid_per_unit)
+        .ok_or_else(|| node.error(path, "量化变换无效（要求有限数值且 grid_per_unit > 0）"))
+}
+
+fn read_segment_json(node: &JsonNode, index: usize, path: &str) -> Result<Segment, JsonError> {
+    let id_path = format!("{path}.id");
+    let id_node = node.get("id", path)?;
+    if id_node.as_usize(&id_path)? != index {
+        return Err(id_node.error(&id_path, format!("线段 id 不连续（期望 {index}）")));
+    }
+    let source_index = node
+        .get("source_index", path)?
+        .as_usize(&format!("{path}.source_index"))?;
+    let a = read_point_i64(node.get("a", path)?, &format!("{path}.a"))?;
+    let b = read_point_i64(node.get("b", path)?, &format!("{path}.b"))?;
+    if a >= b {
+        return Err(node.error(path, "线段端点未规范化（要求 a < b，且不能是零长度线段）"));
+    }
+    Ok(Segment { a, b, source_index })
+}
+
+fn read_point_i64(node: &JsonNode, path: &str) -> Result<PointI64, JsonError> {
+    Ok(PointI64 {
+        x: node.get("x", path)?.as_i64(&format!("{path}.x"))?,
+        y: node.get("y", path)?.as_i64(&format!("{path}.y"))?,
+    })
+}
+
+/// 检查 trace 中所有 `SegmentId` 引用都落在线段集合范围内（直接在 JSON 上检查，以便报出精确位置）。
+fn check_trace_segment_refs(trace: &JsonNode, segment_count: usize) -> Result<(), JsonError> {
+    let check_ids = |node: &JsonNode, path: &str| -> Result<(), JsonError> {
+        for (i, item) in node.as_array(path)?.iter().enumerate() {
+            let item_path = format!("{path}[{i}]");
+            let id = item.as_usize(&item_path)?;
+            if id >= segment_count {
+                return Err(item.error(
+                    &item_path,
+                    format!("引用了不存在的线段 id {id}（共 {segment_count} 条线段）"),
+                ));
+            }
+        }
+        Ok(())
+    };
+
+    for (i, step) in trace
+        .get("steps", "$.trace")?
+        .as_array("$.trace.steps")?
+        .iter()
+        .enumerate()
+    {
+        let step_path = format!("$.trace.steps[{i}]");
+        check_ids(
+            step.get("active", &step_path)?,
+            &format!("{step_path}.active"),
+        )?;
+        let intersections_path = format!("{step_path}.intersections");
+        for (j, it) in step
+            .get("intersections", &step_path)?
+            .as_array(&intersections_path)?
+            .iter()
+            .enumerate()
+        {
+            let it_path = format!("{intersections_path}[{j}]");
+            for key in ["endpoint_segments", "interior_segments"] {
+                check_ids(it.get(key, &it_path)?, &format!("{it_path}.{key}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
 /// 与 `session_v2_to_json_string` 等价，但额外检查 `limits.max_session_bytes`（超限则报错）。
 pub fn session_v2_to_json_string_limited(
     segments: &Segments,
     trace: &Trace,
     limits: Limits,
+) -> Result<String, LimitExceeded> {
+    session_v2_to_json_string_with_quantization_limited(
+        segments,
+        trace,
+        &Quantization::unit(),
+        limits,
+    )
+}
+
+/// 与 `session_v2_to_json_string_with_quantization` 等价，但额外检查 `limits.max_session_bytes`。
+pub fn session_v2_to_json_string_with_quantization_limited(
+    segments: &Segments,
+    trace: &Trace,
+    quantization: &Quantization,
+    limits: Limits,
 ) -> Result<String, LimitExceeded> {
     let mut out = String::new();
-    write_session_json_limited(segments, trace, &mut out, limits.max_session_bytes)?;
+    write_session_json_limited(
+        segments,
+        trace,
+        quantization,
+        &mut out,
+        limits.max_session_bytes,
+    )?;
     Ok(out)
 }
 
-fn write_session_json(segments: &Segments, trace: &Trace, out: &mut String) {
+fn write_session_json(
+    segments: &Segments,
+    trace: &Trace,
+    quantization: &Quantization,
+    out: &mut String,
+) {
     out.push('{');
     write_kv_str(out, "schema", SESSION_SCHEMA);
     out.push(',');
 
-    out.push('"');
-    out.push_str("fixed");
-    out.push('"');
-    out.push(':');
-    out.push('{');
-    write_kv_str(out, "scale", &SCALE.to_string());
-    out.push('}');
+    write_fixed_json(out, quantization);
     out.push(',');
 
     out.push('"');
@@ -63,6 +245,7 @@ fn write_session_json(segments: &Segments, trace: &Trace, out: &mut String) {
 fn write_session_json_limited(
     segments: &Segments,
     trace: &Trace,
+    quantization: &Quantization,
     out: &mut String,
     max_session_bytes: usize,
 ) -> Result<(), LimitExceeded> {
@@ -71,13 +254,7 @@ fn write_session_json_limited(
     out.push(',');
     ensure_session_bytes(out, max_session_bytes)?;
 
-    out.push('"');
-    out.push_str("fixed");
-    out.push('"');
-    out.push(':');
-    out.push('{');
-    write_kv_str(out, "scale", &SCALE.to_string());
-    out.push('}');
+    write_fixed_json(out, quantization);
     out.push(',');
     ensure_session_bytes(out, max_session_bytes)?;
 
@@ -118,7 +295,252 @@ fn write_session_json_limited(
     Ok(())
 }
 
-fn ensure_session_bytes(out: &String, max_session_bytes: usize) -> Result<(), LimitExceeded> {
+/// 将 phase2 结果打包为 `session.v3` JSON（字段顺序固定，便于回归与复现）。
+///
+/// 字段顺序：
+/// - `schema` / `fixed` / `segments`：与 `session.v2` 相同（预处理后线段）；
+/// - `atomic_segments`：原子子段（含覆盖次数 `coverage`）；
+/// - `provenance`：`atomic_segment_id -> [SegmentId...]`；
+/// - `overlap_segments` / `overlap_points` / `coverage_layers`：共线重叠结果（引用 `SegmentId`）；
+/// - `intersections`：最终点交列表（引用 `atomic_segment_id`）；
+/// - `trace`：原子子段集合上的 `trace.v2`（step 中的 ID 即 `atomic_segment_id`）。
+pub fn session_v3_to_json_string(output: &Phase2Output) -> String {
+    let mut out = String::new();
+    write_session_v3_json(output, &mut out, usize::MAX)
+        .expect("不设上限时不应触发 max_session_bytes");
+    out
+}
+
+/// 与 `session_v3_to_json_string` 等价，但额外检查 `limits.max_session_bytes`（超限则报错）。
+pub fn session_v3_to_json_string_limited(
+    output: &Phase2Output,
+    limits: Limits,
+) -> Result<String, LimitExceeded> {
+    let mut out = String::new();
+    write_session_v3_json(output, &mut out, limits.max_session_bytes)?;
+    Ok(out)
+}
+
+fn write_session_v3_json(
+    output: &Phase2Output,
+    out: &mut String,
+    max_session_bytes: usize,
+) -> Result<(), LimitExceeded> {
+    let segments = &output.preprocess.segments;
+    let atomic = &output.atomic;
+
+    out.push('{');
+    write_kv_str(out, "schema", SESSION_V3_SCHEMA);
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_fixed_json(out, &output.preprocess.quantization);
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "segments");
+    out.push('[');
+    for id in 0..segments.len() {
+        if id != 0 {
+            out.push(',');
+        }
+        write_segment_json(out, SegmentId(id), segments.get(SegmentId(id)));
+        ensure_session_bytes(out, max_session_bytes)?;
+    }
+    out.push(']');
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "atomic_segments");
+    out.push('[');
+    for i in 0..atomic.len() {
+        if i != 0 {
+            out.push(',');
+        }
+        let id = AtomicSegmentId(i);
+        let seg = atomic.get(id);
+        out.push('{');
+        write_kv_usize(out, "id", i);
+        out.push(',');
+        write_kv_usize(out, "coverage", atomic.coverage(id));
+        out.push(',');
+        write_key(out, "a");
+        write_point_i64(out, seg.a);
+        out.push(',');
+        write_key(out, "b");
+        write_point_i64(out, seg.b);
+        out.push('}');
+        ensure_session_bytes(out, max_session_bytes)?;
+    }
+    out.push(']');
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "provenance");
+    out.push('[');
+    for i in 0..atomic.len() {
+        if i != 0 {
+            out.push(',');
+        }
+        out.push('{');
+        write_kv_usize(out, "atomic_segment_id", i);
+        out.push(',');
+        write_kv_segment_ids(out, "segments", atomic.provenance(AtomicSegmentId(i)));
+        out.push('}');
+        ensure_session_bytes(out, max_session_bytes)?;
+    }
+    out.push(']');
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "overlap_segments");
+    out.push('[');
+    for (i, item) in output.overlap_segments.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        write_overlap_segment_json(out, item);
+        ensure_session_bytes(out, max_session_bytes)?;
+    }
+    out.push(']');
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "overlap_points");
+    out.push('[');
+    for (i, item) in output.overlap_points.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        write_overlap_point_json(out, item);
+        ensure_session_bytes(out, max_session_bytes)?;
+    }
+    out.push(']');
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "coverage_layers");
+    out.push('[');
+    for (i, item) in output.coverage_layers.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        write_coverage_layer_json(out, item);
+        ensure_session_bytes(out, max_session_bytes)?;
+    }
+    out.push(']');
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "intersections");
+    out.push('[');
+    for (i, item) in output.intersections.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        write_atomic_intersection_json(out, item);
+        ensure_session_bytes(out, max_session_bytes)?;
+    }
+    out.push(']');
+    out.push(',');
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    write_key(out, "trace");
+    let trace_json = output.trace.to_json_string();
+    let projected = out.len().saturating_add(trace_json.len());
+    if projected > max_session_bytes {
+        return Err(LimitExceeded {
+            kind: LimitKind::SessionBytes,
+            limit: max_session_bytes,
+            actual: projected,
+        });
+    }
+    out.push_str(&trace_json);
+    ensure_session_bytes(out, max_session_bytes)?;
+
+    out.push('}');
+    ensure_session_bytes(out, max_session_bytes)?;
+    Ok(())
+}
+
+fn write_overlap_segment_json(out: &mut String, item: &OverlapSegmentRecord) {
+    out.push('{');
+    write_key(out, "a");
+    write_point_i64(out, item.a);
+    out.push(',');
+    write_key(out, "b");
+    write_point_i64(out, item.b);
+    out.push(',');
+    write_kv_segment_ids(out, "segments", &item.segments);
+    out.push('}');
+}
+
+fn write_overlap_point_json(out: &mut String, item: &OverlapPointRecord) {
+    out.push('{');
+    write_key(out, "point");
+    write_point_i64(out, item.point);
+    out.push(',');
+    write_kv_segment_ids(out, "segments", &item.segments);
+    out.push('}');
+}
+
+fn write_coverage_layer_json(out: &mut String, item: &CoverageLayerRecord) {
+    out.push('{');
+    write_key(out, "a");
+    write_point_i64(out, item.a);
+    out.push(',');
+    write_key(out, "b");
+    write_point_i64(out, item.b);
+    out.push(',');
+    write_kv_usize(out, "coverage", item.coverage);
+    out.push(',');
+    write_kv_segment_ids(out, "segments", &item.segments);
+    out.push('}');
+}
+
+fn write_atomic_intersection_json(out: &mut String, item: &AtomicIntersectionGroupRecord) {
+    out.push('{');
+    write_key(out, "point");
+    write_point(out, item.point);
+    out.push(',');
+    write_kv_usize_array(
+        out,
+        "endpoint_segments",
+        item.endpoint_segments.iter().map(|id| id.0),
+    );
+    out.push(',');
+    write_kv_usize_array(
+        out,
+        "interior_segments",
+        item.interior_segments.iter().map(|id| id.0),
+    );
+    out.push('}');
+}
+
+fn write_kv_segment_ids(out: &mut String, key: &str, value: &[SegmentId]) {
+    write_kv_usize_array(out, key, value.iter().map(|id| id.0));
+}
+
+fn write_kv_usize_array(out: &mut String, key: &str, value: impl Iterator<Item = usize>) {
+    write_key(out, key);
+    out.push('[');
+    for (i, item) in value.enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        out.push_str(&item.to_string());
+    }
+    out.push(']');
+}
+
+fn write_key(out: &mut String, key: &str) {
+    out.push('"');
+    out.push_str(key);
+    out.push('"');
+    out.push(':');
+}
+
+fn ensure_session_bytes(out: &str, max_session_bytes: usize) -> Result<(), LimitExceeded> {
     if out.len() > max_session_bytes {
         return Err(LimitExceeded {
             kind: LimitKind::SessionBytes,
@@ -129,20 +551,43 @@ fn ensure_session_bytes(out: &String, max_session_bytes: usize) -> Result<(), Li
     Ok(())
 }
 
-fn write_segment_json(out: &mut String, id: SegmentId, seg: &crate::geom::segment::Segment) {
+/// `fixed`：网格比例 `scale`（即 `SCALE`）+ 输入坐标到网格的仿射变换 `transform`。
+///
+/// `transform` 的数值以 `f64` 的最短往返十进制文本输出（字符串，避免 JSON 数字的精度歧义）。
+fn write_fixed_json(out: &mut String, quantization: &Quantization) {
+    write_key(out, "fixed");
+    out.push('{');
+    write_kv_str(out, "scale", &SCALE.to_string());
+    out.push(',');
+    write_key(out, "transform");
+    out.push('{');
+    write_kv_str(out, "origin_x", &quantization.origin_x.to_string());
+    out.push(',');
+    write_kv_str(out, "origin_y", &quantization.origin_y.to_string());
+    out.push(',');
+    write_kv_str(
+        out,
+        "grid_per_unit",
+        &quantization.grid_per_unit.to_string(),
+    );
+    out.push('}');
+    out.push('}');
+}
+
+fn write_segment_json(out: &mut String, id: SegmentId, seg: &Segment) {
     out.push('{');
     write_kv_usize(out, "id", id.0);
     out.push(',');
     write_kv_usize(out, "source_index", seg.source_index);
     out.push(',');
     out.push('"');
-    out.push_str("a");
+    out.push('a');
     out.push('"');
     out.push(':');
     write_point_i64(out, seg.a);
     out.push(',');
     out.push('"');
-    out.push_str("b");
+    out.push('b');
     out.push('"');
     out.push(':');
     write_point_i64(out, seg.b);
@@ -217,7 +662,6 @@ mod tests {
     use super::*;
     use crate::geom::fixed::PointI64;
     use crate::geom::point::PointRat;
-    use crate::geom::segment::Segment;
     use crate::limits::{LimitKind, Limits};
     use crate::rational::Rational;
     use crate::trace::TraceStep;
@@ -256,7 +700,7 @@ mod tests {
             json,
             concat!(
                 "{\"schema\":\"session.v2\",",
-                "\"fixed\":{\"scale\":\"1000000000\"},",
+                "\"fixed\":{\"scale\":\"1000000000\",\"transform\":{\"origin_x\":\"0\",\"origin_y\":\"0\",\"grid_per_unit\":\"1000000000\"}},",
                 "\"segments\":[",
                 "{\"id\":0,\"source_index\":7,\"a\":{\"x\":-10,\"y\":0},\"b\":{\"x\":10,\"y\":0}},",
                 "{\"id\":1,\"source_index\":8,\"a\":{\"x\":0,\"y\":-10},\"b\":{\"x\":0,\"y\":10}",
@@ -286,6 +730,109 @@ mod tests {
         assert_eq!(SCALE, 1_000_000_000);
     }
 
+    fn phase2_fixture() -> Phase2Output {
+        // 两条共线重叠的水平线段 + 一条穿过重叠部分的竖线。
+        let input = [
+            crate::InputSegmentF64 {
+                ax: -1.0,
+                ay: 0.0,
+                bx: 0.5,
+                by: 0.0,
+            },
+            crate::InputSegmentF64 {
+                ax: -0.5,
+                ay: 0.0,
+                bx: 1.0,
+                by: 0.0,
+            },
+            crate::InputSegmentF64 {
+                ax: 0.0,
+                ay: -1.0,
+                bx: 0.0,
+                by: 1.0,
+            },
+        ];
+        crate::run::run_phase2_with_options(
+            &input,
+            &crate::run::Phase2Options {
+                trace_enabled: false,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn writes_stable_session_v3_json_with_fixed_field_order() {
+        let json = session_v3_to_json_string(&phase2_fixture());
+        assert_eq!(
+            json,
+            concat!(
+                "{\"schema\":\"session.v3\",",
+                "\"fixed\":{\"scale\":\"1000000000\",\"transform\":{\"origin_x\":\"0\",\"origin_y\":\"0\",\"grid_per_unit\":\"1000000000\"}},",
+                "\"segments\":[",
+                "{\"id\":0,\"source_index\":0,\"a\":{\"x\":-1000000000,\"y\":0},\"b\":{\"x\":500000000,\"y\":0}},",
+                "{\"id\":1,\"source_index\":1,\"a\":{\"x\":-500000000,\"y\":0},\"b\":{\"x\":1000000000,\"y\":0}},",
+                "{\"id\":2,\"source_index\":2,\"a\":{\"x\":0,\"y\":-1000000000},\"b\":{\"x\":0,\"y\":1000000000}}",
+                "],",
+                "\"atomic_segments\":[",
+                "{\"id\":0,\"coverage\":1,\"a\":{\"x\":-1000000000,\"y\":0},\"b\":{\"x\":-500000000,\"y\":0}},",
+                "{\"id\":1,\"coverage\":2,\"a\":{\"x\":-500000000,\"y\":0},\"b\":{\"x\":500000000,\"y\":0}},",
+                "{\"id\":2,\"coverage\":1,\"a\":{\"x\":0,\"y\":-1000000000},\"b\":{\"x\":0,\"y\":1000000000}},",
+                "{\"id\":3,\"coverage\":1,\"a\":{\"x\":500000000,\"y\":0},\"b\":{\"x\":1000000000,\"y\":0}}",
+                "],",
+                "\"provenance\":[",
+                "{\"atomic_segment_id\":0,\"segments\":[0]},",
+                "{\"atomic_segment_id\":1,\"segments\":[0,1]},",
+                "{\"atomic_segment_id\":2,\"segments\":[2]},",
+                "{\"atomic_segment_id\":3,\"segments\":[1]}",
+                "],",
+                "\"overlap_segments\":[",
+                "{\"a\":{\"x\":-500000000,\"y\":0},\"b\":{\"x\":500000000,\"y\":0},\"segments\":[0,1]}",
+                "],",
+                "\"overlap_points\":[],",
+                "\"coverage_layers\":[",
+                "{\"a\":{\"x\":-1000000000,\"y\":0},\"b\":{\"x\":-500000000,\"y\":0},\"coverage\":1,\"segments\":[0]},",
+                "{\"a\":{\"x\":-500000000,\"y\":0},\"b\":{\"x\":500000000,\"y\":0},\"coverage\":2,\"segments\":[0,1]},",
+                "{\"a\":{\"x\":500000000,\"y\":0},\"b\":{\"x\":1000000000,\"y\":0},\"coverage\":1,\"segments\":[1]}",
+                "],",
+                "\"intersections\":[",
+                "{\"point\":{\"x\":{\"num\":\"0\",\"den\":\"1\"},\"y\":{\"num\":\"0\",\"den\":\"1\"}},",
+                "\"endpoint_segments\":[],\"interior_segments\":[1,2]}",
+                "],",
+                "\"trace\":{\"schema\":\"trace.v2\",\"warnings\":[],\"steps\":[]}",
+                "}"
+            )
+        );
+    }
+
+    #[test]
+    fn fails_fast_when_session_v3_json_exceeds_max_bytes() {
+        let output = phase2_fixture();
+        let full = session_v3_to_json_string(&output);
+
+        let err = session_v3_to_json_string_limited(
+            &output,
+            Limits {
+                max_session_bytes: full.len() - 1,
+                ..Limits::default()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.kind, LimitKind::SessionBytes);
+        assert!(err.actual > err.limit);
+
+        let ok = session_v3_to_json_string_limited(
+            &output,
+            Limits {
+                max_session_bytes: full.len(),
+                ..Limits::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(ok, full);
+    }
+
     #[test]
     fn fails_fast_when_session_json_exceeds_max_bytes() {
         let mut segments = Segments::new();
@@ -328,4 +875,82 @@ mod tests {
         assert!(err.actual > 10);
         assert!(err.to_string().contains("建议："));
     }
+
+    #[test]
+    fn reads_back_phase1_session_losslessly() {
+        let input = [
+            crate::InputSegmentF64 {
+                ax: -1.0,
+                ay: -1.0,
+                bx: 1.0,
+                by: 1.0,
+            },
+            crate::InputSegmentF64 {
+                ax: -1.0,
+                ay: 1.0,
+                bx: 1.0,
+                by: -1.0,
+            },
+            crate::InputSegmentF64 {
+                ax: 0.0,
+                ay: -1.0,
+                bx: 0.0,
+                by: 1.0,
+            },
+        ];
+        let options = crate::run::Phase1Options {
+            quantization: crate::QuantizationMode::Fixed(
+                Quantization::new(0.25, -0.5, 4e8).unwrap(),
+            ),
+            ..Default::default()
+        };
+        let out = crate::run::run_phase1_with_options(&input, &options).unwrap();
+        let json = out.to_session_json_string();
+
+        let session = session_v2_from_json_str(&json).unwrap();
+        assert_eq!(session.quantization, out.preprocess.quantization);
+        assert_eq!(session.segments.len(), out.preprocess.segments.len());
+        for (read, orig) in session.segments.iter().zip(out.preprocess.segments.iter()) {
+            assert_eq!(read, orig);
+        }
+        let read_hits: Vec<_> = session
+            .trace
+            .steps
+            .iter()
+            .flat_map(|s| s.intersections.iter().cloned())
+            .collect();
+        assert_eq!(read_hits, out.intersections);
+        assert_eq!(
+            session_v2_to_json_string_with_quantization(
+                &session.segments,
+                &session.trace,
+                &session.quantization
+            ),
+            json
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_schema_and_dangling_ids_with_positions() {
+        let err = session_v2_from_json_str("{\"schema\":\"session.v1\"}").unwrap_err();
+        assert_eq!(err.path, "$.schema");
+        assert_eq!((err.pos.line, err.pos.column), (1, 11));
+        assert!(err.message.contains("session.v1"));
+
+        let json = concat!(
+            "{\"schema\":\"session.v2\",\"fixed\":{\"scale\":\"1000000000\"},\n",
+            "\"segments\":[{\"id\":0,\"source_index\":0,\"a\":{\"x\":0,\"y\":0},\"b\":{\"x\":1,\"y\":1}}],\n",
+            "\"trace\":{\"schema\":\"trace.v2\",\"warnings\":[],\"steps\":[\n",
+            "{\"kind\":\"VerticalFlush\",\"sweep_x\":{\"num\":\"0\",\"den\":\"1\"},\"point\":null,",
+            "\"events\":[],\"active\":[0,5],\"intersections\":[],\"notes\":[]}]}}"
+        );
+        let err = session_v2_from_json_str(json).unwrap_err();
+        assert_eq!(err.path, "$.trace.steps[0].active[1]");
+        assert_eq!(err.pos.line, 4);
+        assert!(err.message.contains("不存在的线段 id 5"));
+
+        let err =
+            session_v2_from_json_str(&json.replace("\"den\":\"1\"", "\"den\":\"0\"")).unwrap_err();
+        assert_eq!(err.path, "$.trace.steps[0].sweep_x.den");
+    }
 }
@@ -0,0 +1,454 @@
+//! Snap rounding（Hobby / Guibas–Marimont）：把平面剖分吸附到整数网格，且保持拓扑一致。
+//!
+//! 约定：
+//! - 像素边长为 `SnapOptions.pixel_size`（网格单位，即吸附容差）；像素中心取 `pixel_size` 的整数倍，
+//!   像素为半开正方形 `[cx - s/2, cx + s/2) × [cy - s/2, cy + s/2)`，与 `floor(v/s + 1/2)` 的取整规则一致；
+//! - 热像素（hot pixel）：包含某个线段端点或点交的像素，由 Bentley–Ottmann 扫描（`sweep::bo`）的点交输出确定；
+//! - 每条线段改为依次经过它穿过的全部热像素中心的折线（端点所在像素必为热像素，故首尾即吸附后的端点）；
+//! - 迭代模式（iterated snap rounding）：把上一轮的折线片段当作新线段，再对同一组热像素重新吸附，
+//!   直到没有片段穿过新的热像素或达到轮数上限；这样片段与不相邻的顶点至少相距半个像素。
+//!
+//! 说明：线段与像素的相交判定在有理数上精确进行；热像素按列分桶，线段—热像素的查找不使用扫描线，
+//! 而是对线段 x 范围内的每个热像素列，只查询线段在该列像素宽度内的 y 范围附近的行
+//! （每列 `O(log h + 命中数)`，不会枚举线段包围盒内的全部热像素）。
+
+use core::fmt;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::geom::fixed::{Coord, MAX_ABS_COORD, PointI64};
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::point::PointRat;
+use crate::geom::segment::{Segment, SegmentId, SegmentKey, Segments};
+use crate::limits::Limits;
+use crate::noding::round_half_up;
+use crate::rational::Rational;
+use crate::sweep::bo::{BoError, enumerate_point_intersections_with_limits};
+use crate::sweep::segment_order::{SegmentOrderError, y_at_x};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapOptions {
+    /// 像素边长（网格单位，`1..=2 * MAX_ABS_COORD`）：结果顶点都落在 `pixel_size` 的整数倍上。
+    pub pixel_size: Coord,
+    /// 吸附轮数上限（≥1）：1 为经典 snap rounding，>1 为迭代 snap rounding（提前稳定时提前结束）。
+    pub max_iterations: usize,
+}
+
+impl Default for SnapOptions {
+    fn default() -> Self {
+        Self {
+            pixel_size: 1,
+            max_iterations: 1,
+        }
+    }
+}
+
+/// 吸附后的一条边（相同的边只输出一次）。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnappedEdge {
+    /// `a < b`。
+    pub a: PointI64,
+    pub b: PointI64,
+    /// 经过该边的输入线段（升序、去重）。
+    pub segments: Vec<SegmentId>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapRounding {
+    /// 热像素中心（升序）。
+    pub hot_pixels: Vec<PointI64>,
+    /// 下标为 `SegmentId`：吸附后的折线（沿线段方向；两端落在同一像素的线段退化为单点）。
+    pub polylines: Vec<Vec<PointI64>>,
+    /// 所有折线片段去重后的边（按 `(a,b)` 升序）。
+    pub edges: Vec<SnappedEdge>,
+    /// 实际执行的吸附轮数（最后一轮无变化，或达到 `max_iterations`）。
+    pub iterations: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapError {
+    InvalidPixelSize { pixel_size: Coord },
+    InvalidMaxIterations { max_iterations: usize },
+    Sweep(BoError),
+    SegmentOrder(SegmentOrderError),
+}
+
+impl From<BoError> for SnapError {
+    fn from(value: BoError) -> Self {
+        Self::Sweep(value)
+    }
+}
+
+impl From<SegmentOrderError> for SnapError {
+    fn from(value: SegmentOrderError) -> Self {
+        Self::SegmentOrder(value)
+    }
+}
+
+impl fmt::Display for SnapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapError::InvalidPixelSize { pixel_size } => {
+                write!(
+                    f,
+                    "像素边长必须在 [1, {}] 内（当前为 {}）",
+                    2 * MAX_ABS_COORD,
+                    pixel_size
+                )
+            }
+            SnapError::InvalidMaxIterations { max_iterations } => {
+                write!(f, "吸附轮数上限必须 ≥ 1（当前为 {}）", max_iterations)
+            }
+            SnapError::Sweep(e) => write!(f, "{}", e),
+            SnapError::SegmentOrder(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// 扫描求点交后做 snap rounding。
+pub fn snap_round(
+    segments: &Segments,
+    options: &SnapOptions,
+    limits: Limits,
+) -> Result<SnapRounding, SnapError> {
+    check_options(options)?;
+    let records = enumerate_point_intersections_with_limits(segments, limits)?;
+    snap_round_from_records(segments, &records, options)
+}
+
+/// 用已有的点交结果（`segments` 的完整扫描输出，例如 `Phase1Output.intersections`）做 snap rounding。
+pub fn snap_round_from_records(
+    segments: &Segments,
+    records: &[PointIntersectionGroupRecord],
+    options: &SnapOptions,
+) -> Result<SnapRounding, SnapError> {
+    check_options(options)?;
+    let s = options.pixel_size;
+
+    let mut hot: BTreeSet<PointI64> = BTreeSet::new();
+    for seg in segments.iter() {
+        hot.insert(snap_point(PointRat::from_i64(seg.a), s));
+        hot.insert(snap_point(PointRat::from_i64(seg.b), s));
+    }
+    for r in records {
+        hot.insert(snap_point(r.point, s));
+    }
+    let pixels = HotPixels::new(&hot, s);
+
+    // 第一轮：原始线段；之后每轮对上一轮的片段重新吸附。
+    let mut polylines: Vec<Vec<PointI64>> = Vec::with_capacity(segments.len());
+    for seg in segments.iter() {
+        polylines.push(pixels.route(seg)?);
+    }
+    let mut iterations = 1;
+    while iterations < options.max_iterations {
+        let mut changed = false;
+        for line in &mut polylines {
+            let mut rerouted = vec![line[0]];
+            for w in line.windows(2) {
+                let key = SegmentKey::new(w[0], w[1]);
+                let mut route = pixels.route(&Segment {
+                    a: key.a,
+                    b: key.b,
+                    source_index: 0,
+                })?;
+                if key.a != w[0] {
+                    route.reverse();
+                }
+                changed |= route.len() > 2;
+                rerouted.extend_from_slice(&route[1..]);
+            }
+            *line = rerouted;
+        }
+        iterations += 1;
+        if !changed {
+            break;
+        }
+    }
+
+    let mut edges: BTreeMap<SegmentKey, BTreeSet<SegmentId>> = BTreeMap::new();
+    for (i, line) in polylines.iter().enumerate() {
+        for w in line.windows(2) {
+            edges
+                .entry(SegmentKey::new(w[0], w[1]))
+                .or_default()
+                .insert(SegmentId(i));
+        }
+    }
+
+    Ok(SnapRounding {
+        hot_pixels: hot.into_iter().collect(),
+        polylines,
+        edges: edges
+            .into_iter()
+            .map(|(key, ids)| SnappedEdge {
+                a: key.a,
+                b: key.b,
+                segments: ids.into_iter().collect(),
+            })
+            .collect(),
+        iterations,
+    })
+}
+
+/// 像素边长须在 `[1, 2 * MAX_ABS_COORD]` 内：更大的像素已覆盖整个坐标范围，且热像素查找的
+/// 列/行范围（`x ± s`）与取整的中间量都按此上限估算不溢出；吸附轮数上限至少为 1。
+fn check_options(options: &SnapOptions) -> Result<(), SnapError> {
+    let s = options.pixel_size;
+    if !(1..=2 * MAX_ABS_COORD).contains(&s) {
+        return Err(SnapError::InvalidPixelSize { pixel_size: s });
+    }
+    if options.max_iterations == 0 {
+        return Err(SnapError::InvalidMaxIterations {
+            max_iterations: options.max_iterations,
+        });
+    }
+    Ok(())
+}
+
+/// 点所在像素的中心：每个坐标取 `s * floor(v/s + 1/2)`。
+pub fn snap_point(point: PointRat, pixel_size: Coord) -> PointI64 {
+    PointI64 {
+        x: round_half_up(point.x, pixel_size),
+        y: round_half_up(point.y, pixel_size),
+    }
+}
+
+/// 按列分桶的热像素。
+struct HotPixels {
+    columns: BTreeMap<Coord, BTreeSet<Coord>>,
+    size: Coord,
+}
+
+impl HotPixels {
+    fn new(hot: &BTreeSet<PointI64>, size: Coord) -> Self {
+        let mut columns: BTreeMap<Coord, BTreeSet<Coord>> = BTreeMap::new();
+        for p in hot {
+            columns.entry(p.x).or_default().insert(p.y);
+        }
+        Self { columns, size }
+    }
+
+    /// 线段依次穿过的热像素中心（沿 `a -> b`）。
+    fn route(&self, seg: &Segment) -> Result<Vec<PointI64>, SegmentOrderError> {
+        let s = self.size;
+        let int = |v: Coord| Rational::from_int(v as i128);
+        let floor = |v: Rational| v.num().div_euclid(v.den()) as Coord;
+        let mut hits: Vec<PointI64> = Vec::new();
+        for (&cx, rows) in self.columns.range(seg.a.x - s..=seg.b.x + s) {
+            // 线段在该列像素宽度 `[cx - s/2, cx + s/2]` 内的 y 范围；行范围再放宽 `s`，由精确判定筛选。
+            let (ylo, yhi) = if seg.is_vertical() {
+                (seg.a.y, seg.b.y)
+            } else {
+                let x0 = int(seg.a.x).max(Rational::new(2 * cx as i128 - s as i128, 2));
+                let x1 = int(seg.b.x).min(Rational::new(2 * cx as i128 + s as i128, 2));
+                if x0 > x1 {
+                    continue;
+                }
+                let (y0, y1) = (y_at_x(seg, x0)?, y_at_x(seg, x1)?);
+                (floor(y0.min(y1)), floor(y0.max(y1)))
+            };
+            for &cy in rows.range(ylo - s..=yhi + s) {
+                let c = PointI64 { x: cx, y: cy };
+                if segment_hits_pixel(seg, c, s)? {
+                    hits.push(c);
+                }
+            }
+        }
+        // 线段关于 x 单调：列按 x 升序；同一列内按 y 单调方向排列。
+        if seg.b.y < seg.a.y {
+            hits.sort_by(|p, q| p.x.cmp(&q.x).then(q.y.cmp(&p.y)));
+        }
+        debug_assert!(!hits.is_empty(), "端点所在像素必为热像素");
+        Ok(hits)
+    }
+}
+
+/// 闭线段与半开像素是否相交（精确）。
+fn segment_hits_pixel(seg: &Segment, c: PointI64, s: Coord) -> Result<bool, SegmentOrderError> {
+    let half = |v: Coord, sign: i128| Rational::new(2 * v as i128 + sign * s as i128, 2);
+    let (xl, xr) = (half(c.x, -1), half(c.x, 1));
+    let (yb, yt) = (half(c.y, -1), half(c.y, 1));
+    let int = |v: Coord| Rational::from_int(v as i128);
+
+    if seg.is_vertical() {
+        let x = int(seg.a.x);
+        return Ok(xl <= x && x < xr && int(seg.a.y) < yt && int(seg.b.y) >= yb);
+    }
+
+    let x0 = int(seg.a.x).max(xl);
+    if x0 >= xr || x0 > int(seg.b.x) {
+        return Ok(false);
+    }
+    let open_end = int(seg.b.x) >= xr;
+    let x1 = if open_end { xr } else { int(seg.b.x) };
+    let (y0, y1) = (y_at_x(seg, x0)?, y_at_x(seg, x1)?);
+
+    Ok(if !open_end {
+        // x ∈ [x0, x1]：y 取遍闭区间。
+        y0.min(y1) < yt && y0.max(y1) >= yb
+    } else if y0 <= y1 {
+        // x ∈ [x0, xr)：y 取遍 [y0, y1)（水平线段为单点 y0）。
+        y0 < yt && (y1 > yb || (y0 == y1 && y0 >= yb))
+    } else {
+        // y 取遍 (y1, y0]。
+        y1 < yt && y0 >= yb
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::segment::segments_from;
+
+    fn pts(points: &[(i64, i64)]) -> Vec<PointI64> {
+        points.iter().map(|&(x, y)| PointI64 { x, y }).collect()
+    }
+
+    fn run(coords: &[(i64, i64, i64, i64)], options: SnapOptions) -> SnapRounding {
+        snap_round(&segments_from(coords), &options, Limits::default()).unwrap()
+    }
+
+    #[test]
+    fn routes_crossing_segments_through_the_shared_hot_pixel() {
+        // 交点 (3/2, 1/2) 吸附到 (2, 1)；第 1 条线段恰好经过像素下边界（闭）。
+        let out = run(&[(0, 0, 3, 1), (0, 1, 3, 0)], SnapOptions::default());
+        assert_eq!(
+            out.hot_pixels,
+            pts(&[(0, 0), (0, 1), (2, 1), (3, 0), (3, 1)])
+        );
+        assert_eq!(
+            out.polylines,
+            vec![
+                pts(&[(0, 0), (2, 1), (3, 1)]),
+                pts(&[(0, 1), (2, 1), (3, 0)])
+            ]
+        );
+        assert_eq!(out.edges.len(), 4);
+        assert_eq!(out.iterations, 1);
+    }
+
+    #[test]
+    fn reroutes_segments_passing_near_vertices_and_iterates() {
+        // 长线段不与竖线相交，但穿过其端点 (4,1) 所在的热像素。
+        let coords = [(0, 0, 10, 2), (4, 1, 4, 3), (7, 2, 7, 4)];
+        let classic = run(&coords, SnapOptions::default());
+        assert_eq!(classic.polylines[0], pts(&[(0, 0), (4, 1), (10, 2)]));
+
+        // 片段 (4,1)-(10,2) 穿过热像素 (7,2)（原线段没有），迭代模式继续吸附后稳定。
+        let iterated = run(
+            &coords,
+            SnapOptions {
+                max_iterations: 5,
+                ..SnapOptions::default()
+            },
+        );
+        assert_eq!(
+            iterated.polylines[0],
+            pts(&[(0, 0), (4, 1), (7, 2), (10, 2)])
+        );
+        assert_eq!(iterated.iterations, 3);
+        assert_eq!(
+            iterated.edges[2],
+            SnappedEdge {
+                a: PointI64 { x: 4, y: 1 },
+                b: PointI64 { x: 7, y: 2 },
+                segments: vec![SegmentId(0)],
+            }
+        );
+    }
+
+    #[test]
+    fn coarser_pixels_snap_to_multiples_of_the_tolerance() {
+        let options = SnapOptions {
+            pixel_size: 4,
+            max_iterations: 1,
+        };
+        // 交点 (5, 5) -> (4, 4)；(9,1) -> (8, 0)；短线段两端落在同一像素，退化为单点。
+        let out = run(&[(0, 0, 9, 9), (1, 9, 9, 1), (20, 20, 21, 21)], options);
+        assert_eq!(out.polylines[0], pts(&[(0, 0), (4, 4), (8, 8)]));
+        assert_eq!(out.polylines[1], pts(&[(0, 8), (4, 4), (8, 0)]));
+        assert_eq!(out.polylines[2], pts(&[(20, 20)]));
+        assert!(out.hot_pixels.iter().all(|p| p.x % 4 == 0 && p.y % 4 == 0));
+
+        assert_eq!(
+            snap_round(
+                &Segments::new(),
+                &SnapOptions {
+                    pixel_size: 0,
+                    max_iterations: 1,
+                },
+                Limits::default()
+            ),
+            Err(SnapError::InvalidPixelSize { pixel_size: 0 })
+        );
+        let huge = SnapOptions {
+            pixel_size: Coord::MAX,
+            max_iterations: 1,
+        };
+        assert_eq!(
+            snap_round_from_records(&segments_from(&[(0, 0, 1, 1)]), &[], &huge),
+            Err(SnapError::InvalidPixelSize {
+                pixel_size: Coord::MAX
+            })
+        );
+        // 上限本身可用：所有点都吸附到原点所在像素。
+        let widest = SnapOptions {
+            pixel_size: 2 * MAX_ABS_COORD,
+            ..huge
+        };
+        let out = run(
+            &[(
+                -MAX_ABS_COORD,
+                -MAX_ABS_COORD,
+                MAX_ABS_COORD - 1,
+                MAX_ABS_COORD - 1,
+            )],
+            widest,
+        );
+        assert_eq!(out.polylines[0], pts(&[(0, 0)]));
+
+        assert_eq!(
+            snap_round(
+                &Segments::new(),
+                &SnapOptions {
+                    pixel_size: 1,
+                    max_iterations: 0,
+                },
+                Limits::default()
+            ),
+            Err(SnapError::InvalidMaxIterations { max_iterations: 0 })
+        );
+    }
+
+    #[test]
+    fn column_queries_find_the_same_pixels_as_a_full_scan() {
+        // 长对角线穿过一排交叉短线段与平行短线段：热像素遍布包围盒，只有对角线附近的会被经过。
+        let n = 200;
+        let mut coords = vec![(0, 0, n, n), (0, n, n, 0)];
+        for i in (0..n).step_by(4) {
+            coords.push((i, i + 1, i + 1, i));
+            coords.push((i, i + 3, i + 1, i + 4));
+        }
+        let segments = segments_from(&coords);
+        for pixel_size in [1, 3] {
+            let options = SnapOptions {
+                pixel_size,
+                max_iterations: 1,
+            };
+            let out = snap_round(&segments, &options, Limits::default()).unwrap();
+            for (seg, line) in segments.iter().zip(&out.polylines) {
+                let mut expected: Vec<PointI64> = out
+                    .hot_pixels
+                    .iter()
+                    .copied()
+                    .filter(|&c| segment_hits_pixel(seg, c, pixel_size).unwrap())
+                    .collect();
+                if seg.b.y < seg.a.y {
+                    expected.sort_by(|p, q| p.x.cmp(&q.x).then(q.y.cmp(&p.y)));
+                }
+                assert_eq!(*line, expected);
+            }
+            assert!(out.polylines[0].len() > n as usize / 4 / pixel_size as usize);
+        }
+    }
+}
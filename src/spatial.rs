@@ -0,0 +1,355 @@
+//! 静态空间索引：对固定的 `Segments` 按包围盒批量构建 R-tree（STR 装填），回答不需要重跑扫描线的查询。
+//!
+//! 说明：
+//! - 树只按整数包围盒剪枝，最终判定一律用精确谓词（`orient` / `on_segment` 与整数距离平方），结果不含误判；
+//! - 查询结果按 `SegmentId` 升序输出，与树的形状无关；
+//! - 索引构建后不可修改（需要增删线段时见 `crate::incremental`）。
+
+use std::collections::BTreeSet;
+
+pub use crate::geom::bbox::BBox;
+use crate::geom::fixed::{Coord, PointI64};
+use crate::geom::predicates::{on_segment, orient};
+use crate::geom::segment::{SegmentId, Segments};
+
+/// 闭线段 `ab` 与 `cd` 是否有公共点（允许退化为点）。
+pub fn segments_touch(a: PointI64, b: PointI64, c: PointI64, d: PointI64) -> bool {
+    let (o1, o2) = (orient(a, b, c).signum(), orient(a, b, d).signum());
+    let (o3, o4) = (orient(c, d, a).signum(), orient(c, d, b).signum());
+    if o1 * o2 < 0 && o3 * o4 < 0 {
+        return true;
+    }
+    on_segment(a, b, c) || on_segment(a, b, d) || on_segment(c, d, a) || on_segment(c, d, b)
+}
+
+/// 闭线段 `ab` 与闭包围盒是否有公共点。
+pub fn segment_touches_box(a: PointI64, b: PointI64, bbox: &BBox) -> bool {
+    if !bbox.intersects(&BBox::new(a, b)) {
+        return false;
+    }
+    if bbox.contains(a) || bbox.contains(b) {
+        return true;
+    }
+    let corner = |x, y| PointI64 { x, y };
+    let (x0, y0, x1, y1) = (bbox.min.x, bbox.min.y, bbox.max.x, bbox.max.y);
+    let corners = [
+        corner(x0, y0),
+        corner(x1, y0),
+        corner(x1, y1),
+        corner(x0, y1),
+    ];
+    (0..4).any(|i| segments_touch(a, b, corners[i], corners[(i + 1) % 4]))
+}
+
+/// 点 `p` 到闭线段 `ab` 的距离是否不超过 `distance`（整数精确比较距离平方）。
+///
+/// 前提：坐标均在 `[-MAX_ABS_COORD, MAX_ABS_COORD]` 内（中间量按 `u128` 估算不溢出）。
+pub fn segment_within_distance(a: PointI64, b: PointI64, p: PointI64, distance: u64) -> bool {
+    let d2 = (distance as u128) * (distance as u128);
+    let sq = |x: i128, y: i128| (x * x + y * y) as u128;
+    let (abx, aby) = ((b.x - a.x) as i128, (b.y - a.y) as i128);
+    let (apx, apy) = ((p.x - a.x) as i128, (p.y - a.y) as i128);
+    let (bpx, bpy) = ((p.x - b.x) as i128, (p.y - b.y) as i128);
+    // 垂足落在线段外（或线段退化为点）时，最近点是端点。
+    if abx * apx + aby * apy <= 0 {
+        return sq(apx, apy) <= d2;
+    }
+    if abx * bpx + aby * bpy >= 0 {
+        return sq(bpx, bpy) <= d2;
+    }
+    // 到直线的距离：cross² / |ab|² <= d²。
+    let cross = orient(a, b, p).unsigned_abs();
+    d2.checked_mul(sq(abx, aby))
+        .is_none_or(|rhs| cross * cross <= rhs)
+}
+
+/// 每个节点的最大子节点数。
+const NODE_CAPACITY: usize = 16;
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bbox: BBox,
+    /// 叶子层：`items` 的下标范围；其余层：下一层节点的下标范围。
+    start: usize,
+    end: usize,
+}
+
+/// 静态 R-tree。
+#[derive(Clone, Debug)]
+pub struct SegmentIndex {
+    /// 叶子层的线段（按装填顺序）及其包围盒。
+    items: Vec<(SegmentId, BBox)>,
+    /// `levels[0]` 为叶子层，最后一层只有根节点（空索引时没有任何层）。
+    levels: Vec<Vec<Node>>,
+}
+
+impl SegmentIndex {
+    /// 对全部线段批量构建（STR：先按中心 x 切竖条，条内按中心 y 分组）。
+    pub fn build(segments: &Segments) -> Self {
+        let mut items: Vec<(SegmentId, BBox)> = segments
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (SegmentId(i), BBox::of_segment(s)))
+            .collect();
+        str_sort(&mut items, |(id, b)| (b.center2(), id.0));
+
+        let mut levels = Vec::new();
+        let mut current: Vec<Node> = items
+            .chunks(NODE_CAPACITY)
+            .enumerate()
+            .map(|(i, chunk)| Node {
+                bbox: bounds(chunk.iter().map(|(_, b)| b)),
+                start: i * NODE_CAPACITY,
+                end: i * NODE_CAPACITY + chunk.len(),
+            })
+            .collect();
+        while current.len() > 1 {
+            let mut order: Vec<(usize, Node)> = current.into_iter().enumerate().collect();
+            str_sort(&mut order, |(i, n)| (n.bbox.center2(), *i));
+            let children: Vec<Node> = order.into_iter().map(|(_, n)| n).collect();
+            current = children
+                .chunks(NODE_CAPACITY)
+                .enumerate()
+                .map(|(i, chunk)| Node {
+                    bbox: bounds(chunk.iter().map(|n| &n.bbox)),
+                    start: i * NODE_CAPACITY,
+                    end: i * NODE_CAPACITY + chunk.len(),
+                })
+                .collect();
+            levels.push(children);
+        }
+        if !current.is_empty() {
+            levels.push(current);
+        }
+        Self { items, levels }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 包围盒与 `bbox` 相交的线段（升序；只是候选，未做精确判定）。
+    pub fn candidates(&self, bbox: &BBox) -> Vec<SegmentId> {
+        let mut out = Vec::new();
+        let Some(top) = self.levels.len().checked_sub(1) else {
+            return out;
+        };
+        let mut stack: Vec<(usize, usize)> =
+            (0..self.levels[top].len()).map(|i| (top, i)).collect();
+        while let Some((level, i)) = stack.pop() {
+            let node = self.levels[level][i];
+            if !node.bbox.intersects(bbox) {
+                continue;
+            }
+            if level == 0 {
+                out.extend(
+                    self.items[node.start..node.end]
+                        .iter()
+                        .filter(|(_, b)| b.intersects(bbox))
+                        .map(|(id, _)| *id),
+                );
+            } else {
+                stack.extend((node.start..node.end).map(|c| (level - 1, c)));
+            }
+        }
+        out.sort_unstable();
+        out
+    }
+
+    /// 与闭线段 `ab` 有公共点的线段（升序）。
+    pub fn segments_touching_segment(
+        &self,
+        segments: &Segments,
+        a: PointI64,
+        b: PointI64,
+    ) -> Vec<SegmentId> {
+        self.candidates(&BBox::new(a, b))
+            .into_iter()
+            .filter(|id| {
+                let s = segments.get(*id);
+                segments_touch(s.a, s.b, a, b)
+            })
+            .collect()
+    }
+
+    /// 与闭包围盒 `bbox` 有公共点的线段（升序）。
+    pub fn segments_touching_box(&self, segments: &Segments, bbox: &BBox) -> Vec<SegmentId> {
+        self.candidates(bbox)
+            .into_iter()
+            .filter(|id| {
+                let s = segments.get(*id);
+                segment_touches_box(s.a, s.b, bbox)
+            })
+            .collect()
+    }
+
+    /// 到点 `p` 的距离不超过 `distance` 的线段（升序）。
+    pub fn segments_within_distance(
+        &self,
+        segments: &Segments,
+        p: PointI64,
+        distance: u64,
+    ) -> Vec<SegmentId> {
+        let r = distance.min(Coord::MAX as u64) as Coord;
+        let query = BBox::new(
+            PointI64 {
+                x: p.x.saturating_sub(r),
+                y: p.y.saturating_sub(r),
+            },
+            PointI64 {
+                x: p.x.saturating_add(r),
+                y: p.y.saturating_add(r),
+            },
+        );
+        self.candidates(&query)
+            .into_iter()
+            .filter(|id| {
+                let s = segments.get(*id);
+                segment_within_distance(s.a, s.b, p, distance)
+            })
+            .collect()
+    }
+
+    /// 包围盒相交的全部线段对 `(i, j)`（`i < j`，按字典序升序），供暴力参考实现缩小比较范围。
+    pub fn candidate_pairs(&self, segments: &Segments) -> Vec<(SegmentId, SegmentId)> {
+        let mut pairs = BTreeSet::new();
+        for (i, s) in segments.iter().enumerate() {
+            for j in self.candidates(&BBox::of_segment(s)) {
+                if i < j.0 {
+                    pairs.insert((SegmentId(i), j));
+                }
+            }
+        }
+        pairs.into_iter().collect()
+    }
+}
+
+fn bounds<'a>(mut boxes: impl Iterator<Item = &'a BBox>) -> BBox {
+    let first = *boxes.next().expect("节点至少有一个子项");
+    boxes.fold(first, |acc, b| acc.union(b))
+}
+
+/// STR 排序：按 `key` 的中心 x 切成约 `sqrt(节点数)` 个竖条，每个竖条内按中心 y 排序。
+///
+/// `key` 额外带一个唯一的序号，保证排序结果确定。
+fn str_sort<T>(items: &mut [T], key: impl Fn(&T) -> ((Coord, Coord), usize)) {
+    items.sort_by_key(|t| {
+        let ((x, y), i) = key(t);
+        (x, y, i)
+    });
+    let nodes = items.len().div_ceil(NODE_CAPACITY);
+    let slices = (nodes as f64).sqrt().ceil().max(1.0) as usize;
+    let per_slice = nodes.div_ceil(slices) * NODE_CAPACITY;
+    for slice in items.chunks_mut(per_slice.max(1)) {
+        slice.sort_by_key(|t| {
+            let ((x, y), i) = key(t);
+            (y, x, i)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz::XorShift64;
+    use crate::naive::{
+        enumerate_point_intersections_naive, enumerate_point_intersections_naive_indexed,
+    };
+    use crate::preprocess::{InputSegmentI64, preprocess_segments_i64};
+
+    fn p(x: Coord, y: Coord) -> PointI64 {
+        PointI64 { x, y }
+    }
+
+    fn random_segments(rng: &mut XorShift64, n: usize, grid: Coord) -> Segments {
+        let input: Vec<InputSegmentI64> = (0..n)
+            .map(|_| InputSegmentI64 {
+                ax: rng.range(-grid, grid),
+                ay: rng.range(-grid, grid),
+                bx: rng.range(-grid, grid),
+                by: rng.range(-grid, grid),
+            })
+            .collect();
+        preprocess_segments_i64(&input).segments
+    }
+
+    #[test]
+    fn exact_predicates_handle_touching_and_degenerate_queries() {
+        let bbox = BBox::new(p(4, 4), p(0, 0));
+        // 只碰到角点 / 只碰到边 / 完全在外 / 穿过而端点都在外。
+        assert!(segment_touches_box(p(4, 4), p(6, 8), &bbox));
+        assert!(segment_touches_box(p(-2, 6), p(2, 4), &bbox));
+        assert!(!segment_touches_box(p(3, 6), p(6, 3), &bbox));
+        assert!(segment_touches_box(p(-1, 2), p(5, 3), &bbox));
+        // 退化为点的查询线段。
+        assert!(segments_touch(p(0, 0), p(4, 2), p(2, 1), p(2, 1)));
+        assert!(!segments_touch(p(0, 0), p(4, 2), p(2, 2), p(2, 2)));
+
+        // (3,4) 到 (0,0)-(6,0) 的距离为 4；到端点 (0,0) 的距离为 5。
+        assert!(segment_within_distance(p(0, 0), p(6, 0), p(3, 4), 4));
+        assert!(!segment_within_distance(p(0, 0), p(6, 0), p(3, 4), 3));
+        assert!(segment_within_distance(p(6, 8), p(9, 12), p(0, 0), 10));
+        assert!(!segment_within_distance(p(6, 8), p(9, 12), p(0, 0), 9));
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let mut rng = XorShift64::new(23);
+        for _ in 0..20 {
+            let n = rng.range(0, 300) as usize;
+            let grid = rng.range(4, 200);
+            let segments = random_segments(&mut rng, n, grid);
+            let index = SegmentIndex::build(&segments);
+            assert_eq!(index.len(), segments.len());
+            let all = || (0..segments.len()).map(SegmentId);
+
+            for _ in 0..20 {
+                let (a, b) = (
+                    p(rng.range(-grid, grid), rng.range(-grid, grid)),
+                    p(rng.range(-grid, grid), rng.range(-grid, grid)),
+                );
+                let bbox = BBox::new(a, b);
+                let d = rng.below(grid as u64);
+
+                let touching: Vec<_> = all()
+                    .filter(|id| {
+                        let s = segments.get(*id);
+                        segments_touch(s.a, s.b, a, b)
+                    })
+                    .collect();
+                let in_box: Vec<_> = all()
+                    .filter(|id| {
+                        let s = segments.get(*id);
+                        segment_touches_box(s.a, s.b, &bbox)
+                    })
+                    .collect();
+                let near: Vec<_> = all()
+                    .filter(|id| {
+                        let s = segments.get(*id);
+                        segment_within_distance(s.a, s.b, a, d)
+                    })
+                    .collect();
+                assert_eq!(index.segments_touching_segment(&segments, a, b), touching);
+                assert_eq!(index.segments_touching_box(&segments, &bbox), in_box);
+                assert_eq!(index.segments_within_distance(&segments, a, d), near);
+            }
+
+            let pairs: Vec<_> = all()
+                .flat_map(|i| all().filter(move |j| i < *j).map(move |j| (i, j)))
+                .filter(|(i, j)| {
+                    BBox::of_segment(segments.get(*i))
+                        .intersects(&BBox::of_segment(segments.get(*j)))
+                })
+                .collect();
+            assert_eq!(index.candidate_pairs(&segments), pairs);
+            assert_eq!(
+                enumerate_point_intersections_naive_indexed(&segments),
+                enumerate_point_intersections_naive(&segments)
+            );
+        }
+    }
+}
@@ -1,14 +1,20 @@
 use core::fmt;
+use core::ops::ControlFlow;
 use std::collections::{BTreeMap, BTreeSet};
 
+use crate::geom::fixed::{Coord, MAX_ABS_COORD};
 use crate::geom::intersection::{
-    PointIntersectionGroupRecord, PointIntersectionKind, SegmentIntersection, intersect_segments,
+    LayeredGroupRecord, PointIntersectionGroupRecord, PointIntersectionKind, SegmentIntersection,
+    intersect_segments,
 };
 use crate::geom::point::PointRat;
-use crate::geom::segment::{SegmentId, Segments};
+use crate::geom::predicates::orient;
+use crate::geom::segment::{LayerId, SegmentId, Segments};
 use crate::limits::{LimitExceeded, LimitKind, Limits};
+use crate::overlap::{OverlapSegmentRecord, enumerate_collinear_overlaps};
 use crate::rational::Rational;
 use crate::sweep::event_queue::{Event, EventQueue};
+use crate::sweep::sink::{FnSink, IntersectionSink, SweepEnd};
 use crate::sweep::status::{SweepStatus, SweepStatusError, TreapSweepStatus};
 use crate::trace::Trace;
 use crate::trace::TraceStep;
@@ -17,6 +23,11 @@ use crate::trace::TraceStep;
 pub enum BoError {
     Status(SweepStatusError),
     Limits(LimitExceeded),
+    /// 双色模式的 `layers` 与线段数量不一致（应以 `SegmentId` 为下标一一对应）。
+    LayerCountMismatch {
+        layers: usize,
+        segments: usize,
+    },
 }
 
 impl From<SweepStatusError> for BoError {
@@ -36,6 +47,11 @@ impl fmt::Display for BoError {
         match self {
             BoError::Status(e) => write!(f, "{}", e),
             BoError::Limits(e) => write!(f, "{}", e),
+            BoError::LayerCountMismatch { layers, segments } => write!(
+                f,
+                "图层数量 {} 与线段数量 {} 不一致（layers 必须以 SegmentId 为下标一一对应）",
+                layers, segments
+            ),
         }
     }
 }
@@ -79,7 +95,7 @@ impl PointIntersectionGroupBuilder {
 ///
 /// 说明：
 /// - 垂直线段不进入状态结构，而是在 x 批处理结束时用 `range_by_y` 做命中查询；
-/// - 对共线重叠只返回占位，不输出“重叠段”（第二阶段再做）。
+/// - 共线重叠不在这里输出“重叠段”（见 `crate::overlap` 与 `run::run_phase2`）。
 pub fn enumerate_point_intersections(
     segments: &Segments,
 ) -> Result<Vec<PointIntersectionGroupRecord>, BoError> {
@@ -96,39 +112,285 @@ pub fn enumerate_point_intersections_with_limits(
     segments: &Segments,
     limits: Limits,
 ) -> Result<Vec<PointIntersectionGroupRecord>, BoError> {
-    run_bentley_ottmann(segments, None, limits)
+    let mut out = Vec::new();
+    run_bentley_ottmann(segments, None, limits, None, Slab::default(), &mut out)?;
+    Ok(out)
 }
 
 pub fn enumerate_point_intersections_with_trace_and_limits(
     segments: &Segments,
     limits: Limits,
 ) -> Result<(Vec<PointIntersectionGroupRecord>, Trace), BoError> {
+    let mut out = Vec::new();
+    let mut trace = Trace::default();
+    run_bentley_ottmann(
+        segments,
+        Some(&mut trace),
+        limits,
+        None,
+        Slab::default(),
+        &mut out,
+    )?;
+    Ok((out, trace))
+}
+
+/// 流式枚举点交：每个事件批处理结束即把点组交给 `sink`（见 `crate::sweep::sink`），不在内部收集。
+///
+/// 说明：点组不在内部缓存，因此 `limits.max_intersections` 不生效（输出规模由 sink 自行控制，
+/// 需要上限时在 sink 中返回 `ControlFlow::Break`）；trace 相关的上限照常生效。
+pub fn enumerate_point_intersections_into<S: IntersectionSink + ?Sized>(
+    segments: &Segments,
+    limits: Limits,
+    sink: &mut S,
+) -> Result<SweepEnd, BoError> {
+    run_bentley_ottmann(
+        segments,
+        None,
+        streaming(limits),
+        None,
+        Slab::default(),
+        sink,
+    )
+}
+
+/// 同 `enumerate_point_intersections_into`，并返回 trace（提前结束时 trace 截止到最后一个已处理的批）。
+pub fn enumerate_point_intersections_into_with_trace<S: IntersectionSink + ?Sized>(
+    segments: &Segments,
+    limits: Limits,
+    sink: &mut S,
+) -> Result<(SweepEnd, Trace), BoError> {
     let mut trace = Trace::default();
-    let intersections = run_bentley_ottmann(segments, Some(&mut trace), limits)?;
-    Ok((intersections, trace))
+    let end = run_bentley_ottmann(
+        segments,
+        Some(&mut trace),
+        streaming(limits),
+        None,
+        Slab::default(),
+        sink,
+    )?;
+    Ok((end, trace))
 }
 
-fn run_bentley_ottmann(
+/// 流式接口的上限：不限制交给 sink 的点组数。
+fn streaming(limits: Limits) -> Limits {
+    Limits {
+        max_intersections: usize::MAX,
+        ..limits
+    }
+}
+
+/// 双色（多图层）模式：只报告涉及至少两个不同图层的点组（例如道路 × 河流），`layers` 以 `SegmentId` 为下标。
+///
+/// 说明：
+/// - 同一图层的线段对从不调度交点事件，也不单独输出图层内部的交叉/接触；
+/// - 图层内部允许交叉：同层线段在交叉处只调度 `Event::Swap`。若该点只有重排事件、且经过它的线段
+///   都属于同一图层，批处理只在 `x+ε` 处重排这些线段，不构建点组；否则（交叉点恰有其他图层线段经过）
+///   该点按正常点组输出（包含经过该点的全部线段）；
+/// - 代价仍与完整扫描同阶：同层交叉点仍是事件点（每个 `O(log n)`），省下的只是点组构建与过滤；
+/// - 只有一个图层时不可能存在跨图层点组，直接返回；
+/// - `layers.len() != segments.len()` 时返回 `BoError::LayerCountMismatch`。
+pub fn enumerate_bichromatic_intersections(
+    segments: &Segments,
+    layers: &[LayerId],
+    limits: Limits,
+) -> Result<Vec<LayeredGroupRecord>, BoError> {
+    let mut out = Vec::new();
+    run_bichromatic(segments, layers, limits, &mut out)?;
+    Ok(out
+        .iter()
+        .map(|g| LayeredGroupRecord::from_group(g, layers))
+        .collect())
+}
+
+/// 双色模式的流式版本（sink 收到的点组可用 `LayeredGroupRecord::from_group` 附上图层；
+/// 与 `enumerate_point_intersections_into` 一样不限制点组数）。
+pub fn enumerate_bichromatic_intersections_into<S: IntersectionSink + ?Sized>(
+    segments: &Segments,
+    layers: &[LayerId],
+    limits: Limits,
+    sink: &mut S,
+) -> Result<SweepEnd, BoError> {
+    run_bichromatic(segments, layers, streaming(limits), sink)
+}
+
+fn run_bichromatic<S: IntersectionSink + ?Sized>(
+    segments: &Segments,
+    layers: &[LayerId],
+    limits: Limits,
+    sink: &mut S,
+) -> Result<SweepEnd, BoError> {
+    if layers.len() != segments.len() {
+        return Err(BoError::LayerCountMismatch {
+            layers: layers.len(),
+            segments: segments.len(),
+        });
+    }
+    if layers.windows(2).all(|w| w[0] == w[1]) {
+        return Ok(SweepEnd::Completed);
+    }
+    run_bentley_ottmann(segments, None, limits, Some(layers), Slab::default(), sink)
+}
+
+/// 早停查询的选项（见 `first_intersection` / `has_any_intersection`）。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntersectionQueryOptions {
+    /// 允许“端点-端点接触”的线段对（例如折线的相邻边），按 `(小ID, 大ID)` 存储。
+    ///
+    /// 说明：只放行仅由这些线段对的端点重合构成的点组；只要该点还有内部经过的线段，
+    /// 或存在一对非指定线段的端点重合，仍视为交点。
+    pub neighbour_pairs: BTreeSet<(SegmentId, SegmentId)>,
+}
+
+impl IntersectionQueryOptions {
+    /// 指定 `a`、`b` 互为相邻线段：它们之间的端点-端点接触不作为交点报告。
+    pub fn allow_endpoint_contact(&mut self, a: SegmentId, b: SegmentId) {
+        self.neighbour_pairs.insert((a.min(b), a.max(b)));
+    }
+
+    fn are_neighbours(&self, a: SegmentId, b: SegmentId) -> bool {
+        self.neighbour_pairs.contains(&(a.min(b), a.max(b)))
+    }
+
+    /// 点组是否需要报告：存在内部参与者，或存在一对非相邻线段在该点端点重合。
+    pub fn is_reportable(&self, group: &PointIntersectionGroupRecord) -> bool {
+        if !group.interior_segments.is_empty() {
+            return true;
+        }
+        let ids = &group.endpoint_segments;
+        ids.iter()
+            .enumerate()
+            .any(|(i, &a)| ids[i + 1..].iter().any(|&b| !self.are_neighbours(a, b)))
+    }
+}
+
+/// 早停查询的结果：第一个可报告的点交，或一段共线重叠。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FirstIntersection {
+    Point(PointIntersectionGroupRecord),
+    /// 长度>0 的共线重叠（点交扫描不输出重叠段，这里由 `crate::overlap` 单独检测）。
+    Overlap(OverlapSegmentRecord),
+}
+
+impl FirstIntersection {
+    /// 结果所在位置：点交的点，或重叠段的起点（`(x,y)` 较小端）。
+    pub fn point(&self) -> PointRat {
+        match self {
+            FirstIntersection::Point(group) => group.point,
+            FirstIntersection::Overlap(overlap) => PointRat::from_i64(overlap.a),
+        }
+    }
+}
+
+/// 找到 `(x, y)` 字典序最小的可报告交点后停止扫描（用于“线段集合是否简单”的校验）。
+///
+/// 说明：
+/// - 点交按 `options.is_reportable` 过滤，被放行的点组不计入 `max_intersections`；
+/// - 同一 x 上垂直线段的命中在该 x 的批末才输出，因此扫描到第一个可报告点组后继续处理完该 x，
+///   取其中最小的点，遇到更大的 x 即停止；
+/// - 共线重叠先由 `crate::overlap` 检测（`O(n log n)`）；若最早的重叠段起点（按同一字典序）不晚于该点交，
+///   返回重叠段（相邻线段“折返”也属于重叠，不受 `neighbour_pairs` 放行）。
+pub fn first_intersection(
+    segments: &Segments,
+    options: &IntersectionQueryOptions,
+) -> Result<Option<FirstIntersection>, BoError> {
+    let overlap = enumerate_collinear_overlaps(segments)
+        .segments
+        .into_iter()
+        .min_by_key(|r| (r.a, r.b));
+
+    let mut found: Option<PointIntersectionGroupRecord> = None;
+    let mut sink = FnSink(|group: PointIntersectionGroupRecord| {
+        match &found {
+            Some(best) if group.point.x > best.point.x => return ControlFlow::Break(()),
+            Some(best) if group.point >= best.point => {}
+            _ if options.is_reportable(&group) => found = Some(group),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    });
+    run_query_sweep(segments, &mut sink)?;
+
+    Ok(match (found, overlap) {
+        (Some(group), Some(overlap)) if PointRat::from_i64(overlap.a) <= group.point => {
+            Some(FirstIntersection::Overlap(overlap))
+        }
+        (Some(group), _) => Some(FirstIntersection::Point(group)),
+        (None, overlap) => overlap.map(FirstIntersection::Overlap),
+    })
+}
+
+/// 是否存在任何可报告的交点或共线重叠（见 `first_intersection`）。
+///
+/// 说明：存在共线重叠时直接返回，不再扫描；否则在第一个可报告点组处停止。
+pub fn has_any_intersection(
+    segments: &Segments,
+    options: &IntersectionQueryOptions,
+) -> Result<bool, BoError> {
+    if !enumerate_collinear_overlaps(segments).segments.is_empty() {
+        return Ok(true);
+    }
+    let mut found = false;
+    let mut sink = FnSink(|group: PointIntersectionGroupRecord| {
+        if options.is_reportable(&group) {
+            found = true;
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    run_query_sweep(segments, &mut sink)?;
+    Ok(found)
+}
+
+/// 早停查询的扫描：不限制点组数（被放行的点组可能很多，且 sink 不缓存）。
+fn run_query_sweep<S: IntersectionSink + ?Sized>(
+    segments: &Segments,
+    sink: &mut S,
+) -> Result<SweepEnd, BoError> {
+    enumerate_point_intersections_into(segments, Limits::default(), sink)
+}
+
+fn run_bentley_ottmann<S: IntersectionSink + ?Sized>(
     segments: &Segments,
     mut trace: Option<&mut Trace>,
     limits: Limits,
-) -> Result<Vec<PointIntersectionGroupRecord>, BoError> {
+    layers: Option<&[LayerId]>,
+    slab: Slab,
+    sink: &mut S,
+) -> Result<SweepEnd, BoError> {
     let mut queue = EventQueue::new();
     for id in 0..segments.len() {
         let id = SegmentId(id);
         let seg = segments.get(id);
-        queue.push(
-            PointRat::from_i64(seg.a),
-            Event::SegmentStart { segment: id },
-        );
-        queue.push(PointRat::from_i64(seg.b), Event::SegmentEnd { segment: id });
+        if slab.contains(seg.a.x) {
+            queue.push(
+                PointRat::from_i64(seg.a),
+                Event::SegmentStart { segment: id },
+            );
+        }
+        if slab.contains(seg.b.x) {
+            queue.push(PointRat::from_i64(seg.b), Event::SegmentEnd { segment: id });
+        }
     }
 
     let mut status = TreapSweepStatus::new(Rational::from_int(0));
     let mut scheduled: BTreeSet<(PointRat, SegmentId, SegmentId)> = BTreeSet::new();
+    if let Some(start) = slab.start {
+        seed_slab_status(
+            segments,
+            start,
+            &mut status,
+            &mut queue,
+            &mut scheduled,
+            layers,
+        )?;
+    }
     let mut pending_vertical: BTreeSet<SegmentId> = BTreeSet::new();
     let mut pending_x: Option<Rational> = None;
-    let mut out: Vec<PointIntersectionGroupRecord> = Vec::new();
+    // 当前 x 上已由事件点批处理输出（含垂直线段）的点：VerticalFlush 不再重复输出。
+    let mut vertical_points_at_x: BTreeSet<PointRat> = BTreeSet::new();
+    // 已交给 sink 的点组数（用于 `max_intersections`）。
+    let mut emitted: usize = 0;
     let mut trace_active_entries_total: usize = 0;
 
     let mut push_trace_step_with_limits =
@@ -169,38 +431,53 @@ fn run_bentley_ottmann(
     };
 
     while let Some((point, events)) = queue.pop_next_batch() {
-        if let Some(x) = pending_x {
-            if point.x != x {
-                if !pending_vertical.is_empty() {
-                    let hits = collect_vertical_hit_groups(segments, &status, &pending_vertical)?;
-                    ensure_can_add_groups(out.len(), hits.len())?;
-
-                    if let Some(trace) = trace.as_deref_mut() {
-                        let mut step = TraceStep::vertical_flush(x);
-                        step.events = pending_vertical
-                            .iter()
-                            .map(|id| format!("Vertical({})", id.0))
-                            .collect();
-                        step.active = status.snapshot_order();
-                        step.intersections = hits.clone();
-                        for &v_id in &pending_vertical {
-                            let v = segments.get(v_id);
-                            let y_min = v.a.y.min(v.b.y);
-                            let y_max = v.a.y.max(v.b.y);
-                            step.notes.push(format!(
-                                "VerticalRange({}): y=[{},{}]",
-                                v_id.0, y_min, y_max
-                            ));
-                        }
-                        push_trace_step_with_limits(trace, step)?;
+        if let Some(end) = slab.end
+            && point.x >= Rational::from_int(end as i128)
+        {
+            // 条带之外的事件点（只可能是交点事件）由右侧条带负责。
+            break;
+        }
+        if let Some(x) = pending_x
+            && point.x != x
+        {
+            if !pending_vertical.is_empty() {
+                let mut hits = collect_vertical_hit_groups(
+                    segments,
+                    &status,
+                    &pending_vertical,
+                    &vertical_points_at_x,
+                )?;
+                retain_multilayer_groups(&mut hits, layers);
+                ensure_can_add_groups(emitted, hits.len())?;
+
+                if let Some(trace) = trace.as_deref_mut() {
+                    let mut step = TraceStep::vertical_flush(x);
+                    step.events = pending_vertical
+                        .iter()
+                        .map(|id| format!("Vertical({})", id.0))
+                        .collect();
+                    step.active = status.snapshot_order();
+                    step.intersections = hits.clone();
+                    for &v_id in &pending_vertical {
+                        let v = segments.get(v_id);
+                        let y_min = v.a.y.min(v.b.y);
+                        let y_max = v.a.y.max(v.b.y);
+                        step.notes.push(format!(
+                            "VerticalRange({}): y=[{},{}]",
+                            v_id.0, y_min, y_max
+                        ));
                     }
-
-                    out.extend(hits);
+                    push_trace_step_with_limits(trace, step)?;
                 }
 
-                pending_vertical.clear();
-                pending_x = None;
+                if emit_groups(sink, hits, &mut emitted).is_break() {
+                    return Ok(SweepEnd::Stopped);
+                }
             }
+
+            pending_vertical.clear();
+            vertical_points_at_x.clear();
+            pending_x = None;
         }
         if pending_x.is_none() {
             pending_x = Some(point.x);
@@ -215,31 +492,22 @@ fn run_bentley_ottmann(
             step.events = events.iter().map(|e| event_to_string(*e)).collect();
         }
 
-        let mut intersection_groups: BTreeMap<PointRat, PointIntersectionGroupBuilder> =
-            BTreeMap::new();
-
         // 同一事件点上“作为端点出现”的线段：它们至少在该点存在端点-端点接触。
         let mut endpoint_ids_at_point: Vec<SegmentId> = events
             .iter()
             .filter_map(|e| match *e {
                 Event::SegmentStart { segment } | Event::SegmentEnd { segment } => Some(segment),
-                Event::Intersection { .. } => None,
+                Event::Intersection { .. } | Event::Swap { .. } => None,
             })
             .collect();
         endpoint_ids_at_point.sort();
         endpoint_ids_at_point.dedup();
 
-        if !endpoint_ids_at_point.is_empty() {
-            let group = intersection_groups.entry(point).or_default();
-            for &id in &endpoint_ids_at_point {
-                group.add_segment(segments, point, id);
-            }
-            if endpoint_ids_at_point.len() >= 2 {
-                if let Some(step) = step.as_mut() {
-                    step.notes
-                        .push(format!("EndpointSegments: {}", endpoint_ids_at_point.len()));
-                }
-            }
+        if endpoint_ids_at_point.len() >= 2
+            && let Some(step) = step.as_mut()
+        {
+            step.notes
+                .push(format!("EndpointSegments: {}", endpoint_ids_at_point.len()));
         }
 
         // 用 U/L/C(p) 的批处理语义替代“逐条事件顺序处理”，避免退化下出现“已删除线段仍被重排”。
@@ -275,6 +543,12 @@ fn run_bentley_ottmann(
                             .push(format!("IntersectionEvent({},{})", a.0, b.0));
                     }
                 }
+                Event::Swap { a, b } => {
+                    // 同层交叉：经过该点的线段都会作为 C(p) 重排，这里无需额外处理。
+                    if let Some(step) = step.as_mut() {
+                        step.notes.push(format!("SwapEvent({},{})", a.0, b.0));
+                    }
+                }
             }
         }
 
@@ -285,40 +559,56 @@ fn run_bentley_ottmann(
         intersection_pairs.sort();
         intersection_pairs.dedup();
 
-        // 端点接触（端点-内部）：端点线段在该点可能会“碰到”某条穿过该点的活动线段。
-        // 这类交点不应作为 `Intersection` 事件调度，但仍应在该点输出。
-        let mut endpoint_ids: Vec<SegmentId> = u.clone();
-        endpoint_ids.extend_from_slice(&l);
-        endpoint_ids.sort();
-        endpoint_ids.dedup();
-        record_endpoint_on_interior_hits(
-            segments,
-            &status,
-            point,
-            &endpoint_ids,
-            &mut intersection_groups,
-            step.as_mut(),
-        )?;
-
-        // 垂直线段的批末查询发生在 x 变化时；这会遗漏“非垂直线段在该 x 处结束”的端点接触。
-        // 这里在删除结束线段之前，补齐它们与 pending_vertical 的端点接触输出。
-        record_vertical_endpoint_touches_for_ending_segments(
-            segments,
-            &pending_vertical,
-            point,
-            &l,
-            &mut intersection_groups,
-            step.as_mut(),
-        )?;
+        // 经过 p 的全部线段：端点集合（U/L 与垂直线段端点）+ 活动线段中在 p 处 y 相等者（L(p) 与内部穿过者）
+        // + 覆盖 p 的 pending_vertical。该点的点组由它们统一构建，而不是只依赖相邻对的交点事件：
+        // 共线重叠的线段在状态结构中相邻，穿过它们的线段只会与其中一条相邻。
+        let through = status.range_by_y(segments, point.y, point.y)?;
+        let hit_verticals = pending_verticals_containing(segments, &pending_vertical, point);
+        if !hit_verticals.is_empty() {
+            // 垂直线段的批末查询发生在 x 变化时：该点已在此完整输出，VerticalFlush 跳过该点，
+            // 避免同一点被 PointBatch 与 VerticalFlush 各输出一次。
+            vertical_points_at_x.insert(point);
+        }
+        // 双色模式下只有同层重排、且经过 p 的线段都属于同一图层：该点不可能输出点组，只需重排 C(p)。
+        let same_layer_swap = match layers {
+            Some(layers) => {
+                hit_verticals.is_empty()
+                    && events.iter().all(|e| matches!(e, Event::Swap { .. }))
+                    && through.windows(2).all(|w| layers[w[0].0] == layers[w[1].0])
+            }
+            None => false,
+        };
+        let group = if same_layer_swap {
+            if let Some(step) = step.as_mut() {
+                step.notes
+                    .push(format!("SameLayerSwap: through={}", through.len()));
+            }
+            PointIntersectionGroupBuilder::default()
+        } else {
+            build_point_group(
+                segments,
+                point,
+                &endpoint_ids_at_point,
+                &through,
+                &hit_verticals,
+            )
+        };
+        if let Some(step) = step.as_mut()
+            && !same_layer_swap
+            && (!through.is_empty() || !hit_verticals.is_empty())
+        {
+            step.notes.push(format!(
+                "PointGroup: through={} vertical={}",
+                through.len(),
+                hit_verticals.len()
+            ));
+        }
 
-        let mut c: Vec<SegmentId> = Vec::new();
-        for (a, b) in &intersection_pairs {
-            let a = *a;
-            let b = *b;
-            if let Some(SegmentIntersection::Point { point: ip, kind }) =
-                intersect_segments(segments.get(a), segments.get(b))
-            {
-                if let Some(step) = step.as_mut() {
+        if let Some(step) = step.as_mut() {
+            for (a, b) in &intersection_pairs {
+                if let Some(SegmentIntersection::Point { point: ip, kind }) =
+                    intersect_segments(segments.get(*a), segments.get(*b))
+                {
                     step.notes.push(format!(
                         "IntersectionAt({},{}) -> {} @ {}",
                         a.0,
@@ -327,15 +617,16 @@ fn run_bentley_ottmann(
                         format_point(ip)
                     ));
                 }
-                let group = intersection_groups.entry(ip).or_default();
-                group.add_segment(segments, ip, a);
-                group.add_segment(segments, ip, b);
-                if kind == PointIntersectionKind::Proper {
-                    c.push(a);
-                    c.push(b);
-                }
             }
         }
+
+        // C(p)：以内部穿过 p 的活动线段（Proper 交点事件涉及的线段、端点落在其内部的线段等）。
+        // 它们必须随 U/L 一起按 x+ε 重排并检查新邻居，否则与删除 L(p) 后新相邻线段的交点会被遗漏。
+        let mut c: Vec<SegmentId> = through
+            .iter()
+            .copied()
+            .filter(|id| l.binary_search(id).is_err())
+            .collect();
         c.sort();
         c.dedup();
 
@@ -383,8 +674,9 @@ fn run_bentley_ottmann(
                     point,
                     a,
                     b,
+                    layers,
                     step.as_mut(),
-                );
+                )?;
             }
         } else {
             for id in &to_insert {
@@ -396,8 +688,9 @@ fn run_bentley_ottmann(
                         point,
                         pred,
                         *id,
+                        layers,
                         step.as_mut(),
-                    );
+                    )?;
                 }
                 if let Some(succ) = status.succ(*id) {
                     schedule_or_record_pair(
@@ -407,19 +700,19 @@ fn run_bentley_ottmann(
                         point,
                         *id,
                         succ,
+                        layers,
                         step.as_mut(),
-                    );
+                    )?;
                 }
             }
         }
 
         let mut hits: Vec<PointIntersectionGroupRecord> = Vec::new();
-        for (ip, group) in &intersection_groups {
-            if group.total_segments() >= 2 {
-                hits.push(group.build(*ip));
-            }
+        if group.total_segments() >= 2 {
+            hits.push(group.build(point));
         }
-        ensure_can_add_groups(out.len(), hits.len())?;
+        retain_multilayer_groups(&mut hits, layers);
+        ensure_can_add_groups(emitted, hits.len())?;
 
         if let Some(trace) = trace.as_deref_mut() {
             let mut step = step.expect("trace 存在时 step 应为 Some");
@@ -427,45 +720,149 @@ fn run_bentley_ottmann(
             step.intersections = hits.clone();
             push_trace_step_with_limits(trace, step)?;
         }
-        out.extend(hits);
+        if emit_groups(sink, hits, &mut emitted).is_break() {
+            return Ok(SweepEnd::Stopped);
+        }
     }
 
-    if let Some(x) = pending_x {
-        if !pending_vertical.is_empty() {
-            let hits = collect_vertical_hit_groups(segments, &status, &pending_vertical)?;
-            ensure_can_add_groups(out.len(), hits.len())?;
-
-            if let Some(trace) = trace.as_deref_mut() {
-                let mut step = TraceStep::vertical_flush(x);
-                step.events = pending_vertical
-                    .iter()
-                    .map(|id| format!("Vertical({})", id.0))
-                    .collect();
-                step.active = status.snapshot_order();
-                step.intersections = hits.clone();
-                for &v_id in &pending_vertical {
-                    let v = segments.get(v_id);
-                    let y_min = v.a.y.min(v.b.y);
-                    let y_max = v.a.y.max(v.b.y);
-                    step.notes.push(format!(
-                        "VerticalRange({}): y=[{},{}]",
-                        v_id.0, y_min, y_max
-                    ));
-                }
-                push_trace_step_with_limits(trace, step)?;
+    if let Some(x) = pending_x
+        && !pending_vertical.is_empty()
+    {
+        let mut hits = collect_vertical_hit_groups(
+            segments,
+            &status,
+            &pending_vertical,
+            &vertical_points_at_x,
+        )?;
+        retain_multilayer_groups(&mut hits, layers);
+        ensure_can_add_groups(emitted, hits.len())?;
+
+        if let Some(trace) = trace {
+            let mut step = TraceStep::vertical_flush(x);
+            step.events = pending_vertical
+                .iter()
+                .map(|id| format!("Vertical({})", id.0))
+                .collect();
+            step.active = status.snapshot_order();
+            step.intersections = hits.clone();
+            for &v_id in &pending_vertical {
+                let v = segments.get(v_id);
+                let y_min = v.a.y.min(v.b.y);
+                let y_max = v.a.y.max(v.b.y);
+                step.notes.push(format!(
+                    "VerticalRange({}): y=[{},{}]",
+                    v_id.0, y_min, y_max
+                ));
             }
+            push_trace_step_with_limits(trace, step)?;
+        }
 
-            out.extend(hits);
+        if emit_groups(sink, hits, &mut emitted).is_break() {
+            return Ok(SweepEnd::Stopped);
         }
     }
 
+    Ok(SweepEnd::Completed)
+}
+
+/// 只扫描 `slab` 范围内的事件点，收集输出（供 `crate::sweep::parallel` 的工作线程调用）。
+pub(crate) fn run_slab(
+    segments: &Segments,
+    limits: Limits,
+    slab: Slab,
+) -> Result<Vec<PointIntersectionGroupRecord>, BoError> {
+    let mut out = Vec::new();
+    run_bentley_ottmann(segments, None, limits, None, slab, &mut out)?;
     Ok(out)
 }
 
+/// 扫描的 x 范围（并行条带划分用，见 `crate::sweep::parallel`）：只处理 `start <= x < end` 的事件点。
+///
+/// 默认值（两端均为 `None`）即完整扫描。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Slab {
+    pub start: Option<Coord>,
+    pub end: Option<Coord>,
+}
+
+impl Slab {
+    pub(crate) fn contains(&self, x: Coord) -> bool {
+        self.start.is_none_or(|s| s <= x) && self.end.is_none_or(|e| x < e)
+    }
+}
+
+/// 条带起点的状态结构：放入穿过 `x = start` 的非垂直线段（`a.x < start <= b.x`），
+/// 并为相邻对调度 `x >= start` 的交点事件。
+///
+/// 说明：`x = start` 上的交叉对按交叉后的顺序插入；该点的事件批处理会把它们作为 `C(p)` 重新插入，
+/// 因而与顺序扫描得到相同的状态与输出。
+fn seed_slab_status(
+    segments: &Segments,
+    start: Coord,
+    status: &mut TreapSweepStatus,
+    queue: &mut EventQueue,
+    scheduled: &mut BTreeSet<(PointRat, SegmentId, SegmentId)>,
+    layers: Option<&[LayerId]>,
+) -> Result<(), BoError> {
+    status.set_sweep_x(Rational::from_int(start as i128));
+    for (i, seg) in segments.iter().enumerate() {
+        if seg.a.x < start && start <= seg.b.x {
+            status.insert(segments, SegmentId(i))?;
+        }
+    }
+    // 比 `x = start` 上的任何点都小、比 `x < start` 的任何点都大：只放行条带内的交点。
+    let before_slab = PointRat {
+        x: Rational::from_int(start as i128),
+        y: Rational::from_int(-(MAX_ABS_COORD as i128) - 1),
+    };
+    let order = status.snapshot_order();
+    for w in order.windows(2) {
+        schedule_or_record_pair(
+            segments,
+            queue,
+            scheduled,
+            before_slab,
+            w[0],
+            w[1],
+            layers,
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// 双色模式：只保留涉及至少两个不同图层的点组。
+fn retain_multilayer_groups(
+    hits: &mut Vec<PointIntersectionGroupRecord>,
+    layers: Option<&[LayerId]>,
+) {
+    let Some(layers) = layers else {
+        return;
+    };
+    hits.retain(|g| {
+        let mut ids = g.endpoint_segments.iter().chain(&g.interior_segments);
+        let first = ids.next().map(|id| layers[id.0]);
+        ids.any(|id| Some(layers[id.0]) != first)
+    });
+}
+
+fn emit_groups<S: IntersectionSink + ?Sized>(
+    sink: &mut S,
+    hits: Vec<PointIntersectionGroupRecord>,
+    emitted: &mut usize,
+) -> ControlFlow<()> {
+    for hit in hits {
+        *emitted += 1;
+        sink.accept(hit)?;
+    }
+    ControlFlow::Continue(())
+}
+
 fn collect_vertical_hit_groups(
     segments: &Segments,
     status: &impl SweepStatus,
     vertical: &BTreeSet<SegmentId>,
+    handled_points: &BTreeSet<PointRat>,
 ) -> Result<Vec<PointIntersectionGroupRecord>, BoError> {
     if vertical.is_empty() || status.is_empty() {
         return Ok(Vec::new());
@@ -479,9 +876,6 @@ fn collect_vertical_hit_groups(
             "collect_vertical_hit_groups 仅应处理垂直线段"
         );
 
-        let v_a = PointRat::from_i64(v.a);
-        let v_b = PointRat::from_i64(v.b);
-
         let y_min = Rational::from_int(v.a.y.min(v.b.y) as i128);
         let y_max = Rational::from_int(v.a.y.max(v.b.y) as i128);
 
@@ -493,11 +887,8 @@ fn collect_vertical_hit_groups(
                 continue;
             };
 
-            let s = segments.get(s_id);
-            if (point == v_a || point == v_b)
-                && (point == PointRat::from_i64(s.a) || point == PointRat::from_i64(s.b))
-            {
-                // 端点-端点接触在事件点已输出，避免在 VerticalFlush 重复输出。
+            if handled_points.contains(&point) {
+                // 事件点上的命中（含端点-端点接触）已在该事件点整体输出，避免在 VerticalFlush 重复输出。
                 continue;
             }
 
@@ -517,111 +908,69 @@ fn collect_vertical_hit_groups(
     Ok(hits)
 }
 
-fn record_endpoint_on_interior_hits(
+/// 在事件点 `p` 构建点组。
+///
+/// 说明：
+/// - 候选为所有经过 `p` 的线段（端点集合、活动线段中经过 `p` 者、覆盖 `p` 的垂直线段）；
+/// - 只要候选中存在两个不同方向，每条候选线段都与另一方向的某条线段交于 `p`，全部输出；
+/// - 若候选全部共线，则它们之间只有重叠（留给 phase2）或端点相接：仅输出以 `p` 为端点的线段。
+fn build_point_group(
     segments: &Segments,
-    status: &impl SweepStatus,
     point: PointRat,
     endpoint_ids: &[SegmentId],
-    intersection_groups: &mut BTreeMap<PointRat, PointIntersectionGroupBuilder>,
-    mut trace_step: Option<&mut TraceStep>,
-) -> Result<(), BoError> {
-    if endpoint_ids.is_empty() || status.is_empty() {
-        return Ok(());
-    }
-
-    let endpoint_set: BTreeSet<SegmentId> = endpoint_ids.iter().copied().collect();
-
-    // 找出所有在 x=point.x 处 y 恰好等于 point.y 的活动线段：它们穿过事件点，且未必以该点为端点。
-    let candidates = status.range_by_y(segments, point.y, point.y)?;
-    if candidates.is_empty() {
-        return Ok(());
-    }
-
-    let mut added = 0_usize;
-    for &e_id in endpoint_ids {
-        for &s_id in &candidates {
-            if s_id == e_id {
-                continue;
-            }
-            if endpoint_set.contains(&s_id) {
-                // 两端点都在该点的情况已由端点集合覆盖，避免重复探测。
-                continue;
-            }
-
-            let Some(SegmentIntersection::Point { point: ip, kind }) =
-                intersect_segments(segments.get(e_id), segments.get(s_id))
-            else {
-                continue;
-            };
-            if ip != point || kind != PointIntersectionKind::EndpointTouch {
-                continue;
-            }
-
-            let group = intersection_groups.entry(ip).or_default();
-            group.add_segment(segments, ip, e_id);
-            group.add_segment(segments, ip, s_id);
-            added += 1;
-        }
-    }
-
-    if added != 0 {
-        if let Some(step) = trace_step.as_mut() {
-            step.notes.push(format!("EndpointOnInterior: {}", added));
-        }
+    through: &[SegmentId],
+    hit_verticals: &[SegmentId],
+) -> PointIntersectionGroupBuilder {
+    let mut candidates: Vec<SegmentId> = endpoint_ids
+        .iter()
+        .chain(through)
+        .chain(hit_verticals)
+        .copied()
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+
+    let mut group = PointIntersectionGroupBuilder::default();
+    let Some(&first) = candidates.first() else {
+        return group;
+    };
+    let f = segments.get(first);
+    let has_crossing = candidates.iter().any(|&id| {
+        let s = segments.get(id);
+        orient(f.a, f.b, s.a) != 0 || orient(f.a, f.b, s.b) != 0
+    });
+
+    let members = if has_crossing {
+        &candidates[..]
+    } else {
+        endpoint_ids
+    };
+    for &id in members {
+        group.add_segment(segments, point, id);
     }
-    Ok(())
+    group
 }
 
-fn record_vertical_endpoint_touches_for_ending_segments(
+/// 覆盖事件点 `p`（端点或内部）的 pending_vertical（升序）。
+fn pending_verticals_containing(
     segments: &Segments,
     pending_vertical: &BTreeSet<SegmentId>,
     point: PointRat,
-    ending_ids: &[SegmentId],
-    intersection_groups: &mut BTreeMap<PointRat, PointIntersectionGroupBuilder>,
-    mut trace_step: Option<&mut TraceStep>,
-) -> Result<(), BoError> {
-    if pending_vertical.is_empty() || ending_ids.is_empty() {
-        return Ok(());
-    }
-
-    let mut added = 0_usize;
-    for &s_id in ending_ids {
-        let s = segments.get(s_id);
-        debug_assert!(!s.is_vertical(), "ending_ids 不应包含垂直线段");
-
-        for &v_id in pending_vertical {
+) -> Vec<SegmentId> {
+    pending_vertical
+        .iter()
+        .copied()
+        .filter(|&v_id| {
             let v = segments.get(v_id);
             debug_assert!(v.is_vertical(), "pending_vertical 仅应包含垂直线段");
-
-            // 垂直线段的端点接触（端点-端点）已在事件点按端点集合输出，避免重复。
-            if point == PointRat::from_i64(v.a) || point == PointRat::from_i64(v.b) {
-                continue;
-            }
-
-            let Some(SegmentIntersection::Point { point: ip, kind }) = intersect_segments(v, s)
-            else {
-                continue;
-            };
-            if ip != point || kind != PointIntersectionKind::EndpointTouch {
-                continue;
-            }
-
-            let group = intersection_groups.entry(ip).or_default();
-            group.add_segment(segments, ip, v_id);
-            group.add_segment(segments, ip, s_id);
-            added += 1;
-        }
-    }
-
-    if added != 0 {
-        if let Some(step) = trace_step.as_mut() {
-            step.notes
-                .push(format!("VerticalEndpointTouch(end): {}", added));
-        }
-    }
-    Ok(())
+            let y_min = Rational::from_int(v.a.y.min(v.b.y) as i128);
+            let y_max = Rational::from_int(v.a.y.max(v.b.y) as i128);
+            point.x == Rational::from_int(v.a.x as i128) && y_min <= point.y && point.y <= y_max
+        })
+        .collect()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn schedule_or_record_pair(
     segments: &Segments,
     queue: &mut EventQueue,
@@ -629,10 +978,11 @@ fn schedule_or_record_pair(
     current_point: PointRat,
     a: SegmentId,
     b: SegmentId,
+    layers: Option<&[LayerId]>,
     mut trace_step: Option<&mut TraceStep>,
-) {
+) -> Result<(), BoError> {
     if a == b {
-        return;
+        return Ok(());
     }
     let (a, b) = if a <= b { (a, b) } else { (b, a) };
 
@@ -640,16 +990,19 @@ fn schedule_or_record_pair(
         if let Some(step) = trace_step.as_mut() {
             step.notes.push(format!("Check({},{}) -> none", a.0, b.0));
         }
-        return;
+        return Ok(());
     };
 
     match hit {
-        SegmentIntersection::CollinearOverlap => {
-            // 第一阶段暂不输出“重叠段”。后续会引入“最大重叠段集合”输出。
+        SegmentIntersection::CollinearOverlap { a: oa, b: ob } => {
+            // 点交扫描不输出“重叠段”：最大重叠段集合由 `crate::overlap` 按共线组统一计算（phase2）。
             if let Some(step) = trace_step.as_mut() {
                 step.notes.push(format!(
-                    "Check({},{}) -> CollinearOverlap(phase2)",
-                    a.0, b.0
+                    "Check({},{}) -> CollinearOverlap [{} - {}] (phase2)",
+                    a.0,
+                    b.0,
+                    format_point(PointRat::from_i64(oa)),
+                    format_point(PointRat::from_i64(ob))
                 ));
             }
         }
@@ -657,7 +1010,7 @@ fn schedule_or_record_pair(
             if point == current_point {
                 // 端点接触输出由事件点批处理统一负责（端点集合 + 必要的端点-内部/垂直补齐）。
                 // 这里仅用于“不要调度过去/当前点”的防重复保护。
-                return;
+                return Ok(());
             }
             if point < current_point {
                 if let Some(step) = trace_step.as_mut() {
@@ -668,7 +1021,7 @@ fn schedule_or_record_pair(
                         format_point(point)
                     ));
                 }
-                return;
+                return Ok(());
             }
 
             // `Intersection` 事件仅代表“需要在 x+ε 发生重排”的交点；端点接触不应触发重排，
@@ -682,30 +1035,35 @@ fn schedule_or_record_pair(
                         format_point(point)
                     ));
                 }
-                return;
+                return Ok(());
             }
 
+            // 双色模式：同一图层的线段对不调度交点事件，只调度重排事件，使状态结构在交叉处交换二者的顺序。
+            let event = match layers {
+                Some(layers) if layers[a.0] == layers[b.0] => Event::swap(a, b),
+                _ => Event::intersection(a, b),
+            };
+
             let key = (point, a, b);
             if scheduled.insert(key) {
-                queue.push(point, Event::intersection(a, b));
+                queue.push(point, event);
                 if let Some(step) = trace_step.as_mut() {
                     step.notes.push(format!(
-                        "ScheduleIntersection({},{}) @ {}",
-                        a.0,
-                        b.0,
+                        "Schedule{} @ {}",
+                        event_to_string(event),
                         format_point(point)
                     ));
                 }
             } else if let Some(step) = trace_step.as_mut() {
                 step.notes.push(format!(
-                    "ScheduleIntersection({},{}) @ {} (dedup)",
-                    a.0,
-                    b.0,
+                    "Schedule{} @ {} (dedup)",
+                    event_to_string(event),
                     format_point(point)
                 ));
             }
         }
     }
+    Ok(())
 }
 
 fn event_to_string(event: Event) -> String {
@@ -713,6 +1071,7 @@ fn event_to_string(event: Event) -> String {
         Event::SegmentStart { segment } => format!("SegmentStart({})", segment.0),
         Event::SegmentEnd { segment } => format!("SegmentEnd({})", segment.0),
         Event::Intersection { a, b } => format!("Intersection({},{})", a.0, b.0),
+        Event::Swap { a, b } => format!("Swap({},{})", a.0, b.0),
     }
 }
 
@@ -724,11 +1083,11 @@ fn format_id_list(ids: &[SegmentId], limit: usize) -> String {
     let mut out = String::new();
     out.push('[');
     let shown = ids.len().min(limit);
-    for i in 0..shown {
+    for (i, id) in ids.iter().take(shown).enumerate() {
         if i != 0 {
             out.push(',');
         }
-        out.push_str(&ids[i].0.to_string());
+        out.push_str(&id.0.to_string());
     }
     if ids.len() > shown {
         out.push_str(",...");
@@ -743,7 +1102,9 @@ fn format_id_list(ids: &[SegmentId], limit: usize) -> String {
 mod tests {
     use super::*;
     use crate::geom::fixed::PointI64;
+    use crate::geom::intersection::LayeredSegmentRef;
     use crate::geom::segment::Segment;
+    use crate::geom::segment::segments_from;
     use crate::limits::{LimitExceeded, LimitKind, Limits};
     use crate::trace::TraceStepKind;
 
@@ -1047,6 +1408,50 @@ mod tests {
         assert_eq!(flush_count as i64, n);
     }
 
+    fn group(
+        x: i128,
+        y: i128,
+        endpoint: &[usize],
+        interior: &[usize],
+    ) -> PointIntersectionGroupRecord {
+        PointIntersectionGroupRecord {
+            point: PointRat {
+                x: Rational::from_int(x),
+                y: Rational::from_int(y),
+            },
+            endpoint_segments: endpoint.iter().map(|&i| SegmentId(i)).collect(),
+            interior_segments: interior.iter().map(|&i| SegmentId(i)).collect(),
+        }
+    }
+
+    #[test]
+    fn reports_crossing_on_vertical_once() {
+        // 回归测试：垂直线段经过交叉点时，该点曾被 PointBatch 与 VerticalFlush 各输出一次。
+        let segments = segments_from(&[(0, 0, 4, 4), (0, 4, 4, 0), (2, 0, 2, 4)]);
+        let out = enumerate_point_intersections(&segments).unwrap();
+        assert_eq!(out, vec![group(2, 2, &[], &[0, 1, 2])]);
+    }
+
+    #[test]
+    fn reorders_segments_crossing_at_an_endpoint_event() {
+        // 回归测试：0/1 在第 2 条线段的终点处交叉，但之前被它隔开、没有交点事件；
+        // 它们未随 C(p) 重排，导致第 3 条线段插入后只与错误的邻居比较，漏掉 (4,4)。
+        let segments = segments_from(&[(0, 0, 6, 6), (0, 4, 6, -2), (0, 2, 2, 2), (3, 5, 5, 3)]);
+        let out = enumerate_point_intersections(&segments).unwrap();
+        assert_eq!(
+            out,
+            vec![group(2, 2, &[2], &[0, 1]), group(4, 4, &[], &[0, 3])]
+        );
+    }
+
+    #[test]
+    fn reports_every_segment_of_a_collinear_stack_at_a_crossing() {
+        // 回归测试：穿过共线重叠段的线段只与其中相邻的一条产生交点事件，另一条曾被漏掉。
+        let segments = segments_from(&[(0, 0, 4, 0), (1, 0, 5, 0), (1, -2, 3, 2)]);
+        let out = enumerate_point_intersections(&segments).unwrap();
+        assert_eq!(out, vec![group(2, 0, &[], &[0, 1, 2])]);
+    }
+
     #[test]
     fn fails_fast_when_trace_steps_exceed_limit() {
         let mut segments = Segments::new();
@@ -1135,4 +1540,314 @@ mod tests {
         }
         assert!(err.to_string().contains("建议："));
     }
+
+    #[test]
+    fn streams_groups_in_sweep_order_and_stops_early() {
+        // 3 条水平线 × 3 条竖线：9 个交点，全部由 VerticalFlush 按 x 分三批输出。
+        let mut segments = Segments::new();
+        for (i, y) in [2_i64, 5, 8].into_iter().enumerate() {
+            segments.push(Segment {
+                a: PointI64 { x: 0, y },
+                b: PointI64 { x: 10, y },
+                source_index: i,
+            });
+        }
+        for (i, x) in [3_i64, 5, 7].into_iter().enumerate() {
+            segments.push(Segment {
+                a: PointI64 { x, y: 0 },
+                b: PointI64 { x, y: 10 },
+                source_index: 3 + i,
+            });
+        }
+        let collected = enumerate_point_intersections(&segments).unwrap();
+        assert_eq!(collected.len(), 9);
+
+        let mut streamed = Vec::new();
+        let end = enumerate_point_intersections_into(&segments, Limits::default(), &mut streamed)
+            .unwrap();
+        assert_eq!(end, SweepEnd::Completed);
+        assert_eq!(streamed, collected);
+
+        let mut seen = Vec::new();
+        let mut sink = FnSink(|group: PointIntersectionGroupRecord| {
+            seen.push(group);
+            if seen.len() == 4 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        let (end, trace) =
+            enumerate_point_intersections_into_with_trace(&segments, Limits::default(), &mut sink)
+                .unwrap();
+        assert_eq!(end, SweepEnd::Stopped);
+        assert_eq!(seen, collected[..4]);
+        let full_trace = enumerate_point_intersections_with_trace(&segments)
+            .unwrap()
+            .1;
+        assert!(trace.steps.len() < full_trace.steps.len());
+
+        // 流式输出不受 `max_intersections` 限制；收集版本仍按上限报错。
+        let limits = Limits {
+            max_intersections: 2,
+            ..Limits::default()
+        };
+        let mut streamed = Vec::new();
+        assert_eq!(
+            enumerate_point_intersections_into(&segments, limits, &mut streamed),
+            Ok(SweepEnd::Completed)
+        );
+        assert_eq!(streamed, collected);
+        let mut streamed = Vec::new();
+        assert!(
+            enumerate_point_intersections_into_with_trace(&segments, limits, &mut streamed).is_ok()
+        );
+        assert_eq!(streamed, collected);
+        assert!(enumerate_point_intersections_with_limits(&segments, limits).is_err());
+        assert!(enumerate_point_intersections_with_trace_and_limits(&segments, limits).is_err());
+    }
+
+    fn layered(coords: &[(i64, i64, i64, i64, u32)]) -> (Segments, Vec<LayerId>) {
+        let plain: Vec<_> = coords
+            .iter()
+            .map(|&(ax, ay, bx, by, _)| (ax, ay, bx, by))
+            .collect();
+        let segments = segments_from(&plain);
+        let layers = segments
+            .iter()
+            .map(|s| LayerId(coords[s.source_index].4))
+            .collect();
+        (segments, layers)
+    }
+
+    #[test]
+    fn bichromatic_mode_matches_naive_filtered_to_cross_layer_groups() {
+        let (segments, layers) = layered(&[
+            // 图层 0：共享端点的水平链、一条水平线，以及端点落在该水平线内部的竖线（图层内接触）。
+            (0, 0, 3, 0, 0),
+            (3, 0, 6, 0, 0),
+            (0, 2, 6, 2, 0),
+            (1, 2, 1, 3, 0),
+            // 图层 1：两条平行斜线。
+            (0, -1, 4, 3, 1),
+            (2, -1, 6, 3, 1),
+            // 图层 2：竖线穿过图层 0 的共享端点 (3,0)。
+            (3, -1, 3, 3, 2),
+        ]);
+
+        let out =
+            enumerate_bichromatic_intersections(&segments, &layers, Limits::default()).unwrap();
+        let expected: Vec<LayeredGroupRecord> =
+            crate::naive::enumerate_point_intersections_naive(&segments)
+                .iter()
+                .map(|g| LayeredGroupRecord::from_group(g, &layers))
+                .filter(|g| g.layers().len() >= 2)
+                .collect();
+        assert_eq!(out, expected);
+
+        let at = |x: i128, y: i128| PointRat {
+            x: Rational::from_int(x),
+            y: Rational::from_int(y),
+        };
+        let points: Vec<PointRat> = out.iter().map(|g| g.point).collect();
+        // 图层内的接触 (1,2) 不报告；(3,0) 有图层 2 经过，整组保留（含图层 0 的两条端点线段）。
+        assert!(!points.contains(&at(1, 2)));
+        let shared = out.iter().find(|g| g.point == at(3, 0)).unwrap();
+        assert_eq!(shared.layers(), vec![LayerId(0), LayerId(1), LayerId(2)]);
+        assert_eq!(
+            shared.endpoint_segments,
+            vec![
+                LayeredSegmentRef {
+                    segment: SegmentId(0),
+                    layer: LayerId(0),
+                },
+                LayeredSegmentRef {
+                    segment: SegmentId(1),
+                    layer: LayerId(0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn bichromatic_mode_reorders_crossings_inside_a_layer_silently() {
+        // 道路（图层 0）在 (2,2) 交叉，河流（图层 1）在 (5,5) 穿过道路 0：只报告后者。
+        let (segments, layers) = layered(&[(0, 0, 6, 6, 0), (0, 4, 4, 0, 0), (4, 6, 6, 4, 1)]);
+        let out =
+            enumerate_bichromatic_intersections(&segments, &layers, Limits::default()).unwrap();
+        let points: Vec<PointRat> = out.iter().map(|g| g.point).collect();
+        assert_eq!(
+            points,
+            vec![PointRat {
+                x: Rational::from_int(5),
+                y: Rational::from_int(5),
+            }]
+        );
+        // (2,2) 只有同层重排：不构建点组。
+        let mut trace = Trace::default();
+        let mut sink = Vec::new();
+        run_bentley_ottmann(
+            &segments,
+            Some(&mut trace),
+            Limits::default(),
+            Some(&layers),
+            Slab::default(),
+            &mut sink,
+        )
+        .unwrap();
+        let at = |x, y| PointRat {
+            x: Rational::from_int(x),
+            y: Rational::from_int(y),
+        };
+        let step = trace
+            .steps
+            .iter()
+            .find(|s| s.point == Some(at(2, 2)))
+            .unwrap();
+        assert!(step.notes.iter().any(|n| n.starts_with("SameLayerSwap")));
+        assert!(step.intersections.is_empty());
+
+        // 同层交叉点恰有其他图层线段经过：按正常点组输出全部三条线段。
+        let (segments, layers) = layered(&[(0, 0, 4, 4, 0), (0, 4, 4, 0, 0), (0, 2, 4, 2, 1)]);
+        let out =
+            enumerate_bichromatic_intersections(&segments, &layers, Limits::default()).unwrap();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].point, at(2, 2));
+        assert_eq!(out[0].layers().len(), 2);
+
+        // 随机的自交图层：与暴力结果按跨图层过滤后一致。
+        let mut rng = crate::fuzz::XorShift64::new(16);
+        for _ in 0..200 {
+            let n = rng.range(2, 24) as usize;
+            let coords: Vec<_> = (0..n)
+                .map(|_| {
+                    (
+                        rng.range(0, 10),
+                        rng.range(0, 10),
+                        rng.range(0, 10),
+                        rng.range(0, 10),
+                        rng.below(3) as u32,
+                    )
+                })
+                .filter(|&(ax, ay, bx, by, _)| (ax, ay) != (bx, by))
+                .collect();
+            let (segments, layers) = layered(&coords);
+            let mut out =
+                enumerate_bichromatic_intersections(&segments, &layers, Limits::default()).unwrap();
+            // 垂直线段上的命中在该 x 的批末输出，扫描顺序不一定按点升序。
+            out.sort_by_key(|g| g.point);
+            let expected: Vec<LayeredGroupRecord> =
+                crate::naive::enumerate_point_intersections_naive(&segments)
+                    .iter()
+                    .map(|g| LayeredGroupRecord::from_group(g, &layers))
+                    .filter(|g| g.layers().len() >= 2)
+                    .collect();
+            assert_eq!(out, expected, "{coords:?}");
+        }
+
+        // 单一图层直接返回；图层数量不一致时报错而不是 panic。
+        let (segments, _) = layered(&[(0, 0, 4, 4, 0), (0, 4, 4, 0, 0)]);
+        assert_eq!(
+            enumerate_bichromatic_intersections(&segments, &[LayerId(3); 2], Limits::default()),
+            Ok(vec![])
+        );
+        assert_eq!(
+            enumerate_bichromatic_intersections(&segments, &[LayerId(0)], Limits::default()),
+            Err(BoError::LayerCountMismatch {
+                layers: 1,
+                segments: 2,
+            })
+        );
+    }
+
+    fn polyline(points: &[(i64, i64)], closed: bool) -> (Segments, IntersectionQueryOptions) {
+        let mut segments = Segments::new();
+        let mut options = IntersectionQueryOptions::default();
+        let n = if closed {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        for i in 0..n {
+            let (p, q) = (points[i], points[(i + 1) % points.len()]);
+            let (mut a, mut b) = (PointI64 { x: p.0, y: p.1 }, PointI64 { x: q.0, y: q.1 });
+            if b < a {
+                core::mem::swap(&mut a, &mut b);
+            }
+            let id = segments.push(Segment {
+                a,
+                b,
+                source_index: i,
+            });
+            if i > 0 {
+                options.allow_endpoint_contact(SegmentId(i - 1), id);
+            }
+        }
+        if closed {
+            options.allow_endpoint_contact(SegmentId(n - 1), SegmentId(0));
+        }
+        (segments, options)
+    }
+
+    #[test]
+    fn simple_polylines_have_no_reportable_intersection() {
+        // 含竖边与共线相邻边的闭合多边形。
+        let square = [(0, 0), (5, 0), (10, 0), (10, 10), (0, 10)];
+        let (segments, options) = polyline(&square, true);
+        assert_eq!(first_intersection(&segments, &options).unwrap(), None);
+        // 不放行相邻边时，每个顶点都是端点-端点接触。
+        assert!(has_any_intersection(&segments, &IntersectionQueryOptions::default()).unwrap());
+    }
+
+    #[test]
+    fn first_intersection_reports_crossings_touches_and_backtracks() {
+        // 自交“8”字：第 0 边与第 2 边在 (5,5) 相交。
+        let (segments, options) = polyline(&[(0, 0), (10, 10), (10, 0), (0, 10)], false);
+        let hit = first_intersection(&segments, &options).unwrap().unwrap();
+        assert_eq!(
+            hit,
+            FirstIntersection::Point(PointIntersectionGroupRecord {
+                point: PointRat {
+                    x: Rational::from_int(5),
+                    y: Rational::from_int(5),
+                },
+                endpoint_segments: vec![],
+                interior_segments: vec![SegmentId(0), SegmentId(2)],
+            })
+        );
+
+        // 第 3 边回到第 1 个顶点：三条边在 (4,0) 端点重合，非相邻的 0 与 3 触发报告。
+        let (segments, options) = polyline(&[(0, 0), (4, 0), (4, 4), (0, 4), (4, 0)], false);
+        let hit = first_intersection(&segments, &options).unwrap().unwrap();
+        assert_eq!(hit.point(), PointRat::from_i64(PointI64 { x: 4, y: 0 }));
+
+        // 相邻边折返：只能由共线重叠检测发现。
+        let (segments, options) = polyline(&[(0, 0), (4, 0), (2, 0)], false);
+        assert_eq!(
+            first_intersection(&segments, &options).unwrap(),
+            Some(FirstIntersection::Overlap(OverlapSegmentRecord {
+                a: PointI64 { x: 2, y: 0 },
+                b: PointI64 { x: 4, y: 0 },
+                segments: vec![SegmentId(0), SegmentId(1)],
+            }))
+        );
+        assert!(has_any_intersection(&segments, &options).unwrap());
+    }
+
+    #[test]
+    fn first_intersection_is_the_lexicographic_minimum_within_its_x() {
+        // (2,5) 是事件点，先于竖线上的 (2,1) 输出（后者在 x=2 的批末才输出）；结果仍取 (2,1)。
+        let segments = segments_from(&[(2, 0, 2, 10), (0, 3, 4, 7), (0, 7, 4, 3), (0, 1, 4, 1)]);
+        let options = IntersectionQueryOptions::default();
+        let mut streamed = Vec::new();
+        enumerate_point_intersections_into(&segments, Limits::default(), &mut streamed).unwrap();
+        assert_eq!(streamed[0].point.y, Rational::from_int(5));
+
+        let hit = first_intersection(&segments, &options).unwrap().unwrap();
+        assert_eq!(hit.point(), PointRat::from_i64(PointI64 { x: 2, y: 1 }));
+        assert!(has_any_intersection(&segments, &options).unwrap());
+        assert!(
+            !has_any_intersection(&segments_from(&[(0, 0, 1, 1), (2, 0, 3, 1)]), &options).unwrap()
+        );
+    }
 }
@@ -5,9 +5,21 @@ use crate::geom::segment::SegmentId;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Event {
-    SegmentStart { segment: SegmentId },
-    SegmentEnd { segment: SegmentId },
-    Intersection { a: SegmentId, b: SegmentId },
+    SegmentStart {
+        segment: SegmentId,
+    },
+    SegmentEnd {
+        segment: SegmentId,
+    },
+    Intersection {
+        a: SegmentId,
+        b: SegmentId,
+    },
+    /// 双色模式下同一图层两条线段的交叉：只在该点按 `x+ε` 重排，不作为交点输出。
+    Swap {
+        a: SegmentId,
+        b: SegmentId,
+    },
 }
 
 impl Event {
@@ -16,11 +28,16 @@ impl Event {
         Event::Intersection { a, b }
     }
 
+    pub fn swap(a: SegmentId, b: SegmentId) -> Self {
+        let (a, b) = if a <= b { (a, b) } else { (b, a) };
+        Event::Swap { a, b }
+    }
+
     fn priority(&self) -> u8 {
         match self {
             // 以 `x+ε` 语义为主的批处理：先移除，再处理交点重排，最后插入。
             Event::SegmentEnd { .. } => 0,
-            Event::Intersection { .. } => 1,
+            Event::Intersection { .. } | Event::Swap { .. } => 1,
             Event::SegmentStart { .. } => 2,
         }
     }
@@ -28,7 +45,7 @@ impl Event {
     fn ids_for_ordering(&self) -> (SegmentId, SegmentId) {
         match *self {
             Event::SegmentStart { segment } | Event::SegmentEnd { segment } => (segment, segment),
-            Event::Intersection { a, b } => (a, b),
+            Event::Intersection { a, b } | Event::Swap { a, b } => (a, b),
         }
     }
 }
@@ -1,4 +1,6 @@
 pub mod bo;
 pub mod event_queue;
+pub mod parallel;
 pub mod segment_order;
+pub mod sink;
 pub mod status;
@@ -0,0 +1,158 @@
+//! 并行点交枚举：按竖直条带划分 x 轴，每个条带在独立线程里跑一遍扫描线，最后按条带顺序拼接。
+//!
+//! 说明：
+//! - 条带边界取所有端点 x 坐标的分位数（整数），条带 `i` 负责 `X_i <= x < X_{i+1}` 的事件点；
+//! - 条带的状态结构在 `x = X_i` 处用穿过左边界的线段初始化（见 `bo::seed_slab_status`），
+//!   垂直线段与落在边界上的交点都归属右侧条带；
+//! - 每个条带的输出与顺序扫描在该 x 范围内的输出完全一致，拼接后与
+//!   `enumerate_point_intersections_with_limits` 的结果逐条相同（顺序也相同）。
+
+use std::thread;
+
+use crate::geom::fixed::Coord;
+use crate::geom::intersection::PointIntersectionGroupRecord;
+use crate::geom::segment::Segments;
+use crate::limits::{LimitExceeded, LimitKind, Limits};
+use crate::sweep::bo::{BoError, Slab, enumerate_point_intersections_with_limits, run_slab};
+
+/// 用至多 `threads` 个线程并行枚举点交（`threads <= 1` 时退化为顺序扫描）。
+///
+/// 说明：
+/// - 输出与顺序扫描逐条相同；
+/// - `limits.max_intersections` 对每个条带与拼接后的总数分别检查。超限时报错的 `actual`
+///   为触发时的计数，可能与顺序扫描报告的值不同；
+/// - 线程数可取 `std::thread::available_parallelism()`；线段很少或端点 x 坐标种类很少时条带数会相应减少。
+pub fn enumerate_point_intersections_parallel(
+    segments: &Segments,
+    limits: Limits,
+    threads: usize,
+) -> Result<Vec<PointIntersectionGroupRecord>, BoError> {
+    let slabs = partition_slabs(segments, threads);
+    if slabs.len() <= 1 {
+        return enumerate_point_intersections_with_limits(segments, limits);
+    }
+
+    let results: Vec<Result<Vec<PointIntersectionGroupRecord>, BoError>> = thread::scope(|scope| {
+        let handles: Vec<_> = slabs
+            .iter()
+            .map(|&slab| scope.spawn(move || run_slab(segments, limits, slab)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("条带扫描线程 panic"))
+            .collect()
+    });
+
+    let mut out = Vec::new();
+    for result in results {
+        out.extend(result?);
+        if out.len() > limits.max_intersections {
+            return Err(BoError::Limits(LimitExceeded {
+                kind: LimitKind::Intersections,
+                limit: limits.max_intersections,
+                actual: out.len(),
+            }));
+        }
+    }
+    Ok(out)
+}
+
+/// 按端点 x 坐标的分位数切出至多 `threads` 个条带（按 x 升序，首尾条带向两侧无界）。
+fn partition_slabs(segments: &Segments, threads: usize) -> Vec<Slab> {
+    let mut xs: Vec<Coord> = segments.iter().flat_map(|s| [s.a.x, s.b.x]).collect();
+    xs.sort_unstable();
+    xs.dedup();
+
+    let count = threads.min(xs.len()).max(1);
+    // 下标严格递增且 `xs` 已去重，因此边界严格递增。
+    let bounds: Vec<Coord> = (1..count).map(|i| xs[i * xs.len() / count]).collect();
+
+    let mut slabs = Vec::with_capacity(count);
+    let mut start = None;
+    for &x in &bounds {
+        slabs.push(Slab {
+            start,
+            end: Some(x),
+        });
+        start = Some(x);
+    }
+    slabs.push(Slab { start, end: None });
+    slabs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz::XorShift64;
+    use crate::geom::segment::segments_from;
+
+    fn assert_matches_sequential(segments: &Segments) {
+        let expected = enumerate_point_intersections_with_limits(segments, Limits::default());
+        for threads in [1, 2, 3, 5, 8, 64] {
+            assert_eq!(
+                enumerate_point_intersections_parallel(segments, Limits::default(), threads),
+                expected,
+                "threads={threads}"
+            );
+        }
+    }
+
+    #[test]
+    fn boundary_crossings_verticals_and_touches_match_sequential() {
+        // 端点 x 为 {0,2,4,6,8}：多线程时 x=2/4/6 都会成为条带边界。
+        let segments = segments_from(&[
+            // 在 (4,4) 交叉，交点恰在边界上。
+            (0, 0, 8, 8),
+            (0, 8, 8, 0),
+            // 垂直线段位于边界上，经过上面的交点。
+            (4, 0, 4, 8),
+            // 端点落在边界上另一条线段的内部。
+            (2, 2, 6, 2),
+            (4, 2, 8, 6),
+            // 跨越多个条带、在 x=6 处与第 0 条相交。
+            (0, 6, 8, 6),
+            (6, 0, 6, 1),
+        ]);
+        assert!(!partition_slabs(&segments, 3).is_empty());
+        assert_matches_sequential(&segments);
+    }
+
+    #[test]
+    fn random_dense_grids_match_sequential() {
+        let mut rng = XorShift64::new(21);
+        for _ in 0..60 {
+            let n = rng.range(2, 40) as usize;
+            let grid = rng.range(2, 12);
+            // 零长度与重复线段由预处理过滤。
+            let list: Vec<_> = (0..n)
+                .map(|_| {
+                    (
+                        rng.range(0, grid),
+                        rng.range(0, grid),
+                        rng.range(0, grid),
+                        rng.range(0, grid),
+                    )
+                })
+                .collect();
+            assert_matches_sequential(&segments_from(&list));
+        }
+    }
+
+    #[test]
+    fn limit_is_checked_on_merged_output() {
+        let segments = segments_from(&[(0, 0, 4, 4), (0, 4, 4, 0), (4, 0, 8, 4), (4, 4, 8, 0)]);
+        let limits = Limits {
+            max_intersections: 2,
+            ..Limits::default()
+        };
+        // (2,2)、(4,0)、(4,4)、(6,2) 共 4 个点组。
+        assert!(matches!(
+            enumerate_point_intersections_parallel(&segments, limits, 4),
+            Err(BoError::Limits(LimitExceeded {
+                kind: LimitKind::Intersections,
+                limit: 2,
+                ..
+            }))
+        ));
+    }
+}
@@ -0,0 +1,45 @@
+//! 点交结果的流式输出：扫描线在每个事件批处理结束时立即把点组交给 sink（按扫描顺序），
+//! sink 可随时要求提前结束扫描（例如只关心“是否存在交点”，或边扫描边写盘）。
+
+use core::ops::ControlFlow;
+
+use crate::geom::intersection::PointIntersectionGroupRecord;
+
+/// 点组接收者。
+///
+/// 说明：
+/// - 点组按扫描顺序到达（x 升序；同一 x 上事件点在前、垂直线段的批末查询在后）；
+/// - 每个几何点只到达一次，内容与收集为 `Vec` 时完全一致；
+/// - 返回 `ControlFlow::Break(())` 后扫描立即结束，不再调用 `accept`。
+pub trait IntersectionSink {
+    fn accept(&mut self, group: PointIntersectionGroupRecord) -> ControlFlow<()>;
+}
+
+/// 收集全部点组（与非流式入口的行为一致）。
+impl IntersectionSink for Vec<PointIntersectionGroupRecord> {
+    fn accept(&mut self, group: PointIntersectionGroupRecord) -> ControlFlow<()> {
+        self.push(group);
+        ControlFlow::Continue(())
+    }
+}
+
+/// 闭包适配器：`FnSink(|group| { ...; ControlFlow::Continue(()) })`。
+pub struct FnSink<F>(pub F);
+
+impl<F> IntersectionSink for FnSink<F>
+where
+    F: FnMut(PointIntersectionGroupRecord) -> ControlFlow<()>,
+{
+    fn accept(&mut self, group: PointIntersectionGroupRecord) -> ControlFlow<()> {
+        (self.0)(group)
+    }
+}
+
+/// 流式扫描的结束方式。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SweepEnd {
+    /// 扫描完整结束：sink 已收到全部点组。
+    Completed,
+    /// sink 要求提前结束：之后的点组未计算。
+    Stopped,
+}
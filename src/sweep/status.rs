@@ -54,6 +54,13 @@ pub trait SweepStatus {
         y_min: Rational,
     ) -> Result<Option<SegmentId>, SweepStatusError>;
 
+    /// `sweep_x` 处 y 严格小于 `y_max` 的最后一条线段（即该点正下方最近的线段）。
+    fn last_below_y(
+        &self,
+        segments: &Segments,
+        y_max: Rational,
+    ) -> Result<Option<SegmentId>, SweepStatusError>;
+
     fn range_by_y(
         &self,
         segments: &Segments,
@@ -208,6 +215,15 @@ impl SweepStatus for VecSweepStatus {
         Ok(self.active.get(index).copied())
     }
 
+    fn last_below_y(
+        &self,
+        segments: &Segments,
+        y_max: Rational,
+    ) -> Result<Option<SegmentId>, SweepStatusError> {
+        let index = self.lower_bound_index_by_y(segments, y_max)?;
+        Ok(index.checked_sub(1).map(|i| self.active[i]))
+    }
+
     fn range_by_y(
         &self,
         segments: &Segments,
@@ -594,6 +610,27 @@ impl SweepStatus for TreapSweepStatus {
         Ok(candidate)
     }
 
+    fn last_below_y(
+        &self,
+        segments: &Segments,
+        y_max: Rational,
+    ) -> Result<Option<SegmentId>, SweepStatusError> {
+        let mut current = self.root;
+        let mut candidate = None;
+
+        while let Some(id) = current {
+            let y = y_at_x(segments.get(id), self.sweep_x)?;
+            if y < y_max {
+                candidate = Some(id);
+                current = self.nodes[id.0].right;
+            } else {
+                current = self.nodes[id.0].left;
+            }
+        }
+
+        Ok(candidate)
+    }
+
     fn snapshot_order(&self) -> Vec<SegmentId> {
         let mut out = Vec::with_capacity(self.len);
         self.inorder_collect(&mut out);
@@ -783,6 +820,18 @@ mod tests {
             .range_by_y(&segments, Rational::from_int(9), Rational::from_int(11))
             .unwrap();
         assert_eq!(ids, vec![s2, s3]);
+        assert_eq!(
+            status.last_below_y(&segments, Rational::from_int(10)),
+            Ok(Some(s1))
+        );
+        assert_eq!(
+            status.last_below_y(&segments, Rational::from_int(11)),
+            Ok(Some(s3))
+        );
+        assert_eq!(
+            status.last_below_y(&segments, Rational::from_int(0)),
+            Ok(None)
+        );
     }
 
     #[test]
@@ -876,6 +925,18 @@ mod tests {
             .range_by_y(&segments, Rational::from_int(9), Rational::from_int(11))
             .unwrap();
         assert_eq!(ids, vec![s2, s3]);
+        assert_eq!(
+            status.last_below_y(&segments, Rational::from_int(10)),
+            Ok(Some(s1))
+        );
+        assert_eq!(
+            status.last_below_y(&segments, Rational::from_int(11)),
+            Ok(Some(s3))
+        );
+        assert_eq!(
+            status.last_below_y(&segments, Rational::from_int(0)),
+            Ok(None)
+        );
 
         status.remove(s2).unwrap();
         assert_eq!(status.snapshot_order(), vec![s1, s3]);
@@ -3,8 +3,11 @@ use core::fmt;
 use crate::geom::intersection::PointIntersectionGroupRecord;
 use crate::geom::point::PointRat;
 use crate::geom::segment::SegmentId;
+use crate::json::{JsonError, JsonNode, parse_json};
 use crate::rational::Rational;
 
+pub const TRACE_SCHEMA: &str = "trace.v2";
+
 #[derive(Clone, Debug, Default)]
 pub struct Trace {
     pub warnings: Vec<String>,
@@ -19,6 +22,16 @@ pub enum TraceStepKind {
     VerticalFlush,
 }
 
+impl TraceStepKind {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "PointBatch" => Some(TraceStepKind::PointBatch),
+            "VerticalFlush" => Some(TraceStepKind::VerticalFlush),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for TraceStepKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -71,11 +84,145 @@ impl Trace {
         write_trace_json(self, &mut out);
         out
     }
+
+    /// 从 `trace.v2` JSON 读回 `Trace`（`to_json_string` 的逆操作）。
+    ///
+    /// 说明：schema 不是 `trace.v2` 时报错；错误包含行/列与字段路径。
+    pub fn from_json_str(json: &str) -> Result<Self, JsonError> {
+        let root = parse_json(json)?;
+        read_trace_json(&root, "$")
+    }
+}
+
+pub(crate) fn read_trace_json(node: &JsonNode, path: &str) -> Result<Trace, JsonError> {
+    let schema_path = format!("{path}.schema");
+    let schema = node.get("schema", path)?;
+    let name = schema.as_str(&schema_path)?;
+    if name != TRACE_SCHEMA {
+        return Err(schema.error(
+            &schema_path,
+            format!("不支持的 schema：{name}（期望 {TRACE_SCHEMA}）"),
+        ));
+    }
+
+    let warnings = read_kv_string_array(node, "warnings", path)?;
+    let steps_path = format!("{path}.steps");
+    let steps = node
+        .get("steps", path)?
+        .as_array(&steps_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, step)| read_step_json(step, &format!("{steps_path}[{i}]")))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Trace { warnings, steps })
+}
+
+fn read_step_json(node: &JsonNode, path: &str) -> Result<TraceStep, JsonError> {
+    let kind_path = format!("{path}.kind");
+    let kind_node = node.get("kind", path)?;
+    let kind_name = kind_node.as_str(&kind_path)?;
+    let kind = TraceStepKind::from_name(kind_name)
+        .ok_or_else(|| kind_node.error(&kind_path, format!("未知的 step kind：{kind_name}")))?;
+
+    let sweep_x_path = format!("{path}.sweep_x");
+    let sweep_x = read_rational(node.get("sweep_x", path)?, &sweep_x_path)?;
+
+    let point_path = format!("{path}.point");
+    let point_node = node.get("point", path)?;
+    let point = if point_node.is_null() {
+        None
+    } else {
+        Some(read_point(point_node, &point_path)?)
+    };
+
+    let intersections_path = format!("{path}.intersections");
+    let intersections = node
+        .get("intersections", path)?
+        .as_array(&intersections_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, it)| read_intersection(it, &format!("{intersections_path}[{i}]")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(TraceStep {
+        kind,
+        sweep_x,
+        point,
+        events: read_kv_string_array(node, "events", path)?,
+        active: read_kv_segment_id_array(node, "active", path)?,
+        intersections,
+        notes: read_kv_string_array(node, "notes", path)?,
+    })
+}
+
+fn read_intersection(
+    node: &JsonNode,
+    path: &str,
+) -> Result<PointIntersectionGroupRecord, JsonError> {
+    Ok(PointIntersectionGroupRecord {
+        point: read_point(node.get("point", path)?, &format!("{path}.point"))?,
+        endpoint_segments: read_kv_segment_id_array(node, "endpoint_segments", path)?,
+        interior_segments: read_kv_segment_id_array(node, "interior_segments", path)?,
+    })
+}
+
+fn read_kv_string_array(node: &JsonNode, key: &str, path: &str) -> Result<Vec<String>, JsonError> {
+    let field_path = format!("{path}.{key}");
+    node.get(key, path)?
+        .as_array(&field_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            item.as_str(&format!("{field_path}[{i}]"))
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+fn read_kv_segment_id_array(
+    node: &JsonNode,
+    key: &str,
+    path: &str,
+) -> Result<Vec<SegmentId>, JsonError> {
+    let field_path = format!("{path}.{key}");
+    node.get(key, path)?
+        .as_array(&field_path)?
+        .iter()
+        .enumerate()
+        .map(|(i, item)| item.as_usize(&format!("{field_path}[{i}]")).map(SegmentId))
+        .collect()
+}
+
+fn read_point(node: &JsonNode, path: &str) -> Result<PointRat, JsonError> {
+    Ok(PointRat {
+        x: read_rational(node.get("x", path)?, &format!("{path}.x"))?,
+        y: read_rational(node.get("y", path)?, &format!("{path}.y"))?,
+    })
+}
+
+fn read_rational(node: &JsonNode, path: &str) -> Result<Rational, JsonError> {
+    let num_path = format!("{path}.num");
+    let num_node = node.get("num", path)?;
+    let num = num_node.as_i128_text(&num_path)?;
+    // 写出端总是输出约分后、分母为正的形式；这里拒绝负分母与 `i128::MIN`，避免规范化时取负溢出。
+    if num == i128::MIN {
+        return Err(num_node.error(&num_path, "分子超出范围"));
+    }
+    let den_path = format!("{path}.den");
+    let den_node = node.get("den", path)?;
+    let den = den_node.as_i128_text(&den_path)?;
+    if den == 0 {
+        return Err(den_node.error(&den_path, "分母不能为 0"));
+    }
+    if den < 0 {
+        return Err(den_node.error(&den_path, "分母必须为正"));
+    }
+    Ok(Rational::new(num, den))
 }
 
 fn write_trace_json(trace: &Trace, out: &mut String) {
     out.push('{');
-    write_kv_str(out, "schema", "trace.v2");
+    write_kv_str(out, "schema", TRACE_SCHEMA);
     out.push(',');
     write_kv_string_array(out, "warnings", &trace.warnings);
     out.push(',');
@@ -134,6 +281,13 @@ fn write_kv_intersections(out: &mut String, key: &str, value: &[PointIntersectio
     out.push(']');
 }
 
+/// 单条按点聚合交点记录的 JSON（与 `trace.v2` 中 `intersections[]` 的元素格式一致）。
+pub fn intersection_to_json_string(it: &PointIntersectionGroupRecord) -> String {
+    let mut out = String::new();
+    write_intersection(&mut out, it);
+    out
+}
+
 fn write_intersection(out: &mut String, it: &PointIntersectionGroupRecord) {
     out.push('{');
     out.push('"');
@@ -194,16 +348,16 @@ fn write_kv_str(out: &mut String, key: &str, value: &str) {
     write_json_string(out, value);
 }
 
-fn write_point(out: &mut String, p: PointRat) {
+pub(crate) fn write_point(out: &mut String, p: PointRat) {
     out.push('{');
     out.push('"');
-    out.push_str("x");
+    out.push('x');
     out.push('"');
     out.push(':');
     write_rational(out, p.x);
     out.push(',');
     out.push('"');
-    out.push_str("y");
+    out.push('y');
     out.push('"');
     out.push(':');
     write_rational(out, p.y);
@@ -276,5 +430,42 @@ mod tests {
             json,
             "{\"schema\":\"trace.v2\",\"warnings\":[],\"steps\":[{\"kind\":\"PointBatch\",\"sweep_x\":{\"num\":\"5\",\"den\":\"1\"},\"point\":{\"x\":{\"num\":\"5\",\"den\":\"1\"},\"y\":{\"num\":\"-2\",\"den\":\"1\"}},\"events\":[\"SegmentStart(1)\"],\"active\":[1,3],\"intersections\":[{\"point\":{\"x\":{\"num\":\"5\",\"den\":\"1\"},\"y\":{\"num\":\"-2\",\"den\":\"1\"}},\"endpoint_segments\":[1],\"interior_segments\":[3]}],\"notes\":[\"包含引号: \\\" 和换行\\n\"]}]}"
         );
+
+        // 读回后再写出应逐字节一致。
+        let read = Trace::from_json_str(&json).unwrap();
+        assert_eq!(read.steps[0].kind, TraceStepKind::PointBatch);
+        assert_eq!(read.steps[0].notes, trace.steps[0].notes);
+        assert_eq!(read.to_json_string(), json);
+    }
+
+    #[test]
+    fn rejects_denominators_that_cannot_be_normalized() {
+        let step = |x: &str| {
+            format!(
+                "{{\"schema\":\"trace.v2\",\"warnings\":[],\"steps\":[{{\"kind\":\"VerticalFlush\",\"sweep_x\":{x},\"point\":null,\"events\":[],\"active\":[],\"intersections\":[],\"notes\":[]}}]}}"
+            )
+        };
+        let cases = [
+            (
+                r#"{"num":"-170141183460469231731687303715884105728","den":"-1"}"#,
+                "$.steps[0].sweep_x.num",
+            ),
+            (
+                r#"{"num":"1","den":"-170141183460469231731687303715884105728"}"#,
+                "$.steps[0].sweep_x.den",
+            ),
+            (r#"{"num":"1","den":"-2"}"#, "$.steps[0].sweep_x.den"),
+        ];
+        for (x, path) in cases {
+            let err = Trace::from_json_str(&step(x)).unwrap_err();
+            assert_eq!(err.path, path, "{x}");
+        }
+        assert_eq!(
+            Trace::from_json_str(&step(r#"{"num":"-6","den":"4"}"#))
+                .unwrap()
+                .steps[0]
+                .sweep_x,
+            Rational::new(-3, 2)
+        );
     }
 }